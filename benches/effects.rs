@@ -31,6 +31,96 @@ impl AudioSource for DummySource {
     }
 }
 
+/// Plays a constant signal for a while, then silence forever.
+struct Burst {
+    remaining: usize,
+}
+
+impl AudioSource for Burst {
+    fn read(&mut self, buffer: &mut [Sample]) -> timbre::ReadResult {
+        for sample in buffer.iter_mut() {
+            *sample = if self.remaining > 0 { 0.5 } else { 0.0 };
+            self.remaining = self.remaining.saturating_sub(1);
+        }
+        timbre::ReadResult::good(buffer.len())
+    }
+
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            sample_rate: SAMPLE_RATE as u32,
+            channels: CHANNELS as u8,
+        }
+    }
+}
+
+/// A one-pole low-pass filter like `LowPass`, but without the denormal guard.
+struct UnguardedLowPass {
+    source: Burst,
+    state: [f32; CHANNELS],
+}
+
+impl AudioSource for UnguardedLowPass {
+    fn read(&mut self, buffer: &mut [Sample]) -> timbre::ReadResult {
+        let result = self.source.read(buffer);
+        let dt = 1.0 / SAMPLE_RATE as f32;
+        let a = dt / (1.0 / (2.0 * std::f32::consts::PI * 1000.0) + dt);
+        for frame in buffer.chunks_exact_mut(CHANNELS) {
+            for (sample, previous) in frame.iter_mut().zip(self.state.iter_mut()) {
+                *previous = *previous + a * (*sample - *previous);
+                *sample = *previous;
+            }
+        }
+        result
+    }
+
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+}
+
+/// Read a second of signal followed by several seconds of silence, by which
+/// point any feedback state has decayed into the denormal range.
+fn decay_to_silence(source: &mut dyn AudioSource) {
+    let mut samples = vec![0.0; WINDOW_SIZE * CHANNELS];
+    for _ in 0..(5 * SAMPLE_RATE / WINDOW_SIZE) {
+        source.read(&mut samples);
+    }
+}
+
+fn bench_denormals(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Denormals");
+    let burst = || Burst {
+        remaining: SAMPLE_RATE * CHANNELS,
+    };
+    let sources: Vec<(&str, Box<dyn AudioSource>)> = vec![
+        (
+            "Echo",
+            Box::new(Echo::new(burst(), Duration::from_millis(1), 0.5)),
+        ),
+        ("LowPass", Box::new(LowPass::new(burst(), 1000.0))),
+        ("HighPass", Box::new(HighPass::new(burst(), 1000.0))),
+        (
+            "UnguardedLowPass",
+            Box::new(UnguardedLowPass {
+                source: burst(),
+                state: [0.0; CHANNELS],
+            }),
+        ),
+    ];
+
+    // Compare against `UnguardedLowPass` to see the cost the guard avoids.
+    for (name, mut source) in sources {
+        decay_to_silence(source.as_mut());
+        group.bench_function(BenchmarkId::new("silence after signal", name), |b| {
+            let mut samples = vec![0.0; WINDOW_SIZE * CHANNELS];
+            b.iter(|| {
+                source.read(&mut samples);
+            });
+            black_box(samples);
+        });
+    }
+}
+
 fn bench_echo(c: &mut Criterion) {
     let mut group = c.benchmark_group("Echo");
     for delay in [0.1, 0.5, 1.0, 2.0, 4.0].iter() {
@@ -148,12 +238,48 @@ fn bench_basicmixer(c: &mut Criterion) {
     }
 }
 
+/// Compares reading many sources that each do real work serially and in parallel.
+#[cfg(feature = "rayon")]
+fn bench_basicmixer_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BasicMixerParallel");
+    for sources in [4, 16, 64].iter() {
+        for &parallel in [false, true].iter() {
+            let name = if parallel { "parallel" } else { "serial" };
+            group.bench_with_input(BenchmarkId::new(name, sources), sources, |b, &sources| {
+                let mut samples = vec![0.0; WINDOW_SIZE * CHANNELS];
+
+                let mut basic_mixer = BasicMixer::new();
+                basic_mixer.set_parallel(parallel);
+                for _ in 0..sources {
+                    let source = DummySource {
+                        number: black_box(0.5),
+                    };
+                    let source = LowPass::new(source, 1000.0);
+                    let source = HighPass::new(source, 100.0);
+                    let source = Echo::new(source, Duration::from_secs_f32(0.05), 0.5);
+                    basic_mixer.add_source(source.into_shared());
+                }
+
+                b.iter(|| {
+                    basic_mixer.read(&mut samples);
+                });
+                black_box(samples);
+            });
+        }
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+fn bench_basicmixer_parallel(_: &mut Criterion) {}
+
 criterion_group!(
     benches,
     bench_echo,
     bench_highpass,
     bench_lowpass,
     bench_basicmixer,
-    bench_composite
+    bench_composite,
+    bench_denormals,
+    bench_basicmixer_parallel
 );
 criterion_main!(benches);
@@ -1,4 +1,13 @@
-use std::sync::{Arc, Mutex};
+use crate::{effects::smoothed::Smoothed, Error};
+
+use std::{
+    convert::TryFrom,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 /// Used to know how to interpret audio data.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -31,6 +40,84 @@ impl AudioFormat {
         channels: 2,
         sample_rate: 48000,
     };
+
+    /// Returns the number of samples in `frames` frames, one per channel in each frame.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::AudioFormat;
+    /// assert_eq!(AudioFormat::STEREO_CD.frames_to_samples(1024), 2048);
+    /// assert_eq!(AudioFormat::MONO_CD.frames_to_samples(1024), 1024);
+    /// ```
+    pub fn frames_to_samples(&self, frames: usize) -> usize {
+        frames * self.channels as usize
+    }
+
+    /// Returns the number of whole frames in `samples` samples.
+    ///
+    /// A partial frame at the end is not counted.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::AudioFormat;
+    /// assert_eq!(AudioFormat::STEREO_CD.samples_to_frames(2048), 1024);
+    /// assert_eq!(AudioFormat::STEREO_CD.samples_to_frames(2049), 1024);
+    /// ```
+    pub fn samples_to_frames(&self, samples: usize) -> usize {
+        samples / self.channels as usize
+    }
+
+    /// Returns the number of frames that play in `duration`, rounded to the nearest frame.
+    ///
+    /// The arithmetic is done on integers, so it's exact however long the duration is.
+    /// Durations too long to count in a `u64` saturate to `u64::MAX`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::AudioFormat;
+    /// # use std::time::Duration;
+    /// let format = AudioFormat::STEREO_CD;
+    /// assert_eq!(format.duration_to_frames(Duration::from_millis(500)), 22050);
+    /// assert_eq!(format.duration_to_frames(Duration::from_micros(10)), 0);
+    /// assert_eq!(format.duration_to_frames(Duration::from_micros(12)), 1);
+    ///
+    /// // A year of audio is still exact to the frame.
+    /// let year = Duration::from_secs(365 * 24 * 60 * 60);
+    /// assert_eq!(format.duration_to_frames(year), 365 * 24 * 60 * 60 * 44100);
+    /// assert_eq!(format.duration_to_frames(year + Duration::from_nanos(22676)), 365 * 24 * 60 * 60 * 44100 + 1);
+    ///
+    /// assert_eq!(format.duration_to_frames(Duration::MAX), u64::MAX);
+    /// ```
+    pub fn duration_to_frames(&self, duration: Duration) -> u64 {
+        let rate = self.sample_rate as u128;
+        let nanos = duration.as_nanos() * rate;
+        u64::try_from((nanos + 500_000_000) / 1_000_000_000).unwrap_or(u64::MAX)
+    }
+
+    /// Returns how long `frames` frames take to play, rounded to the nearest nanosecond.
+    ///
+    /// # Panics
+    ///
+    /// If the sample rate is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::AudioFormat;
+    /// # use std::time::Duration;
+    /// let format = AudioFormat::MONO_DVD;
+    /// assert_eq!(format.frames_to_duration(48000), Duration::from_secs(1));
+    /// assert_eq!(format.frames_to_duration(24), Duration::from_micros(500));
+    ///
+    /// // Converting back gives the same number of frames, even for very long streams.
+    /// let frames = u64::MAX / 2;
+    /// assert_eq!(format.duration_to_frames(format.frames_to_duration(frames)), frames);
+    /// ```
+    pub fn frames_to_duration(&self, frames: u64) -> Duration {
+        let rate = self.sample_rate as u64;
+        let seconds = frames / rate;
+        let nanos = ((frames % rate) as u128 * 1_000_000_000 + rate as u128 / 2) / rate as u128;
+        Duration::from_secs(seconds) + Duration::from_nanos(nanos as u64)
+    }
 }
 
 impl Default for AudioFormat {
@@ -41,6 +128,156 @@ impl Default for AudioFormat {
     }
 }
 
+/// The position of the speaker that a channel is meant to be played on.
+///
+/// The positions, and their order in [`ChannelLayout::from_mask`], follow the
+/// speaker positions of the WAV `WAVEFORMATEXTENSIBLE` channel mask.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Speaker {
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    LowFrequency,
+    BackLeft,
+    BackRight,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    BackCenter,
+    SideLeft,
+    SideRight,
+    /// A channel with no known position, e.g. an overhead speaker or an
+    /// unlabeled channel of a multitrack recording.
+    Other,
+}
+
+/// Describes which speaker each channel of a stream is meant for, so that
+/// downmixing effects like [`ToMono`](crate::effects::ToMono) and
+/// [`Downmix`](crate::effects::Downmix) know which channels are center, LFE,
+/// or surrounds.
+///
+/// # Examples
+/// ```
+/// # use timbre::{ChannelLayout, Speaker};
+/// // Channel counts map to the usual WAV layouts.
+/// let surround = ChannelLayout::from_channels(6);
+/// assert_eq!(surround.speakers()[2], Speaker::FrontCenter);
+/// assert_eq!(surround.speakers()[3], Speaker::LowFrequency);
+///
+/// // A WAV channel mask lists the speakers that are present, in order.
+/// let layout = ChannelLayout::from_mask(0x1 | 0x2 | 0x4, 3);
+/// assert_eq!(layout.speakers(), [Speaker::FrontLeft, Speaker::FrontRight, Speaker::FrontCenter]);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ChannelLayout {
+    speakers: Vec<Speaker>,
+}
+
+impl ChannelLayout {
+    /// The speakers of the `WAVEFORMATEXTENSIBLE` channel mask, in bit order.
+    const MASK_SPEAKERS: [Speaker; 11] = [
+        Speaker::FrontLeft,
+        Speaker::FrontRight,
+        Speaker::FrontCenter,
+        Speaker::LowFrequency,
+        Speaker::BackLeft,
+        Speaker::BackRight,
+        Speaker::FrontLeftOfCenter,
+        Speaker::FrontRightOfCenter,
+        Speaker::BackCenter,
+        Speaker::SideLeft,
+        Speaker::SideRight,
+    ];
+
+    /// Construct a layout from the speaker of each channel, in channel order.
+    pub fn new(speakers: Vec<Speaker>) -> Self {
+        ChannelLayout { speakers }
+    }
+
+    /// Construct the default layout for a number of channels.
+    ///
+    /// | Channels | Layout |
+    /// |----------|--------|
+    /// | 1 | Center |
+    /// | 2 | Left, right |
+    /// | 3 | Left, right, center |
+    /// | 4 | Left, right, back left, back right |
+    /// | 5 | 5.0: left, right, center, back left, back right |
+    /// | 6 | 5.1: left, right, center, LFE, back left, back right |
+    /// | 8 | 7.1: left, right, center, LFE, back left, back right, side left, side right |
+    ///
+    /// Any other number of channels has no known positions.
+    pub fn from_channels(channels: u8) -> Self {
+        use Speaker::*;
+        let speakers = match channels {
+            1 => vec![FrontCenter],
+            2 => vec![FrontLeft, FrontRight],
+            3 => vec![FrontLeft, FrontRight, FrontCenter],
+            4 => vec![FrontLeft, FrontRight, BackLeft, BackRight],
+            5 => vec![FrontLeft, FrontRight, FrontCenter, BackLeft, BackRight],
+            6 => vec![
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                BackLeft,
+                BackRight,
+            ],
+            8 => vec![
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                BackLeft,
+                BackRight,
+                SideLeft,
+                SideRight,
+            ],
+            _ => vec![Other; channels as usize],
+        };
+        ChannelLayout { speakers }
+    }
+
+    /// Construct a layout from a WAV `WAVEFORMATEXTENSIBLE` channel mask, as
+    /// returned by [`WavDecoder::channel_mask`](crate::decoders::WavDecoder::channel_mask).
+    ///
+    /// Channels are assigned to the speakers in the mask in bit order. Channels
+    /// beyond the speakers in the mask, or assigned to positions timbre doesn't
+    /// know, are [`Speaker::Other`].
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` -- The channel mask, with bit 0 for front left, bit 1 for front right, and so on.
+    /// * `channels` -- The number of channels in the stream.
+    pub fn from_mask(mask: u32, channels: u8) -> Self {
+        let speakers = (0..32)
+            .filter(|bit| mask & (1 << bit) != 0)
+            .map(|bit| {
+                Self::MASK_SPEAKERS
+                    .get(bit)
+                    .copied()
+                    .unwrap_or(Speaker::Other)
+            })
+            .chain(std::iter::repeat(Speaker::Other))
+            .take(channels as usize)
+            .collect();
+        ChannelLayout { speakers }
+    }
+
+    /// Returns the speaker of each channel, in channel order.
+    pub fn speakers(&self) -> &[Speaker] {
+        &self.speakers
+    }
+
+    pub fn channels(&self) -> usize {
+        self.speakers.len()
+    }
+
+    /// Returns true if every channel has a known speaker position.
+    pub fn is_positioned(&self) -> bool {
+        !self.speakers.contains(&Speaker::Other)
+    }
+}
+
 /// Indicates the state of an [`AudioSource`](crate::AudioSource).
 #[derive(Debug, Eq, PartialEq)]
 pub enum StreamState {
@@ -82,8 +319,147 @@ impl ReadResult {
     }
 }
 
+/// Indicates the state of an [`AudioSink`](crate::AudioSink).
+#[derive(Debug, Eq, PartialEq)]
+pub enum SinkState {
+    /// The sink accepted all of the data.
+    Good,
+    /// The sink couldn't accept all of the data, but may accept more later.
+    Full,
+    /// The sink won't accept any more data, e.g. because its receiver is gone.
+    Closed,
+}
+
+/// Indicates the amount of data written and the status of an [`AudioSink`](crate::AudioSink).
+#[derive(Debug, Eq, PartialEq)]
+pub struct WriteResult {
+    pub state: SinkState,
+    pub written: usize,
+}
+
+impl WriteResult {
+    pub fn good(written: usize) -> Self {
+        WriteResult {
+            state: SinkState::Good,
+            written,
+        }
+    }
+
+    pub fn full(written: usize) -> Self {
+        WriteResult {
+            state: SinkState::Full,
+            written,
+        }
+    }
+
+    pub fn closed(written: usize) -> Self {
+        WriteResult {
+            state: SinkState::Closed,
+            written,
+        }
+    }
+}
+
 pub type Sample = f32;
 
+/// Convert a gain in decibels to a linear multiplier.
+///
+/// Negative infinity is silence, and returns exactly 0.0, as do gains so small
+/// they can't be represented as a normal `f32`.
+///
+/// # Examples
+/// ```
+/// # use timbre::db_to_linear;
+/// assert!((db_to_linear(-6.0) - 0.501).abs() < 0.001);
+/// assert_eq!(db_to_linear(0.0), 1.0);
+/// assert_eq!(db_to_linear(f32::NEG_INFINITY), 0.0);
+/// ```
+pub fn db_to_linear(db: f32) -> f32 {
+    let linear = 10.0f32.powf(db / 20.0);
+    if linear < f32::MIN_POSITIVE {
+        0.0
+    } else {
+        linear
+    }
+}
+
+/// Convert a linear multiplier to a gain in decibels.
+///
+/// The sign of `linear` is ignored, and 0.0 returns negative infinity.
+///
+/// # Examples
+/// ```
+/// # use timbre::{db_to_linear, linear_to_db};
+/// assert!((linear_to_db(0.5) + 6.02).abs() < 0.01);
+/// assert_eq!(linear_to_db(0.0), f32::NEG_INFINITY);
+///
+/// for &db in [-60.0, -12.0, -6.0, 0.0, 6.0].iter() {
+///     assert!((linear_to_db(db_to_linear(db)) - db).abs() < 1e-4);
+/// }
+/// ```
+pub fn linear_to_db(linear: f32) -> f32 {
+    let linear = linear.abs();
+    if linear == 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        20.0 * linear.log10()
+    }
+}
+
+/// Convert a MIDI note number to its frequency in Hz.
+///
+/// Notes are in equal temperament, with A4 (note 69) at 440 Hz. Fractional
+/// notes are allowed, e.g. for pitch bends.
+///
+/// # Examples
+/// ```
+/// # use timbre::midi_to_frequency;
+/// assert_eq!(midi_to_frequency(69.0), 440.0);
+/// assert_eq!(midi_to_frequency(81.0), 880.0);
+/// assert!((midi_to_frequency(60.0) - 261.63).abs() < 0.01);
+/// ```
+pub fn midi_to_frequency(note: f32) -> f32 {
+    440.0 * 2.0f32.powf((note - 69.0) / 12.0)
+}
+
+/// The frequencies that normalized cutoffs of 0.0 and 1.0 map to: the range of human hearing.
+const NORMALIZED_FREQUENCY_RANGE: (f32, f32) = (20.0, 20000.0);
+
+/// Map `normalized`, clamped to [0.0, 1.0], logarithmically onto 20 Hz to 20 kHz.
+///
+/// Equal steps in `normalized` are equal musical intervals, which suits a
+/// slider or knob better than a linear mapping.
+pub(crate) fn normalized_frequency(normalized: f32) -> f32 {
+    let (min, max) = NORMALIZED_FREQUENCY_RANGE;
+    min * (max / min).powf(normalized.clamp(0.0, 1.0))
+}
+
+/// Flush a denormal (subnormal) float to zero.
+///
+/// When the input to a feedback loop goes quiet, such as the delay line of an
+/// [`Echo`](crate::effects::Echo) or the state of an IIR filter, the loop's
+/// state decays toward zero and eventually becomes denormal. Arithmetic on
+/// denormals is many times slower on many CPUs, so a graph can suddenly use far
+/// more CPU once it falls silent. Effects pass their feedback state through
+/// this to skip that range.
+///
+/// # Examples
+/// ```
+/// # use timbre::flush_denormal;
+/// assert_eq!(flush_denormal(1e-40), 0.0);
+/// assert_eq!(flush_denormal(-1e-40), 0.0);
+/// assert_eq!(flush_denormal(1e-30), 1e-30);
+/// assert_eq!(flush_denormal(-0.5), -0.5);
+/// ```
+#[inline]
+pub fn flush_denormal(value: f32) -> f32 {
+    if value.abs() < f32::MIN_POSITIVE {
+        0.0
+    } else {
+        value
+    }
+}
+
 /// Trait implemented to provide audio data to consumers.
 ///
 /// This is the center of this entire library. Almost everything
@@ -108,10 +484,550 @@ pub trait AudioSource {
     ///
     /// May panic if `buffer.len()` is not a multiple of `format().channels`.
     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult;
+
+    /// Turn this source into an iterator over blocks of samples.
+    ///
+    /// Each item holds the samples from one read of up to `block_frames`
+    /// frames. The final block of a finite source may be shorter, and the
+    /// iterator ends once the source is [`Finished`](crate::StreamState::Finished).
+    /// If the source underruns, the block holds whatever it provided, which
+    /// may be nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_frames` -- The number of frames to read for each block.
+    ///
+    /// # Panics
+    ///
+    /// If `block_frames` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::Ramp, AudioFormat, AudioSource};
+    /// # use std::time::Duration;
+    /// // 4410 stereo frames, which isn't a multiple of the block size.
+    /// let ramp = Ramp::finite(0.0, 1.0, Duration::from_millis(100), AudioFormat::STEREO_CD);
+    ///
+    /// let mut total = 0;
+    /// let mut blocks = 0;
+    /// for block in ramp.frames(1024) {
+    ///     total += block.len();
+    ///     blocks += 1;
+    /// }
+    /// assert_eq!(total, 4410 * 2);
+    /// assert_eq!(blocks, 5);
+    /// ```
+    fn frames(self, block_frames: usize) -> Frames<Self>
+    where
+        Self: Sized,
+    {
+        assert!(block_frames > 0, "Blocks must contain at least one frame.");
+        Frames {
+            buffer: vec![0.0; self.format().frames_to_samples(block_frames)],
+            source: self,
+            finished: false,
+        }
+    }
+}
+
+/// Trait implemented to consume audio data pushed by a producer.
+///
+/// This is the counterpart to [`AudioSource`](crate::AudioSource), for
+/// integrations where audio is pushed rather than pulled, such as encoding
+/// to a file or handing audio to another thread. [`pump`](crate::pump)
+/// connects a source to a sink.
+pub trait AudioSink {
+    /// Returns the format this sink expects.
+    fn format(&self) -> AudioFormat;
+
+    /// Consume as much of the given buffer of interleaved samples as possible.
+    ///
+    /// # Returns
+    ///
+    /// A [`WriteResult`](crate::WriteResult) indicating how much data was
+    /// consumed and the state of the sink.
+    ///
+    /// # Panics
+    ///
+    /// May panic if `buffer.len()` is not a multiple of `format().channels`.
+    fn write(&mut self, buffer: &[Sample]) -> WriteResult;
+}
+
+/// Trait implemented by sources that can restart from the beginning.
+///
+/// Effects implement this whenever their inner source does, clearing their
+/// own internal state so the restarted stream sounds like the original.
+pub trait Rewind {
+    /// Restart this source from the beginning.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the source was rewound, `false` if it could not be.
+    fn rewind(&mut self) -> bool;
+}
+
+/// Trait implemented by sources that can jump to an arbitrary position.
+pub trait Seek {
+    /// Move the read position of this source to the given frame.
+    ///
+    /// A frame is one sample for each channel, so seeking is independent
+    /// of the channel count.
+    ///
+    /// # Returns
+    ///
+    /// The frame actually seeked to, which may be less than `frame` if
+    /// the source is shorter than requested.
+    fn seek_frame(&mut self, frame: u64) -> u64;
+}
+
+/// Trait implemented by sources that can jump to a point in time, such as
+/// decoders, for scrubbing with the transport controls of a player.
+///
+/// Unlike [`Seek`](crate::Seek), which effects pass through to their source,
+/// this works in time rather than frames and reports the current position.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use timbre::{decoders::WavDecoder, AudioSource, Seekable, StreamState};
+/// use std::time::Duration;
+///
+/// let mut decoder = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+/// decoder.seek(Duration::from_millis(50))?;
+/// assert_eq!(decoder.position(), Duration::from_millis(50));
+///
+/// // Seeking past the end clamps to the end.
+/// decoder.seek(Duration::from_secs(10))?;
+/// assert_eq!(decoder.position(), Duration::from_millis(100));
+/// assert_eq!(decoder.read(&mut [0.0; 16]).state, StreamState::Finished);
+///
+/// // Seeking back to zero plays from the start again.
+/// decoder.seek(Duration::ZERO)?;
+/// assert_eq!(decoder.read(&mut [0.0; 16]).state, StreamState::Good);
+/// # Ok(())
+/// # }
+/// ```
+pub trait Seekable {
+    /// Move the read position of this source to `position`.
+    ///
+    /// Positions past the end clamp to the end, so that the next read is
+    /// [`Finished`](crate::StreamState::Finished).
+    ///
+    /// # Errors
+    ///
+    /// If the source can't move to the position, e.g. because its stream
+    /// can't seek.
+    fn seek(&mut self, position: Duration) -> Result<(), Error>;
+
+    /// Returns the current read position, from the start of the source.
+    fn position(&self) -> Duration;
+}
+
+/// Trait implemented by sources to report the processing delay they introduce.
+///
+/// Effects report their own latency plus the latency of their source, so
+/// the outermost effect in a chain reports the total for the whole chain.
+/// This can be used to compensate when synchronizing audio with video, etc.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Echo, LowPass}, generators::SineWave, AudioFormat, AudioSource, Latency, ReadResult, Sample};
+/// # use std::time::Duration;
+/// // A stage that holds back 64 frames, e.g. for lookahead.
+/// struct Lookahead(SineWave);
+///
+/// impl AudioSource for Lookahead {
+///     fn format(&self) -> AudioFormat { self.0.format() }
+///     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult { self.0.read(buffer) }
+/// }
+///
+/// impl Latency for Lookahead {
+///     fn latency_frames(&self) -> u64 { 64 + self.0.latency_frames() }
+/// }
+///
+/// let source = Lookahead(SineWave::new(1.0, 440.0));
+/// let source = LowPass::new(source, 1000.0);
+/// let source = Echo::new(source, Duration::from_secs_f32(0.25), 0.5);
+/// assert_eq!(source.latency_frames(), 64);
+/// ```
+pub trait Latency {
+    /// Returns the number of frames by which this source delays its input.
+    fn latency_frames(&self) -> u64;
+}
+
+/// Trait implemented by effects whose output continues after their input stops.
+///
+/// Echoes and reverbs keep ringing after their source is
+/// [`Finished`](crate::StreamState::Finished). Such effects keep producing
+/// their tail when read again after reporting `Finished`, and report its
+/// length here so that offline rendering with
+/// [`render_with_tail`](crate::render_with_tail) knows how long to keep pulling.
+/// Sources without a tail implement this trait with the default of zero,
+/// effects add their own tail to their source's, and sources whose tail never
+/// dies away, such as an echo with no decay, report `u64::MAX`.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Echo, generators::SineWave, Tail};
+/// # use std::time::Duration;
+/// let echo = Echo::new(SineWave::new(1.0, 440.0), Duration::from_millis(100), 0.5);
+/// assert!(echo.tail_frames() > 0);
+/// ```
+pub trait Tail {
+    /// Returns the number of frames this source keeps producing after its input
+    /// finishes, or `u64::MAX` if it never stops.
+    fn tail_frames(&self) -> u64 {
+        0
+    }
+}
+
+/// A parameter that can be read by the audio thread and written by any other thread.
+///
+/// `Param` stores an `f32` in an atomic, so reading and writing it never
+/// blocks. Cloning a `Param` gives another handle to the same value, so an
+/// effect can hold one handle while a UI thread holds another.
+///
+/// # Examples
+/// ```
+/// # use timbre::Param;
+/// let param = Param::new(1.5);
+/// let handle = param.clone();
+///
+/// // Values written from one thread are never torn when read from another.
+/// let writer = std::thread::spawn(move || {
+///     for i in 0..10000 {
+///         handle.set(if i % 2 == 0 { -2.25e10 } else { 1.5 });
+///     }
+/// });
+/// for _ in 0..10000 {
+///     let value = param.get();
+///     assert!(value == 1.5 || value == -2.25e10);
+/// }
+/// writer.join().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Param {
+    value: Arc<AtomicU32>,
+}
+
+impl Param {
+    pub fn new(value: f32) -> Self {
+        Param {
+            value: Arc::new(AtomicU32::new(value.to_bits())),
+        }
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.value.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, value: f32) {
+        self.value.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Returns a reader that ramps to new values over a few milliseconds instead of jumping.
+    pub fn smoothed(&self) -> SmoothedParam {
+        SmoothedParam {
+            param: self.clone(),
+            smoothed: Smoothed::new(self.get()),
+        }
+    }
+}
+
+/// A reader for a [`Param`](crate::Param) that ramps linearly to new values.
+///
+/// Abruptly changing a parameter such as a gain makes the output jump, which
+/// is heard as a click. Effects that read their parameters through a
+/// `SmoothedParam` ramp to each new value over a few milliseconds instead.
+///
+/// # Examples
+/// ```
+/// # use timbre::Param;
+/// let param = Param::new(0.0);
+/// let mut smoothed = param.smoothed();
+///
+/// param.set(1.0);
+/// let (start, end) = smoothed.advance(0.001);
+/// assert_eq!(start, 0.0);
+/// assert!(end > 0.0 && end < 1.0);
+///
+/// smoothed.advance(1.0);
+/// assert_eq!(smoothed.value(), 1.0);
+/// ```
+pub struct SmoothedParam {
+    param: Param,
+    smoothed: Smoothed,
+}
+
+impl SmoothedParam {
+    /// Returns a handle to the underlying parameter.
+    pub fn param(&self) -> &Param {
+        &self.param
+    }
+
+    /// Returns the current value of the ramp.
+    pub fn value(&self) -> f32 {
+        self.smoothed.current()
+    }
+
+    /// Advance the ramp toward the parameter's value by `seconds`.
+    ///
+    /// # Returns
+    ///
+    /// The values at the start and end of the elapsed time.
+    pub fn advance(&mut self, seconds: f32) -> (f32, f32) {
+        let target = self.param.get();
+        if target != self.smoothed.target() {
+            self.smoothed.set(target);
+        }
+        self.smoothed.advance(seconds)
+    }
+
+    /// Jump to the parameter's value immediately.
+    pub fn finish(&mut self) {
+        self.smoothed.set(self.param.get());
+        self.smoothed.finish();
+    }
 }
 
 pub type SharedAudioSource = Arc<Mutex<dyn AudioSource + Send>>;
 
+const RENDER_BLOCK_FRAMES: usize = 1024;
+/// How long to wait before reading again when a source underruns without providing anything.
+const UNDERRUN_BACKOFF: Duration = Duration::from_millis(1);
+
+/// Read a finite source to completion and collect all of its samples.
+///
+/// Reads are made in fixed-size blocks until the source is
+/// [`Finished`](crate::StreamState::Finished). If the source underruns
+/// without providing anything, e.g. because it's fed from another thread,
+/// this waits briefly before reading again. If the source never finishes,
+/// this never returns; use [`render_frames`](crate::render_frames) instead.
+/// Effects that ring on after their source finishes, such as an echo, are cut
+/// off; use [`render_with_tail`](crate::render_with_tail) to include their tail.
+///
+/// # Returns
+///
+/// All samples read from the source, and the source's format.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::Ramp, render, AudioFormat};
+/// # use std::time::Duration;
+/// let ramp = Ramp::finite(0.0, 1.0, Duration::from_millis(100), AudioFormat::STEREO_CD);
+/// let (samples, format) = render(ramp);
+///
+/// assert_eq!(format, AudioFormat::STEREO_CD);
+/// assert_eq!(samples.len(), 4410 * 2);
+/// ```
+pub fn render<S: AudioSource>(source: S) -> (Vec<Sample>, AudioFormat) {
+    render_frames(source, u64::MAX)
+}
+
+/// Read a finite source to completion, including the tail that rings on after it finishes.
+///
+/// This is like [`render`](crate::render), except that once the source is
+/// [`Finished`](crate::StreamState::Finished), it keeps reading for
+/// [`tail_frames`](crate::Tail::tail_frames) more frames, so that e.g. the
+/// repetitions of an [`Echo`](crate::effects::Echo) aren't cut off.
+///
+/// # Returns
+///
+/// All samples read from the source and its tail, and the source's format.
+///
+/// # Panics
+///
+/// If the tail is infinite, i.e. [`tail_frames`](crate::Tail::tail_frames)
+/// is `u64::MAX`, since rendering it would never finish. Use
+/// [`render_frames`](crate::render_frames) to render part of it instead.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Echo, generators::VecSource, render, render_with_tail, AudioFormat};
+/// # use std::time::Duration;
+/// let echo = || {
+///     let click = VecSource::new(vec![1.0; 10], AudioFormat::MONO_CD);
+///     Echo::new(click, Duration::from_secs_f64(100.0 / 44100.0), 0.5)
+/// };
+///
+/// let (samples, _) = render(echo());
+/// assert_eq!(samples.len(), 10);
+///
+/// // Ten repetitions, until the echo has decayed below -60 dB.
+/// let (samples, _) = render_with_tail(echo());
+/// assert_eq!(samples.len(), 10 + 1000);
+/// assert_eq!(samples[100], 0.5);
+/// ```
+///
+/// An echo that never decays has no end to render:
+/// ```should_panic
+/// # use timbre::{effects::Echo, generators::VecSource, render_with_tail, AudioFormat};
+/// # use std::time::Duration;
+/// let click = VecSource::new(vec![1.0; 10], AudioFormat::MONO_CD);
+/// render_with_tail(Echo::new(click, Duration::from_millis(10), 1.0));
+/// ```
+pub fn render_with_tail<S: AudioSource + Tail>(mut source: S) -> (Vec<Sample>, AudioFormat) {
+    let tail_frames = source.tail_frames();
+    assert_ne!(tail_frames, u64::MAX, "Can't render an infinite tail.");
+    let (mut samples, format) = render(&mut source);
+    let (tail, _) = render_frames(TailOf(&mut source), tail_frames);
+    samples.extend_from_slice(&tail);
+    (samples, format)
+}
+
+/// Reads the tail of a finished source, finishing only when the source stops providing samples.
+struct TailOf<'a, S: AudioSource>(&'a mut S);
+
+impl<'a, S: AudioSource> AudioSource for TailOf<'a, S> {
+    fn format(&self) -> AudioFormat {
+        self.0.format()
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.0.read(buffer);
+        if result.read == 0 {
+            ReadResult::finished(0)
+        } else {
+            ReadResult::good(result.read)
+        }
+    }
+}
+
+/// Read from a source until it finishes or `max_frames` frames have been read.
+///
+/// Like [`render`](crate::render), this waits briefly before reading again
+/// whenever the source underruns without providing anything.
+///
+/// # Returns
+///
+/// All samples read from the source, and the source's format.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SineWave, render_frames};
+/// let (samples, format) = render_frames(SineWave::new(1.0, 440.0), 1000);
+/// assert_eq!(samples.len(), 1000 * format.channels as usize);
+/// ```
+pub fn render_frames<S: AudioSource>(mut source: S, max_frames: u64) -> (Vec<Sample>, AudioFormat) {
+    let format = source.format();
+    let channels = format.channels as usize;
+
+    let mut samples = Vec::new();
+    let mut remaining = max_frames;
+    while remaining > 0 {
+        let frames = std::cmp::min(remaining, RENDER_BLOCK_FRAMES as u64) as usize;
+        let start = samples.len();
+        samples.resize(start + frames * channels, 0.0);
+
+        let result = source.read(&mut samples[start..]);
+        samples.truncate(start + result.read);
+        remaining -= (result.read / channels) as u64;
+
+        match result.state {
+            StreamState::Finished => break,
+            StreamState::Underrun if result.read == 0 => std::thread::sleep(UNDERRUN_BACKOFF),
+            _ => {}
+        }
+    }
+
+    (samples, format)
+}
+
+/// Pull from a source and push to a sink until the source finishes or the sink closes.
+///
+/// Reads are made in fixed-size blocks. If the source underruns, whatever it
+/// provided is written and reading continues, after a brief wait if it
+/// provided nothing. If the sink is full, the rest of the block is retried,
+/// so this blocks while either side waits. If the source never finishes and
+/// the sink never closes, this never returns.
+///
+/// # Returns
+///
+/// The number of frames the sink accepted.
+///
+/// # Panics
+///
+/// If the source and sink have different formats.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::Ramp, pump, sinks::ChannelSink, AudioFormat};
+/// # use std::time::Duration;
+/// let ramp = Ramp::finite(0.0, 1.0, Duration::from_millis(100), AudioFormat::STEREO_CD);
+/// let (sender, receiver) = std::sync::mpsc::channel();
+///
+/// let frames = pump(ramp, ChannelSink::new(sender, AudioFormat::STEREO_CD));
+/// assert_eq!(frames, 4410);
+/// assert_eq!(receiver.iter().flatten().count(), 4410 * 2);
+/// ```
+pub fn pump<S: AudioSource, K: AudioSink>(mut source: S, mut sink: K) -> u64 {
+    let format = source.format();
+    assert_eq!(
+        format,
+        sink.format(),
+        "The source and sink must have the same format."
+    );
+
+    let mut buffer = vec![0.0; format.frames_to_samples(RENDER_BLOCK_FRAMES)];
+    let mut frames = 0;
+    loop {
+        let result = source.read(&mut buffer);
+
+        let mut written = 0;
+        while written < result.read {
+            let write = sink.write(&buffer[written..result.read]);
+            written += write.written;
+            match write.state {
+                SinkState::Good => {}
+                SinkState::Full => std::thread::yield_now(),
+                SinkState::Closed => return frames + format.samples_to_frames(written) as u64,
+            }
+        }
+        frames += format.samples_to_frames(written) as u64;
+
+        match result.state {
+            StreamState::Finished => return frames,
+            StreamState::Underrun if result.read == 0 => std::thread::sleep(UNDERRUN_BACKOFF),
+            _ => {}
+        }
+    }
+}
+
+/// An iterator over blocks of samples from a source.
+///
+/// Returned by [`AudioSource::frames`](crate::AudioSource::frames).
+pub struct Frames<S: AudioSource> {
+    source: S,
+    buffer: Vec<Sample>,
+    finished: bool,
+}
+
+impl<S: AudioSource> Frames<S> {
+    /// Returns the source, e.g. to rewind it.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: AudioSource> Iterator for Frames<S> {
+    type Item = Vec<Sample>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.source.read(&mut self.buffer);
+        if result.state == StreamState::Finished {
+            self.finished = true;
+            if result.read == 0 {
+                return None;
+            }
+        }
+        Some(self.buffer[..result.read].to_vec())
+    }
+}
+
 /// Helpful extension to move [`AudioSource`](crate::AudioSource) implementations
 /// into an `Arc<Mutex<...>>`.
 pub trait IntoShared {
@@ -126,6 +1042,46 @@ impl<T: AudioSource + Send + 'static> IntoShared for T {
     }
 }
 
+impl<S: AudioSource + ?Sized> AudioSource for Box<S> {
+    fn format(&self) -> AudioFormat {
+        (**self).format()
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        (**self).read(buffer)
+    }
+}
+
+impl<S: AudioSource + ?Sized> AudioSource for &mut S {
+    fn format(&self) -> AudioFormat {
+        (**self).format()
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        (**self).read(buffer)
+    }
+}
+
+impl<K: AudioSink + ?Sized> AudioSink for &mut K {
+    fn format(&self) -> AudioFormat {
+        (**self).format()
+    }
+
+    fn write(&mut self, buffer: &[Sample]) -> WriteResult {
+        (**self).write(buffer)
+    }
+}
+
+impl<K: AudioSink + ?Sized> AudioSink for Box<K> {
+    fn format(&self) -> AudioFormat {
+        (**self).format()
+    }
+
+    fn write(&mut self, buffer: &[Sample]) -> WriteResult {
+        (**self).write(buffer)
+    }
+}
+
 impl AudioSource for SharedAudioSource {
     fn format(&self) -> AudioFormat {
         self.lock().unwrap().format()
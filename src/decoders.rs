@@ -1,5 +1,26 @@
 //! [`AudioSource`](crate::AudioSource) implementations that read common audio codecs.
 
+#[cfg(feature = "flac")]
+mod flac_decoder;
+#[cfg(feature = "mp3")]
+mod mp3_decoder;
+#[cfg(feature = "ogg")]
+mod ogg_decoder;
+#[cfg(feature = "symphonia")]
+mod packet_decoder;
+mod riff;
+mod tagged;
 mod wav_decoder;
+mod wav_metadata;
+mod wav_stream_decoder;
 
+#[cfg(feature = "flac")]
+pub use flac_decoder::FlacDecoder;
+#[cfg(feature = "mp3")]
+pub use mp3_decoder::Mp3Decoder;
+#[cfg(feature = "ogg")]
+pub use ogg_decoder::OggDecoder;
+pub use tagged::Tagged;
 pub use wav_decoder::WavDecoder;
+pub use wav_metadata::WavMetadata;
+pub use wav_stream_decoder::WavStreamDecoder;
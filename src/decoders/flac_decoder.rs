@@ -0,0 +1,159 @@
+use crate::{
+    decoders::{packet_decoder::PacketDecoder, Tagged},
+    AudioFormat, AudioSource, Error, ReadResult, Sample, Tail,
+};
+
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+    time::Duration,
+};
+use symphonia::core::{codecs::CODEC_TYPE_FLAC, io::MediaSource};
+use tracing::instrument;
+
+/// An AudioSource that decodes a FLAC file.
+///
+/// Frames are decoded as they're read, so long files don't need to fit in
+/// memory as samples. When the stream ends, AudioSource::read returns
+/// [`Finished`](crate::StreamState::Finished) status.
+///
+/// Samples are scaled to `[-1.0, 1.0]` the same way
+/// [`WavDecoder`](crate::decoders::WavDecoder) scales integer samples, so a
+/// WAV file converted to FLAC decodes to exactly the same samples. Files
+/// with any number of channels and any bit depth FLAC allows, from 4 to 32
+/// bits, are supported.
+///
+/// Requires the `flac` feature.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use timbre::{decoders::{FlacDecoder, WavDecoder}, render, AudioFormat, AudioSource};
+///
+/// let stereo = FlacDecoder::from_file("./assets/stereo-i24.flac")?;
+/// assert_eq!(stereo.format(), AudioFormat::STEREO_CD);
+///
+/// let flac = FlacDecoder::from_file("./assets/mono-i16.flac")?;
+/// let wav = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+/// assert_eq!(render(flac), render(wav));
+/// # Ok(())
+/// # }
+/// ```
+pub struct FlacDecoder {
+    stream: PacketDecoder,
+}
+
+impl FlacDecoder {
+    /// Construct a FlacDecoder that reads from a [`std::io::Read`](std::io::Read).
+    ///
+    /// The whole stream is read into memory, and decoded as it's played.
+    ///
+    /// # Errors
+    ///
+    /// If `read` fails, will return the underlying I/O error. If the stream
+    /// isn't a FLAC file, will return a [`DecodeError`](crate::Error::DecodeError),
+    /// and if its bit depth isn't supported, [`UnsupportedFormat`](crate::Error::UnsupportedFormat).
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::FlacDecoder, Error};
+    ///
+    /// let decoder = FlacDecoder::new(std::fs::File::open("./assets/mono-i16.flac")?)?;
+    ///
+    /// match FlacDecoder::new(&b"Not a FLAC file"[..]) {
+    ///     Err(Error::DecodeError(_)) => {}
+    ///     _ => panic!("Expected a decode error."),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "FlacDecoder::new", skip(read))]
+    pub fn new<R: Read>(mut read: R) -> Result<Self, Error> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+        FlacDecoder::open(Box::new(Cursor::new(bytes)))
+    }
+
+    /// Construct a FlacDecoder that plays the file given by `path`.
+    ///
+    /// The file is read as it's played.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be opened or read, will return the underlying I/O
+    /// error. Otherwise, returns the same errors as [`new`](FlacDecoder::new).
+    #[instrument(name = "FlacDecoder::from_file")]
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        FlacDecoder::open(Box::new(std::fs::File::open(path)?))
+    }
+
+    /// Returns the length of the file, or `None` if its header doesn't record it.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::decoders::FlacDecoder;
+    /// use std::time::Duration;
+    ///
+    /// let decoder = FlacDecoder::from_file("./assets/mono-i16.flac")?;
+    /// assert_eq!(decoder.duration(), Some(Duration::from_millis(100)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn duration(&self) -> Option<Duration> {
+        let frames = self.stream.frames()?;
+        Some(self.stream.format().frames_to_duration(frames))
+    }
+
+    fn open(source: Box<dyn MediaSource>) -> Result<Self, Error> {
+        let stream = PacketDecoder::new(source, "flac", CODEC_TYPE_FLAC, "FLAC")?;
+        match stream.bits_per_sample() {
+            Some(4..=32) => Ok(FlacDecoder { stream }),
+            Some(bits) => Err(Error::UnsupportedFormat(format!(
+                "{}-bit FLAC isn't supported.",
+                bits
+            ))),
+            None => Err(Error::UnsupportedFormat(
+                "The FLAC stream doesn't specify its bit depth.".to_string(),
+            )),
+        }
+    }
+}
+
+impl AudioSource for FlacDecoder {
+    fn format(&self) -> AudioFormat {
+        self.stream.format()
+    }
+
+    #[instrument(name = "FlacDecoder::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.stream.read(buffer)
+    }
+}
+
+impl Tagged for FlacDecoder {
+    /// Returns the Vorbis comments from the file's `VORBIS_COMMENT` block.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::decoders::{FlacDecoder, Tagged};
+    ///
+    /// let decoder = FlacDecoder::from_file("./assets/tagged-mono-i16.flac")?;
+    /// let tags = decoder.tags();
+    /// assert_eq!(tags.get("TITLE").map(String::as_str), Some("Test Tone"));
+    /// assert_eq!(tags.get("ARTIST").map(String::as_str), Some("timbre"));
+    /// assert_eq!(tags.get("ALBUM"), None);
+    ///
+    /// let untagged = FlacDecoder::from_file("./assets/mono-i16.flac")?;
+    /// assert!(untagged.tags().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn tags(&self) -> HashMap<String, String> {
+        self.stream.tags()
+    }
+}
+
+impl Tail for FlacDecoder {}
@@ -0,0 +1,112 @@
+use crate::{
+    decoders::packet_decoder::PacketDecoder, AudioFormat, AudioSource, Error, ReadResult, Sample,
+    Tail,
+};
+
+use std::io::{Cursor, Read};
+use symphonia::core::{codecs::CODEC_TYPE_MP3, io::MediaSource};
+use tracing::instrument;
+
+/// An AudioSource that decodes an MP3 file.
+///
+/// Frames are decoded as they're read, rather than all up front, so long
+/// tracks start quickly and don't need to fit in memory as samples. When the
+/// stream ends, AudioSource::read returns [`Finished`](crate::StreamState::Finished)
+/// status. Frames that fail to decode are skipped with a warning, so a
+/// damaged file plays around the damage.
+///
+/// If the file has a LAME or Xing header, the encoder's delay and padding are
+/// trimmed, so the decoded length matches the original audio.
+///
+/// Requires the `mp3` feature.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use timbre::{decoders::Mp3Decoder, effects::LowPass, render, AudioFormat, AudioSource};
+///
+/// let decoder = Mp3Decoder::from_file("./assets/silence-mono.mp3")?;
+/// assert_eq!(decoder.format(), AudioFormat::MONO_CD);
+///
+/// let (samples, _) = render(LowPass::new(decoder, 1000.0));
+/// assert_eq!(samples.len(), 10 * 1152);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Mp3Decoder {
+    stream: PacketDecoder,
+}
+
+impl Mp3Decoder {
+    /// Construct an Mp3Decoder that reads from a [`std::io::Read`](std::io::Read).
+    ///
+    /// The whole stream is read into memory, and decoded as it's played.
+    ///
+    /// # Errors
+    ///
+    /// If `read` fails, will return the underlying I/O error. If the stream
+    /// isn't an MP3 file, will return a [`DecodeError`](crate::Error::DecodeError).
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::Mp3Decoder, Error};
+    ///
+    /// let decoder = Mp3Decoder::new(std::fs::File::open("./assets/silence-mono.mp3")?)?;
+    ///
+    /// match Mp3Decoder::new(&b"Not an MP3 file"[..]) {
+    ///     Err(Error::DecodeError(_)) => {}
+    ///     _ => panic!("Expected a decode error."),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "Mp3Decoder::new", skip(read))]
+    pub fn new<R: Read>(mut read: R) -> Result<Self, Error> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+        Mp3Decoder::open(Box::new(Cursor::new(bytes)))
+    }
+
+    /// Construct an Mp3Decoder that plays the file given by `path`.
+    ///
+    /// The file is read as it's played.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be opened or read, will return the underlying I/O
+    /// error. Otherwise, returns the same errors as [`new`](Mp3Decoder::new).
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::decoders::Mp3Decoder;
+    ///
+    /// let decoder = Mp3Decoder::from_file("./assets/silence-mono.mp3")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "Mp3Decoder::from_file")]
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        Mp3Decoder::open(Box::new(std::fs::File::open(path)?))
+    }
+
+    fn open(source: Box<dyn MediaSource>) -> Result<Self, Error> {
+        Ok(Mp3Decoder {
+            stream: PacketDecoder::new(source, "mp3", CODEC_TYPE_MP3, "MP3")?,
+        })
+    }
+}
+
+impl AudioSource for Mp3Decoder {
+    fn format(&self) -> AudioFormat {
+        self.stream.format()
+    }
+
+    #[instrument(name = "Mp3Decoder::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.stream.read(buffer)
+    }
+}
+
+impl Tail for Mp3Decoder {}
@@ -0,0 +1,130 @@
+use crate::{
+    decoders::{
+        packet_decoder::{PacketDecoder, SeekableSource},
+        Tagged,
+    },
+    AudioFormat, AudioSource, Error, ReadResult, Sample, Tail,
+};
+
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+};
+use symphonia::core::codecs::CODEC_TYPE_VORBIS;
+use tracing::instrument;
+
+/// An AudioSource that decodes an Ogg Vorbis file.
+///
+/// Packets are read and decoded as they're played, so a long track never has
+/// to be held in memory. When the stream ends, AudioSource::read returns
+/// [`Finished`](crate::StreamState::Finished) status, with the samples from
+/// the final partial read. Packets that fail to decode are skipped with a
+/// warning.
+///
+/// Requires the `ogg` feature.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use timbre::{decoders::OggDecoder, AudioFormat, AudioSource, StreamState};
+///
+/// let mut decoder = OggDecoder::from_file("./assets/silence-mono.ogg")?;
+/// assert_eq!(decoder.format(), AudioFormat::MONO_CD);
+///
+/// let mut samples = vec![0.0; 10000];
+/// assert_eq!(decoder.read(&mut samples).state, StreamState::Good);
+/// let result = decoder.read(&mut samples);
+/// assert_eq!((result.state, result.read), (StreamState::Finished, 2800));
+/// # Ok(())
+/// # }
+/// ```
+pub struct OggDecoder {
+    stream: PacketDecoder,
+}
+
+impl OggDecoder {
+    /// Construct an OggDecoder that reads from a seekable stream.
+    ///
+    /// # Errors
+    ///
+    /// If `read` fails, will return the underlying I/O error. If the stream
+    /// isn't an Ogg file or has no Vorbis stream, will return a
+    /// [`DecodeError`](crate::Error::DecodeError), and if the Vorbis headers
+    /// are malformed, a `DecodeError` or [`UnsupportedFormat`](crate::Error::UnsupportedFormat).
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::OggDecoder, Error};
+    /// use std::io::Cursor;
+    ///
+    /// let bytes = std::fs::read("./assets/silence-mono.ogg")?;
+    /// let decoder = OggDecoder::new(Cursor::new(bytes))?;
+    ///
+    /// match OggDecoder::new(Cursor::new(b"Not an Ogg file")) {
+    ///     Err(Error::DecodeError(_)) => {}
+    ///     _ => panic!("Expected a decode error."),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "OggDecoder::new", skip(read))]
+    pub fn new<R: Read + Seek + Send + Sync + 'static>(read: R) -> Result<Self, Error> {
+        Ok(OggDecoder {
+            stream: PacketDecoder::new(
+                Box::new(SeekableSource(read)),
+                "ogg",
+                CODEC_TYPE_VORBIS,
+                "Ogg Vorbis",
+            )?,
+        })
+    }
+
+    /// Construct an OggDecoder that plays the file given by `path`.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be opened or read, will return the underlying I/O
+    /// error. Otherwise, returns the same errors as [`new`](OggDecoder::new).
+    #[instrument(name = "OggDecoder::from_file")]
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        OggDecoder::new(std::fs::File::open(path)?)
+    }
+}
+
+impl AudioSource for OggDecoder {
+    fn format(&self) -> AudioFormat {
+        self.stream.format()
+    }
+
+    #[instrument(name = "OggDecoder::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.stream.read(buffer)
+    }
+}
+
+impl Tagged for OggDecoder {
+    /// Returns the Vorbis comments of the stream.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::decoders::{OggDecoder, Tagged};
+    ///
+    /// let decoder = OggDecoder::from_file("./assets/tagged-silence-mono.ogg")?;
+    /// let tags = decoder.tags();
+    /// assert_eq!(tags.get("TITLE").map(String::as_str), Some("Silence"));
+    /// assert_eq!(tags.get("ARTIST").map(String::as_str), Some("timbre"));
+    /// assert_eq!(tags.get("ALBUM").map(String::as_str), Some("Test Assets"));
+    ///
+    /// let untagged = OggDecoder::from_file("./assets/silence-mono.ogg")?;
+    /// assert!(untagged.tags().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn tags(&self) -> HashMap<String, String> {
+        self.stream.tags()
+    }
+}
+
+impl Tail for OggDecoder {}
@@ -0,0 +1,265 @@
+use crate::{AudioFormat, Error, ReadResult, Sample};
+
+use std::{
+    collections::HashMap,
+    io::{ErrorKind, Read, Seek, SeekFrom},
+};
+use symphonia::core::{
+    audio::{AudioBuffer, AudioBufferRef, SampleBuffer, Signal},
+    codecs::{CodecType, Decoder, DecoderOptions},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, FormatReader},
+    io::{MediaSource, MediaSourceStream},
+    meta::{MetadataOptions, MetadataRevision},
+    probe::Hint,
+};
+use tracing::warn;
+
+/// Decodes the packets of one track with Symphonia, as they're needed.
+///
+/// This is shared by the decoders for compressed formats, which differ only in
+/// the container and codec they accept.
+pub(crate) struct PacketDecoder {
+    reader: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    format: AudioFormat,
+    frames: Option<u64>,
+    bits_per_sample: Option<u32>,
+    tags: HashMap<String, String>,
+    /// The decoded samples of the current packet, and how many have been read.
+    decoded: Vec<Sample>,
+    position: usize,
+    finished: bool,
+}
+
+impl PacketDecoder {
+    /// Open the first track of `codec` in `source`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The stream to decode.
+    /// * `extension` -- The usual file extension, as a hint for finding the container.
+    /// * `codec` -- The codec the track must use.
+    /// * `name` -- The name of the format, for error messages.
+    pub fn new(
+        source: Box<dyn MediaSource>,
+        extension: &str,
+        codec: CodecType,
+        name: &str,
+    ) -> Result<Self, Error> {
+        let stream = MediaSourceStream::new(source, Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension(extension);
+        let options = FormatOptions {
+            enable_gapless: true,
+            ..Default::default()
+        };
+
+        let mut reader = symphonia::default::get_probe()
+            .format(&hint, stream, &options, &MetadataOptions::default())
+            .map_err(|error| match error {
+                SymphoniaError::IoError(error) if error.kind() != ErrorKind::UnexpectedEof => {
+                    Error::IoError(error)
+                }
+                _ => Error::DecodeError(format!("Not a {} stream.", name)),
+            })?
+            .format;
+        let tags = reader
+            .metadata()
+            .skip_to_latest()
+            .map(to_tags)
+            .unwrap_or_default();
+
+        let track = reader
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec == codec)
+            .ok_or_else(|| Error::DecodeError(format!("No {} track found.", name)))?;
+        let track_id = track.id;
+        let frames = track.codec_params.n_frames;
+        let bits_per_sample = track.codec_params.bits_per_sample;
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(Error::from_symphonia)?;
+
+        let (channels, sample_rate) =
+            match (track.codec_params.channels, track.codec_params.sample_rate) {
+                (Some(channels), Some(sample_rate)) => (channels.count(), sample_rate),
+                _ => {
+                    return Err(Error::UnsupportedFormat(format!(
+                        "The {} stream doesn't specify its channels and sample rate.",
+                        name
+                    )))
+                }
+            };
+        if channels == 0 || channels > u8::MAX as usize {
+            return Err(Error::UnsupportedFormat(format!(
+                "{} channels aren't supported.",
+                channels
+            )));
+        }
+
+        Ok(PacketDecoder {
+            reader,
+            decoder,
+            track_id,
+            format: AudioFormat {
+                channels: channels as u8,
+                sample_rate,
+            },
+            frames,
+            bits_per_sample,
+            tags,
+            decoded: Vec::new(),
+            position: 0,
+            finished: false,
+        })
+    }
+
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    /// Returns the length of the track in frames, if the container records it.
+    pub fn frames(&self) -> Option<u64> {
+        self.frames
+    }
+
+    /// Returns the bit depth of the track, for lossless codecs.
+    pub fn bits_per_sample(&self) -> Option<u32> {
+        self.bits_per_sample
+    }
+
+    /// Returns the tags the container holds, keyed by upper-case field name.
+    pub fn tags(&self) -> HashMap<String, String> {
+        self.tags.clone()
+    }
+
+    /// Fill `buffer` with decoded samples, decoding more packets as needed.
+    pub fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let mut written = 0;
+        while written < buffer.len() {
+            if self.position == self.decoded.len() && !self.decode_packet() {
+                return ReadResult::finished(written);
+            }
+
+            let count = std::cmp::min(buffer.len() - written, self.decoded.len() - self.position);
+            buffer[written..written + count]
+                .copy_from_slice(&self.decoded[self.position..self.position + count]);
+            written += count;
+            self.position += count;
+        }
+
+        ReadResult::good(written)
+    }
+
+    /// Decode the next packet of the track into `decoded`.
+    ///
+    /// Packets that fail to decode are skipped, as players do. Returns false
+    /// once the stream ends or can't be read.
+    fn decode_packet(&mut self) -> bool {
+        while !self.finished {
+            let packet = match self.reader.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(error)) if error.kind() == ErrorKind::UnexpectedEof => {
+                    break;
+                }
+                Err(error) => {
+                    warn!("Stopped reading the stream: {}", error);
+                    break;
+                }
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            match self.decoder.decode(&packet) {
+                Ok(audio) => {
+                    self.decoded.clear();
+                    self.position = 0;
+                    match (audio, self.bits_per_sample) {
+                        (AudioBufferRef::S32(audio), Some(bits)) => {
+                            interleave_integer(&audio, bits, &mut self.decoded)
+                        }
+                        (audio, _) => {
+                            let mut samples =
+                                SampleBuffer::<Sample>::new(audio.capacity() as u64, *audio.spec());
+                            samples.copy_interleaved_ref(audio);
+                            self.decoded.extend_from_slice(samples.samples());
+                        }
+                    }
+                    if !self.decoded.is_empty() {
+                        return true;
+                    }
+                }
+                Err(SymphoniaError::DecodeError(message)) => {
+                    warn!("Skipping a packet that failed to decode: {}", message);
+                }
+                Err(error) => {
+                    warn!("Stopped decoding the stream: {}", error);
+                    break;
+                }
+            }
+        }
+
+        self.finished = true;
+        false
+    }
+}
+
+/// Interleave full-scale integer samples that hold `bits` bits of audio.
+///
+/// They're scaled by the largest positive value of that bit depth, as
+/// [`WavDecoder`](crate::decoders::WavDecoder) does, so the same audio decodes
+/// identically from either format.
+fn interleave_integer(audio: &AudioBuffer<i32>, bits: u32, decoded: &mut Vec<Sample>) {
+    let shift = 32 - bits;
+    let scale = ((1u64 << (bits - 1)) - 1) as f32;
+    let planes = audio.planes();
+    for frame in 0..audio.frames() {
+        decoded.extend(
+            planes
+                .planes()
+                .iter()
+                .map(|plane| (plane[frame] >> shift) as f32 / scale),
+        );
+    }
+}
+
+/// Collect the tags of a metadata revision, such as the Vorbis comments of an
+/// Ogg or FLAC file.
+///
+/// Vorbis comment field names are case-insensitive, so they're upper-cased.
+fn to_tags(revision: &MetadataRevision) -> HashMap<String, String> {
+    revision
+        .tags()
+        .iter()
+        .map(|tag| (tag.key.to_uppercase(), tag.value.to_string()))
+        .collect()
+}
+
+/// Adapts any seekable stream to be read by Symphonia.
+pub(crate) struct SeekableSource<R>(pub R);
+
+impl<R: Read> Read for SeekableSource<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<R: Seek> Seek for SeekableSource<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+impl<R: Read + Seek + Send + Sync> MediaSource for SeekableSource<R> {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
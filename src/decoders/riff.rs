@@ -0,0 +1,306 @@
+//! Helpers for walking the chunks of RIFF/WAVE files.
+
+use crate::{AudioFormat, Error};
+
+use std::{
+    convert::TryInto,
+    io::{ErrorKind, Read},
+};
+
+/// Returns true if `bytes` begins with a RIFF/WAVE header.
+pub(crate) fn is_wave(bytes: &[u8]) -> bool {
+    bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE"
+}
+
+/// Iterate over the `(id, data)` pairs of a sequence of RIFF chunks.
+pub(crate) fn chunks(mut bytes: &[u8]) -> impl Iterator<Item = (&[u8; 4], &[u8])> {
+    std::iter::from_fn(move || {
+        if bytes.len() < 8 {
+            return None;
+        }
+
+        let id: &[u8; 4] = bytes[0..4].try_into().unwrap();
+        let size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        let data = bytes.get(8..8 + size)?;
+
+        // Chunks are padded to an even number of bytes.
+        let next = std::cmp::min(8 + size + (size & 1), bytes.len());
+        bytes = &bytes[next..];
+        Some((id, data))
+    })
+}
+
+const PCM: u16 = 1;
+const IEEE_FLOAT: u16 = 3;
+const EXTENSIBLE: u16 = 0xFFFE;
+
+/// The last 14 bytes of the `KSDATAFORMAT_SUBTYPE_*` GUIDs for PCM and IEEE
+/// float. The first two bytes hold the equivalent format tag.
+const SUBTYPE_SUFFIX: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// The contents of a `fmt ` chunk.
+pub(crate) struct WaveFormat {
+    /// The format tag, with `WAVE_FORMAT_EXTENSIBLE` resolved to its sub-format.
+    pub tag: u16,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits: u16,
+    /// The speaker positions of the channels, from a `WAVE_FORMAT_EXTENSIBLE` header.
+    pub channel_mask: Option<u32>,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::DecodeError(message.to_string())
+}
+
+/// Find the `fmt ` and `data` chunks of a RIFF/WAVE file.
+///
+/// Unlike [`chunks`], this reports a chunk that runs past the end of the
+/// file as [`Truncated`](crate::Error::Truncated) if either chunk is missing.
+fn find_chunks(bytes: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    if !is_wave(bytes) {
+        return Err(invalid("Not a RIFF/WAVE file."));
+    }
+
+    let mut fmt = None;
+    let mut data = None;
+    let mut truncated = false;
+    let mut rest = &bytes[12..];
+    while !rest.is_empty() {
+        if rest.len() < 8 {
+            truncated = true;
+            break;
+        }
+
+        let size = u32::from_le_bytes(rest[4..8].try_into().unwrap()) as usize;
+        let chunk = match rest.get(8..8 + size) {
+            Some(chunk) => chunk,
+            None => {
+                truncated = true;
+                break;
+            }
+        };
+        match &rest[0..4] {
+            b"fmt " => fmt = Some(chunk),
+            b"data" => data = Some(chunk),
+            _ => {}
+        }
+
+        let next = std::cmp::min(8 + size + (size & 1), rest.len());
+        rest = &rest[next..];
+    }
+
+    match (fmt, data) {
+        (Some(fmt), _) if fmt.len() < 16 => Err(invalid("Incomplete fmt chunk.")),
+        (Some(fmt), Some(data)) => Ok((fmt, data)),
+        _ if truncated => Err(Error::Truncated),
+        (None, _) => Err(invalid("Missing fmt chunk.")),
+        (_, None) => Err(invalid("Missing data chunk.")),
+    }
+}
+
+/// Parse the `fmt ` chunk of a RIFF/WAVE file, including `WAVE_FORMAT_EXTENSIBLE` headers.
+///
+/// # Errors
+///
+/// [`DecodeError`](crate::Error::DecodeError) if `bytes` isn't a WAV file,
+/// [`Truncated`](crate::Error::Truncated) if it ends before its `fmt ` or `data`
+/// chunk is complete, and [`UnsupportedFormat`](crate::Error::UnsupportedFormat)
+/// for an extensible sub-format other than PCM or IEEE float.
+pub(crate) fn read_format(bytes: &[u8]) -> Result<WaveFormat, Error> {
+    let (fmt, _) = find_chunks(bytes)?;
+    parse_format(fmt)
+}
+
+/// Read the header of a RIFF/WAVE stream, up to the first sample of its `data` chunk.
+///
+/// Unlike [`read_format`], this doesn't need the whole file in memory or a
+/// stream that can seek: it reads only as far as the `data` chunk, skipping
+/// any chunks before it other than `fmt `, and tolerates reads that return
+/// only part of what was asked for.
+///
+/// # Returns
+///
+/// The format, and the length of the `data` chunk in bytes, or `None` if the
+/// length is 0xFFFFFFFF, which live streams use when the length is unknown.
+///
+/// # Errors
+///
+/// As [`read_format`], plus the underlying I/O error if reading fails.
+pub(crate) fn read_header<R: Read>(read: &mut R) -> Result<(WaveFormat, Option<u64>), Error> {
+    let mut header = [0; 12];
+    read_exact(read, &mut header)?;
+    if !is_wave(&header) {
+        return Err(invalid("Not a RIFF/WAVE file."));
+    }
+
+    let mut format = None;
+    loop {
+        let mut chunk = [0; 8];
+        read_exact(read, &mut chunk)?;
+        let size = u32::from_le_bytes(chunk[4..8].try_into().unwrap());
+        // Chunks are padded to an even number of bytes.
+        let padded = size as u64 + (size & 1) as u64;
+
+        match &chunk[0..4] {
+            b"fmt " if !(16..=MAX_FMT_BYTES).contains(&size) => {
+                return Err(invalid("Incomplete fmt chunk."))
+            }
+            b"fmt " => {
+                let mut fmt = vec![0; padded as usize];
+                read_exact(read, &mut fmt)?;
+                format = Some(parse_format(&fmt)?);
+            }
+            b"data" => {
+                let format = format.ok_or_else(|| invalid("Missing fmt chunk."))?;
+                let size = if size == u32::MAX {
+                    None
+                } else {
+                    Some(size as u64)
+                };
+                return Ok((format, size));
+            }
+            _ => {
+                let skipped = std::io::copy(&mut read.take(padded), &mut std::io::sink())?;
+                if skipped < padded {
+                    return Err(Error::Truncated);
+                }
+            }
+        }
+    }
+}
+
+/// The largest `fmt ` chunk accepted by [`read_header`]. Real ones are at most 40 bytes.
+const MAX_FMT_BYTES: u32 = 1024;
+
+/// Fill `buffer` from `read`, reporting the end of the stream as [`Truncated`](crate::Error::Truncated).
+fn read_exact<R: Read>(read: &mut R, buffer: &mut [u8]) -> Result<(), Error> {
+    read.read_exact(buffer).map_err(|error| match error.kind() {
+        ErrorKind::UnexpectedEof => Error::Truncated,
+        _ => Error::IoError(error),
+    })
+}
+
+/// Parse the contents of a `fmt ` chunk, which must be at least 16 bytes long.
+///
+/// # Errors
+///
+/// [`DecodeError`](crate::Error::DecodeError) for an incomplete extensible
+/// header, and [`UnsupportedFormat`](crate::Error::UnsupportedFormat) for an
+/// extensible sub-format other than PCM or IEEE float.
+pub(crate) fn parse_format(fmt: &[u8]) -> Result<WaveFormat, Error> {
+    let mut tag = u16::from_le_bytes(fmt[0..2].try_into().unwrap());
+    let channels = u16::from_le_bytes(fmt[2..4].try_into().unwrap());
+    let sample_rate = u32::from_le_bytes(fmt[4..8].try_into().unwrap());
+    let bits = u16::from_le_bytes(fmt[14..16].try_into().unwrap());
+
+    let mut channel_mask = None;
+    if tag == EXTENSIBLE {
+        if fmt.len() < 40 {
+            return Err(invalid("Incomplete WAVE_FORMAT_EXTENSIBLE header."));
+        }
+        if fmt[26..40] != SUBTYPE_SUFFIX {
+            return Err(Error::UnsupportedFormat(
+                "WAVE_FORMAT_EXTENSIBLE sub-format is not PCM or IEEE float.".to_string(),
+            ));
+        }
+        channel_mask = Some(u32::from_le_bytes(fmt[20..24].try_into().unwrap()));
+        tag = u16::from_le_bytes(fmt[24..26].try_into().unwrap());
+    }
+
+    Ok(WaveFormat {
+        tag,
+        channels,
+        sample_rate,
+        bits,
+        channel_mask,
+    })
+}
+
+/// Decode the samples of a PCM or IEEE float WAV file without SDL.
+///
+/// Supports 8, 16, 24 and 32-bit integer PCM, and 32 and 64-bit float, with
+/// either the standard or the `WAVE_FORMAT_EXTENSIBLE` header.
+///
+/// # Errors
+///
+/// As [`read_format`], plus [`UnsupportedFormat`](crate::Error::UnsupportedFormat)
+/// for other sample formats or more than 255 channels.
+pub(crate) fn read_samples(bytes: &[u8]) -> Result<(Vec<f32>, AudioFormat), Error> {
+    let wave_format = read_format(bytes)?;
+    let (_, data) = find_chunks(bytes)?;
+    let format = wave_format.audio_format()?;
+    let samples = decode_samples(&wave_format, data)?;
+    Ok((samples, format))
+}
+
+impl WaveFormat {
+    /// Returns the format of the decoded samples.
+    ///
+    /// # Errors
+    ///
+    /// [`DecodeError`](crate::Error::DecodeError) if there are no channels, and
+    /// [`UnsupportedFormat`](crate::Error::UnsupportedFormat) for more than 255.
+    pub fn audio_format(&self) -> Result<AudioFormat, Error> {
+        if self.channels == 0 {
+            return Err(invalid("The fmt chunk has no channels."));
+        }
+        if self.channels > u8::MAX as u16 {
+            return Err(Error::UnsupportedFormat(format!(
+                "{} channels is more than the 255 supported.",
+                self.channels
+            )));
+        }
+
+        Ok(AudioFormat {
+            channels: self.channels as u8,
+            sample_rate: self.sample_rate,
+        })
+    }
+}
+
+/// Decode the bytes of a `data` chunk, or any whole number of samples from it.
+///
+/// # Errors
+///
+/// [`UnsupportedFormat`](crate::Error::UnsupportedFormat) for sample formats
+/// other than those supported by [`read_samples`].
+pub(crate) fn decode_samples(format: &WaveFormat, data: &[u8]) -> Result<Vec<f32>, Error> {
+    let WaveFormat { tag, bits, .. } = *format;
+    let samples = match (tag, bits) {
+        (PCM, 8) => data
+            .iter()
+            .map(|&sample| (sample as f32 - 128.0) / i8::MAX as f32)
+            .collect(),
+        (PCM, 16) => data
+            .chunks_exact(2)
+            .map(|d| i16::from_le_bytes(d.try_into().unwrap()) as f32 / i16::MAX as f32)
+            .collect(),
+        (PCM, 24) => data
+            .chunks_exact(3)
+            .map(|d| (i32::from_le_bytes([0, d[0], d[1], d[2]]) >> 8) as f32 / 8_388_607.0)
+            .collect(),
+        (PCM, 32) => data
+            .chunks_exact(4)
+            .map(|d| i32::from_le_bytes(d.try_into().unwrap()) as f32 / i32::MAX as f32)
+            .collect(),
+        (IEEE_FLOAT, 32) => data
+            .chunks_exact(4)
+            .map(|d| f32::from_le_bytes(d.try_into().unwrap()))
+            .collect(),
+        (IEEE_FLOAT, 64) => data
+            .chunks_exact(8)
+            .map(|d| f64::from_le_bytes(d.try_into().unwrap()) as f32)
+            .collect(),
+        _ => {
+            return Err(Error::UnsupportedFormat(format!(
+                "WAV format {} with {} bits per sample.",
+                tag, bits
+            )))
+        }
+    };
+
+    Ok(samples)
+}
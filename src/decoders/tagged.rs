@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+/// Trait implemented by decoders that can read descriptive tags from their files.
+///
+/// Tags use the upper-case field names of Vorbis comments, e.g. `TITLE`,
+/// `ARTIST`, and `ALBUM`, whatever format they were stored in, so a media
+/// player can show track info the same way for every decoder.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use timbre::decoders::{Tagged, WavDecoder};
+///
+/// let decoder = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+/// let tags = decoder.tags();
+/// assert_eq!(tags.get("TITLE").map(String::as_str), Some("Test Tone"));
+/// assert_eq!(tags.get("ARTIST").map(String::as_str), Some("timbre"));
+/// assert_eq!(tags.get("ALBUM"), None);
+/// # Ok(())
+/// # }
+/// ```
+pub trait Tagged {
+    /// Returns the tags read from the file, keyed by upper-case field name.
+    ///
+    /// The map is empty if the file has no tags.
+    fn tags(&self) -> HashMap<String, String>;
+}
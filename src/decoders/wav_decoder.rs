@@ -1,21 +1,64 @@
-use crate::{AudioFormat, AudioSource, Error, ReadResult, Sample};
+use crate::{
+    decoders::{
+        riff::{self, WaveFormat},
+        wav_metadata, Tagged, WavMetadata,
+    },
+    AudioFormat, AudioSource, ChannelLayout, Error, Latency, ReadResult, Rewind, Sample, Seek,
+    Seekable, Tail,
+};
 
+#[cfg(feature = "sdl2")]
 use sdl2::{
     audio::{AudioFormatNum, AudioSpecWAV},
     rwops::RWops,
 };
 
-use std::{convert::TryInto, io::Read};
-use tracing::instrument;
+#[cfg(feature = "sdl2")]
+use std::convert::TryInto;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek as _, SeekFrom},
+    time::Duration,
+};
+use tracing::{instrument, warn};
+
+/// The number of frames decoded at a time by a streaming WavDecoder.
+const WINDOW_FRAMES: usize = 8192;
+/// The most bytes of chunks after the samples that a streaming WavDecoder
+/// reads looking for metadata.
+const MAX_TRAILER_BYTES: u64 = 1 << 20;
 
 /// An AudioSource that reads audio data from a WAV file.
 ///
 /// WavDecoder reads from the given WAV file; when finished, AudioSource::read
 /// returns [`Finished`](crate::StreamState::Finished) status.
+///
+/// Tags from the optional `LIST`/`INFO` chunk and marker positions from the
+/// optional `cue ` chunk are available from [`metadata`](WavDecoder::metadata)
+/// and [`cue_points`](WavDecoder::cue_points).
+///
+/// With the `sdl2` feature (the default), files are loaded by SDL. Without it,
+/// a built-in parser handles integer PCM and IEEE float files. Files with a
+/// `WAVE_FORMAT_EXTENSIBLE` header are always decoded by the built-in parser,
+/// which reads the real sub-format and the channel mask from the header.
+///
+/// Decoders from [`from_file`](WavDecoder::from_file) and the other
+/// constructors decode the whole file up front. For long files,
+/// [`streaming`](WavDecoder::streaming) instead decodes a window at a time
+/// from disk, so memory use doesn't grow with the length of the file.
 pub struct WavDecoder {
+    /// The decoded samples: the whole file, or the current window when streaming.
     data: Vec<f32>,
+    /// The index of the sample at the start of `data`.
+    offset: usize,
+    file: Option<WavFile>,
     format: AudioFormat,
+    /// The index of the next sample to read.
     position: usize,
+    metadata: WavMetadata,
+    cue_points: Vec<u64>,
+    channel_mask: Option<u32>,
 }
 
 impl WavDecoder {
@@ -23,7 +66,11 @@ impl WavDecoder {
     ///
     /// # Errors
     ///
-    /// If the WAV file in `read` in corrupted or empty, will return the underlying SDL error.
+    /// If `read` fails, will return the underlying I/O error. If the WAV file in `read`
+    /// is corrupted or empty, will return a [`DecodeError`](crate::Error::DecodeError);
+    /// if it ends early, [`Truncated`](crate::Error::Truncated); and if its sample
+    /// format isn't supported, [`UnsupportedFormat`](crate::Error::UnsupportedFormat)
+    /// or, with the `sdl2` feature, the underlying SDL error.
     ///
     /// # Examples
     /// ```
@@ -36,28 +83,17 @@ impl WavDecoder {
     /// ```
     #[instrument(name = "WavDecoder::new", skip(read))]
     pub fn new<R: Read>(mut read: R) -> Result<Self, Error> {
-        let mut read_buffer = Vec::new();
-        let mut rwops = RWops::from_read(&mut read, &mut read_buffer).map_err(Error::from_sdl)?;
-        let wav_data = AudioSpecWAV::load_wav_rw(&mut rwops).map_err(Error::from_sdl)?;
-        let data = convert_samples(wav_data.buffer(), wav_data.format);
-
-        let format = AudioFormat {
-            channels: wav_data.channels,
-            sample_rate: wav_data.freq as u32,
-        };
-
-        Ok(WavDecoder {
-            data,
-            format,
-            position: 0,
-        })
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+        WavDecoder::decode(&bytes)
     }
 
     /// Construct a WavDecoder the file given by `path`.
     ///
     /// # Errors
     ///
-    /// If the file cannot be opened or is not a valid WAV file, will return the underlying SDL error.
+    /// If the file cannot be read, will return the underlying I/O error. Otherwise,
+    /// returns the same errors as [`new`](WavDecoder::new).
     ///
     /// # Examples
     /// ```
@@ -70,18 +106,330 @@ impl WavDecoder {
     /// ```
     #[instrument(name = "WavDecoder::from_file")]
     pub fn from_file(path: &str) -> Result<Self, Error> {
-        let wav_data = AudioSpecWAV::load_wav(path).map_err(Error::from_sdl)?;
-        let data = convert_samples(wav_data.buffer(), wav_data.format);
+        let bytes = std::fs::read(path)?;
+        WavDecoder::decode(&bytes)
+    }
+
+    /// Construct a WavDecoder from the bytes of a WAV file in memory.
+    ///
+    /// This suits assets embedded with [`include_bytes!`], which can be
+    /// decoded directly instead of through a [`Cursor`](std::io::Cursor) and
+    /// an intermediate copy.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`new`](WavDecoder::new), except for I/O errors.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::WavDecoder, render};
+    ///
+    /// static TONE: &[u8] = include_bytes!("../../assets/tagged-mono-i16.wav");
+    /// let embedded = WavDecoder::from_bytes(TONE)?;
+    /// let file = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+    /// assert_eq!(embedded.metadata(), file.metadata());
+    /// assert_eq!(render(embedded), render(file));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "WavDecoder::from_bytes", skip(bytes))]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        WavDecoder::decode(bytes)
+    }
+
+    /// Construct a WavDecoder that streams the file given by `path` from disk.
+    ///
+    /// Unlike [`from_file`](WavDecoder::from_file), this keeps the file open
+    /// and decodes it a window of about 8000 frames at a time as it's read, so
+    /// memory use stays the same however long the file is. Reads, seeking,
+    /// and the end of the stream behave just as they do for the whole file.
+    /// Files are always decoded by the built-in parser, which supports integer
+    /// PCM and IEEE float.
+    ///
+    /// If reading the file fails partway through, playback finishes early
+    /// with a warning logged.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be opened or read, will return the underlying I/O
+    /// error. If the file is corrupted, will return a
+    /// [`DecodeError`](crate::Error::DecodeError); if its `data` chunk runs
+    /// past the end of the file, [`Truncated`](crate::Error::Truncated); and if
+    /// its sample format isn't supported, [`UnsupportedFormat`](crate::Error::UnsupportedFormat).
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::WavDecoder, render};
+    ///
+    /// let streamed = WavDecoder::streaming("./assets/tagged-mono-i16.wav")?;
+    /// let loaded = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+    /// assert_eq!(streamed.metadata(), loaded.metadata());
+    /// assert_eq!(streamed.cue_points(), loaded.cue_points());
+    /// assert_eq!(render(streamed), render(loaded));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "WavDecoder::streaming")]
+    pub fn streaming(path: &str) -> Result<Self, Error> {
+        let mut file = File::open(path)?;
+        let file_bytes = file.metadata()?.len();
+        let (wave_format, size) = riff::read_header(&mut file)?;
+        let format = wave_format.audio_format()?;
+        // Decoding nothing checks that the sample format is supported.
+        riff::decode_samples(&wave_format, &[])?;
+
+        let data_start = file.stream_position()?;
+        let size = size.unwrap_or(file_bytes - data_start);
+        if data_start + size > file_bytes {
+            return Err(Error::Truncated);
+        }
+
+        // The metadata chunks may come before or after the samples, so read
+        // everything but the samples.
+        let mut bytes = vec![0; data_start as usize - 8];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut bytes)?;
+        file.seek(SeekFrom::Start(data_start + size + (size & 1)))?;
+        (&mut file)
+            .take(MAX_TRAILER_BYTES)
+            .read_to_end(&mut bytes)?;
+        let (metadata, cue_points) = wav_metadata::parse(&bytes);
+
+        let sample_bytes = wave_format.bits as u64 / 8;
+        let mut decoder = WavDecoder {
+            data: Vec::new(),
+            offset: 0,
+            channel_mask: wave_format.channel_mask,
+            file: Some(WavFile {
+                file,
+                samples: (size / sample_bytes) as usize,
+                sample_bytes,
+                data_start,
+                wave_format,
+            }),
+            format,
+            position: 0,
+            metadata,
+            cue_points,
+        };
+        decoder.fill();
+        Ok(decoder)
+    }
+
+    /// Returns the tags from the file's `LIST`/`INFO` chunk.
+    ///
+    /// If the file has no such chunk, every field is `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::decoders::WavDecoder;
+    ///
+    /// let decoder = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+    /// let metadata = decoder.metadata();
+    /// assert_eq!(metadata.title.as_deref(), Some("Test Tone"));
+    /// assert_eq!(metadata.artist.as_deref(), Some("timbre"));
+    /// assert_eq!(metadata.album, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn metadata(&self) -> WavMetadata {
+        self.metadata.clone()
+    }
+
+    /// Returns the positions of the markers in the file's `cue ` chunk, in frames.
+    ///
+    /// The positions are sorted, and empty if the file has no cue chunk.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::decoders::WavDecoder;
+    ///
+    /// let decoder = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+    /// assert_eq!(decoder.cue_points(), vec![0, 2205]);
+    ///
+    /// let untagged = WavDecoder::from_file("./assets/music-mono-f32.wav")?;
+    /// assert!(untagged.cue_points().is_empty());
+    /// assert_eq!(untagged.metadata().title, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cue_points(&self) -> Vec<u64> {
+        self.cue_points.clone()
+    }
+
+    /// Returns the speaker positions of the channels, for files with a
+    /// `WAVE_FORMAT_EXTENSIBLE` header.
+    ///
+    /// Each set bit is a speaker position, in the order of the channels, as
+    /// defined by `WAVEFORMATEXTENSIBLE`: `0x1` is front left, `0x2` front right,
+    /// `0x4` front center, and so on. Returns `None` for files with a standard header.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::WavDecoder, render, AudioFormat};
+    ///
+    /// let extensible = WavDecoder::from_file("./assets/extensible-stereo-i24.wav")?;
+    /// let standard = WavDecoder::from_file("./assets/stereo-i24.wav")?;
+    /// assert_eq!(extensible.channel_mask(), Some(0x3));
+    /// assert_eq!(standard.channel_mask(), None);
+    ///
+    /// // Both files hold the same audio.
+    /// let (extensible_samples, extensible_format) = render(extensible);
+    /// let (standard_samples, standard_format) = render(standard);
+    /// assert_eq!(extensible_format, AudioFormat::STEREO_CD);
+    /// assert_eq!(extensible_format, standard_format);
+    /// assert_eq!(extensible_samples.len(), standard_samples.len());
+    /// for (a, b) in extensible_samples.iter().zip(standard_samples) {
+    ///     assert!((a - b).abs() < 1e-6);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn channel_mask(&self) -> Option<u32> {
+        self.channel_mask
+    }
+
+    /// Returns the speaker each channel is meant for: from the channel mask if
+    /// the file has one, otherwise the default layout for its number of channels.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::WavDecoder, effects::Downmix, ChannelLayout};
+    ///
+    /// let decoder = WavDecoder::from_file("./assets/extensible-stereo-i24.wav")?;
+    /// assert_eq!(decoder.channel_layout(), ChannelLayout::from_channels(2));
+    ///
+    /// let layout = decoder.channel_layout();
+    /// let stereo = Downmix::with_layout(decoder, layout);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn channel_layout(&self) -> ChannelLayout {
+        match self.channel_mask {
+            Some(mask) => ChannelLayout::from_mask(mask, self.format.channels),
+            None => ChannelLayout::from_channels(self.format.channels),
+        }
+    }
 
-        let format = AudioFormat {
-            channels: wav_data.channels,
-            sample_rate: wav_data.freq as u32,
+    /// Borrow up to `frames` frames of decoded samples without consuming them.
+    ///
+    /// This avoids the copy made by [`read`](AudioSource::read) for consumers
+    /// that can process samples in place. Call [`advance`](WavDecoder::advance)
+    /// to move past the samples once they've been used. The returned slice is
+    /// shorter than requested near the end of the file, and empty at the end.
+    /// When [`streaming`](WavDecoder::streaming), it's also cut short at the
+    /// end of each decoded window.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::WavDecoder, AudioSource};
+    ///
+    /// let mut reader = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+    /// let mut peeker = WavDecoder::from_file("./assets/tagged-mono-i16.wav")?;
+    ///
+    /// let mut buffer = vec![0.0; 1000];
+    /// loop {
+    ///     let result = reader.read(&mut buffer);
+    ///     let peeked = peeker.peek(1000);
+    ///     assert_eq!(&buffer[..result.read], peeked);
+    ///
+    ///     let frames = peeked.len(); // Mono, so one sample per frame.
+    ///     assert_eq!(peeker.advance(frames), frames);
+    ///     if frames < 1000 {
+    ///         break;
+    ///     }
+    /// }
+    /// assert!(peeker.peek(1000).is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn peek(&self, frames: usize) -> &[Sample] {
+        let samples = frames.saturating_mul(self.format.channels as usize);
+        let buffered = self.buffered();
+        &buffered[..std::cmp::min(samples, buffered.len())]
+    }
+
+    /// Move past `frames` frames, e.g. after processing them with [`peek`](WavDecoder::peek).
+    ///
+    /// # Returns
+    ///
+    /// The number of frames actually skipped, which is less than `frames` near
+    /// the end of the file.
+    pub fn advance(&mut self, frames: usize) -> usize {
+        let channels = self.format.channels as usize;
+        let remaining = (self.len() - self.position) / channels;
+        let frames = std::cmp::min(frames, remaining);
+        self.position += frames * channels;
+        self.fill();
+        frames
+    }
+
+    /// Returns the total number of samples in the file.
+    fn len(&self) -> usize {
+        match &self.file {
+            Some(file) => file.samples,
+            None => self.data.len(),
+        }
+    }
+
+    /// Returns the decoded samples from `position` to the end of `data`.
+    fn buffered(&self) -> &[Sample] {
+        &self.data[self.position - self.offset..]
+    }
+
+    /// When streaming, decode the window starting at `position` if it isn't
+    /// already decoded.
+    fn fill(&mut self) {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => return,
         };
+        if self.position >= self.offset && self.position < self.offset + self.data.len() {
+            return;
+        }
+
+        self.offset = self.position;
+        self.data.clear();
+        if self.position >= file.samples {
+            return;
+        }
+
+        let count = std::cmp::min(
+            self.format.frames_to_samples(WINDOW_FRAMES),
+            file.samples - self.position,
+        );
+        match file.read(self.position, count) {
+            Ok(data) => self.data = data,
+            Err(error) => {
+                warn!("Stopped reading the WAV file: {}", error);
+                file.samples = self.position;
+            }
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let (data, format) = load_samples(bytes)?;
+        let (metadata, cue_points) = wav_metadata::parse(bytes);
+        let channel_mask = riff::read_format(bytes)
+            .ok()
+            .and_then(|wave_format| wave_format.channel_mask);
 
         Ok(WavDecoder {
             data,
+            offset: 0,
+            file: None,
             format,
             position: 0,
+            metadata,
+            cue_points,
+            channel_mask,
         })
     }
 }
@@ -93,20 +441,144 @@ impl AudioSource for WavDecoder {
 
     #[instrument(name = "WavDecoder::read", skip(self, buffer))]
     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
-        let remaining = self.data.len() - self.position;
+        let mut written = 0;
+        while written < buffer.len() {
+            let buffered = self.buffered();
+            if buffered.is_empty() {
+                break;
+            }
+
+            let count = std::cmp::min(buffer.len() - written, buffered.len());
+            buffer[written..written + count].copy_from_slice(&buffered[..count]);
+            written += count;
+            self.position += count;
+            self.fill();
+        }
 
-        if buffer.len() <= remaining {
-            buffer.copy_from_slice(&self.data[self.position..self.position + buffer.len()]);
-            self.position += buffer.len();
-            ReadResult::good(buffer.len())
+        if written == buffer.len() {
+            ReadResult::good(written)
         } else {
-            buffer[..remaining].copy_from_slice(&self.data[self.position..self.data.len()]);
-            self.position = self.data.len();
-            ReadResult::finished(remaining)
+            ReadResult::finished(written)
         }
     }
 }
 
+impl Tagged for WavDecoder {
+    /// Returns the tags from the file's `LIST`/`INFO` chunk.
+    ///
+    /// `INAM`, `IART`, `IPRD`, `IGNR`, `ICRD`, and `ICMT` are returned as
+    /// `TITLE`, `ARTIST`, `ALBUM`, `GENRE`, `DATE`, and `COMMENT`.
+    fn tags(&self) -> HashMap<String, String> {
+        self.metadata.to_tags()
+    }
+}
+
+impl Latency for WavDecoder {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for WavDecoder {}
+
+impl Rewind for WavDecoder {
+    /// Restart playback from the first sample.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use timbre::{decoders::WavDecoder, AudioSource, Rewind};
+    ///
+    /// let mut decoder = WavDecoder::from_file("./assets/music-mono-f32.wav")?;
+    /// let mut first = vec![0.0; 1024];
+    /// let mut second = vec![0.0; 1024];
+    ///
+    /// decoder.read(&mut first);
+    /// assert!(decoder.rewind());
+    /// decoder.read(&mut second);
+    /// assert_eq!(first, second);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn rewind(&mut self) -> bool {
+        self.position = 0;
+        self.fill();
+        true
+    }
+}
+
+impl Seek for WavDecoder {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        let channels = self.format.channels as usize;
+        let frames = (self.len() / channels) as u64;
+        let frame = std::cmp::min(frame, frames);
+        self.position = frame as usize * channels;
+        self.fill();
+        frame
+    }
+}
+
+impl Seekable for WavDecoder {
+    fn seek(&mut self, position: Duration) -> Result<(), Error> {
+        self.seek_frame(self.format.duration_to_frames(position));
+        Ok(())
+    }
+
+    fn position(&self) -> Duration {
+        let frame = self.format.samples_to_frames(self.position) as u64;
+        self.format.frames_to_duration(frame)
+    }
+}
+
+/// A WAV file being streamed from disk.
+struct WavFile {
+    file: File,
+    wave_format: WaveFormat,
+    /// The offset of the first sample in the file, in bytes.
+    data_start: u64,
+    sample_bytes: u64,
+    /// The number of samples in the file.
+    samples: usize,
+}
+
+impl WavFile {
+    /// Decode `count` samples, starting with the sample at `position`.
+    fn read(&mut self, position: usize, count: usize) -> Result<Vec<Sample>, Error> {
+        let mut bytes = vec![0; count * self.sample_bytes as usize];
+        self.file.seek(SeekFrom::Start(
+            self.data_start + position as u64 * self.sample_bytes,
+        ))?;
+        self.file.read_exact(&mut bytes)?;
+        riff::decode_samples(&self.wave_format, &bytes)
+    }
+}
+
+#[cfg(feature = "sdl2")]
+fn load_samples(bytes: &[u8]) -> Result<(Vec<f32>, AudioFormat), Error> {
+    // Check the headers first, so malformed files give the same errors as
+    // without SDL. SDL also doesn't expose the sub-format or channel mask of
+    // extensible files.
+    if riff::read_format(bytes)?.channel_mask.is_some() {
+        return riff::read_samples(bytes);
+    }
+
+    let mut rwops = RWops::from_bytes(bytes).map_err(Error::from_sdl)?;
+    let wav_data = AudioSpecWAV::load_wav_rw(&mut rwops).map_err(Error::from_sdl)?;
+    let data = convert_samples(wav_data.buffer(), wav_data.format);
+
+    let format = AudioFormat {
+        channels: wav_data.channels,
+        sample_rate: wav_data.freq as u32,
+    };
+    Ok((data, format))
+}
+
+#[cfg(not(feature = "sdl2"))]
+fn load_samples(bytes: &[u8]) -> Result<(Vec<f32>, AudioFormat), Error> {
+    riff::read_samples(bytes)
+}
+
+#[cfg(feature = "sdl2")]
 #[instrument(skip(buffer))]
 fn convert_samples(buffer: &[u8], format: sdl2::audio::AudioFormat) -> Vec<f32> {
     match format {
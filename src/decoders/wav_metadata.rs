@@ -0,0 +1,104 @@
+use crate::decoders::riff::{chunks, is_wave};
+
+use std::{collections::HashMap, convert::TryInto};
+
+/// Descriptive tags read from the `LIST`/`INFO` chunk of a WAV file.
+///
+/// Every field is `None` if the file doesn't contain the corresponding tag.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WavMetadata {
+    /// The title of the recording (`INAM`).
+    pub title: Option<String>,
+    /// The artist who created the recording (`IART`).
+    pub artist: Option<String>,
+    /// The album or product the recording belongs to (`IPRD`).
+    pub album: Option<String>,
+    /// The genre of the recording (`IGNR`).
+    pub genre: Option<String>,
+    /// The date the recording was created (`ICRD`).
+    pub date: Option<String>,
+    /// A free-form comment (`ICMT`).
+    pub comment: Option<String>,
+}
+
+impl WavMetadata {
+    /// Convert to tags with Vorbis comment field names, for [`Tagged`](crate::decoders::Tagged).
+    pub(crate) fn to_tags(&self) -> HashMap<String, String> {
+        let fields = [
+            ("TITLE", &self.title),
+            ("ARTIST", &self.artist),
+            ("ALBUM", &self.album),
+            ("GENRE", &self.genre),
+            ("DATE", &self.date),
+            ("COMMENT", &self.comment),
+        ];
+
+        fields
+            .iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value.as_ref()?.clone())))
+            .collect()
+    }
+}
+
+/// Walk the chunks of a RIFF/WAVE file and collect its metadata and cue points.
+///
+/// Chunks that are malformed or truncated end the walk early rather than
+/// producing an error, since the audio data is validated separately.
+pub(crate) fn parse(bytes: &[u8]) -> (WavMetadata, Vec<u64>) {
+    let mut metadata = WavMetadata::default();
+    let mut cue_points = Vec::new();
+
+    if !is_wave(bytes) {
+        return (metadata, cue_points);
+    }
+
+    for (id, data) in chunks(&bytes[12..]) {
+        match id {
+            b"LIST" if data.len() >= 4 && &data[0..4] == b"INFO" => {
+                parse_info(&data[4..], &mut metadata)
+            }
+            b"cue " => parse_cue(data, &mut cue_points),
+            _ => {}
+        }
+    }
+
+    cue_points.sort_unstable();
+    (metadata, cue_points)
+}
+
+fn parse_info(bytes: &[u8], metadata: &mut WavMetadata) {
+    for (id, data) in chunks(bytes) {
+        let field = match id {
+            b"INAM" => &mut metadata.title,
+            b"IART" => &mut metadata.artist,
+            b"IPRD" => &mut metadata.album,
+            b"IGNR" => &mut metadata.genre,
+            b"ICRD" => &mut metadata.date,
+            b"ICMT" => &mut metadata.comment,
+            _ => continue,
+        };
+
+        let text = String::from_utf8_lossy(data);
+        let text = text.trim_end_matches('\0');
+        if !text.is_empty() {
+            *field = Some(text.to_string());
+        }
+    }
+}
+
+fn parse_cue(bytes: &[u8], cue_points: &mut Vec<u64>) {
+    const CUE_POINT_SIZE: usize = 24;
+    const SAMPLE_OFFSET: usize = 20;
+
+    if bytes.len() < 4 {
+        return;
+    }
+
+    let count = u32::from_le_bytes(bytes[0..4].try_into().unwrap()) as usize;
+    cue_points.extend(
+        bytes[4..]
+            .chunks_exact(CUE_POINT_SIZE)
+            .take(count)
+            .map(|point| u32::from_le_bytes(point[SAMPLE_OFFSET..].try_into().unwrap()) as u64),
+    );
+}
@@ -0,0 +1,179 @@
+use crate::{
+    decoders::riff::{self, WaveFormat},
+    generators::ChannelSource,
+    AudioFormat, AudioSource, Error, Latency, ReadResult, Sample, Tail,
+};
+
+use std::{
+    io::{ErrorKind, Read},
+    sync::mpsc::{sync_channel, SyncSender},
+};
+use tracing::{instrument, warn};
+
+/// The most bytes requested from the stream in each read.
+const READ_BYTES: usize = 4096;
+/// The number of decoded reads to buffer before the decoding thread waits for
+/// them to be played. With CD quality stereo, this is about three quarters of a second.
+const BUFFERED_READS: usize = 32;
+
+/// An AudioSource that decodes a WAV stream as it arrives, e.g. from the network.
+///
+/// [`WavDecoder`](crate::decoders::WavDecoder) needs the whole file before it
+/// can play anything. `WavStreamDecoder` instead reads the header up front,
+/// then decodes the samples on a background thread as the stream delivers
+/// them, so it can play an endless internet radio stream from any
+/// [`Read`](std::io::Read) that blocks while waiting for data. The stream
+/// never needs to seek, and reads may return any number of bytes.
+///
+/// Reading from the decoder never blocks. If the stream stalls and the
+/// decoded samples run out, the read is an
+/// [`Underrun`](crate::StreamState::Underrun). Once the end of the `data`
+/// chunk or the stream is reached and everything decoded has been played, the
+/// read is [`Finished`](crate::StreamState::Finished). Only about three
+/// quarters of a second is decoded ahead, so a fast stream is read no faster
+/// than it's played.
+///
+/// A stream that fails partway through, or whose `data` chunk is cut short,
+/// finishes early with a warning logged. After the decoder is dropped, the
+/// background thread exits once its next read from the stream returns.
+///
+/// Integer PCM and IEEE float streams are supported, as by the built-in parser
+/// of `WavDecoder`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use timbre::{decoders::WavStreamDecoder, render, AudioFormat, AudioSource};
+///
+/// // Any `Read + Send` works, such as a `TcpStream` or an HTTP response body.
+/// let stream = std::fs::File::open("./assets/tagged-mono-i16.wav")?;
+/// let decoder = WavStreamDecoder::new(stream)?;
+/// assert_eq!(decoder.format(), AudioFormat::MONO_CD);
+///
+/// let (samples, _) = render(decoder);
+/// assert_eq!(samples.len(), 4410);
+/// # Ok(())
+/// # }
+/// ```
+pub struct WavStreamDecoder {
+    source: ChannelSource,
+    channel_mask: Option<u32>,
+}
+
+impl WavStreamDecoder {
+    /// Construct a WavStreamDecoder that decodes the WAV stream from `read`.
+    ///
+    /// This blocks until the header has been read, and then returns while the
+    /// samples are decoded in the background.
+    ///
+    /// # Errors
+    ///
+    /// If `read` fails before the header is complete, will return the
+    /// underlying I/O error. If the header is corrupted, will return a
+    /// [`DecodeError`](crate::Error::DecodeError); if the stream ends before
+    /// the samples begin, [`Truncated`](crate::Error::Truncated); and if the
+    /// sample format isn't supported, [`UnsupportedFormat`](crate::Error::UnsupportedFormat).
+    #[instrument(name = "WavStreamDecoder::new", skip(read))]
+    pub fn new<R: Read + Send + 'static>(mut read: R) -> Result<Self, Error> {
+        let (wave_format, length) = riff::read_header(&mut read)?;
+        let format = wave_format.audio_format()?;
+        // Decoding nothing checks that the sample format is supported.
+        riff::decode_samples(&wave_format, &[])?;
+
+        let channel_mask = wave_format.channel_mask;
+        let (sender, receiver) = sync_channel(BUFFERED_READS);
+        std::thread::Builder::new()
+            .name("timbre-wav-stream".to_string())
+            .spawn(move || decode(read, wave_format, length, sender))?;
+
+        Ok(WavStreamDecoder {
+            source: ChannelSource::new(receiver, format),
+            channel_mask,
+        })
+    }
+
+    /// Returns the speaker positions of the channels, for streams with a
+    /// `WAVE_FORMAT_EXTENSIBLE` header.
+    ///
+    /// See [`WavDecoder::channel_mask`](crate::decoders::WavDecoder::channel_mask).
+    pub fn channel_mask(&self) -> Option<u32> {
+        self.channel_mask
+    }
+}
+
+/// Decode the `data` chunk of the stream, sending the samples as they arrive.
+///
+/// Only whole frames are sent; a partial frame waits for the rest of its bytes.
+fn decode<R: Read>(
+    mut read: R,
+    format: WaveFormat,
+    mut remaining: Option<u64>,
+    sender: SyncSender<Vec<Sample>>,
+) {
+    let frame_bytes = format.channels as usize * (format.bits as usize / 8);
+    let mut bytes = Vec::new();
+    let mut chunk = [0; READ_BYTES];
+    loop {
+        let limit = remaining.map_or(READ_BYTES, |remaining| {
+            std::cmp::min(remaining, READ_BYTES as u64) as usize
+        });
+        if limit == 0 {
+            break;
+        }
+
+        let count = match read.read(&mut chunk[..limit]) {
+            Ok(0) => {
+                if remaining.is_some() {
+                    warn!("The WAV stream ended before its data chunk.");
+                }
+                break;
+            }
+            Ok(count) => count,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => {
+                warn!("Stopped reading the WAV stream: {}", error);
+                break;
+            }
+        };
+        remaining = remaining.map(|remaining| remaining - count as u64);
+
+        bytes.extend_from_slice(&chunk[..count]);
+        let whole = bytes.len() - bytes.len() % frame_bytes;
+        if whole == 0 {
+            continue;
+        }
+        let samples = match riff::decode_samples(&format, &bytes[..whole]) {
+            Ok(samples) => samples,
+            Err(error) => {
+                warn!("Stopped decoding the WAV stream: {}", error);
+                break;
+            }
+        };
+        bytes.drain(..whole);
+
+        // The decoder was dropped, so nobody is listening.
+        if sender.send(samples).is_err() {
+            break;
+        }
+    }
+}
+
+impl AudioSource for WavStreamDecoder {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "WavStreamDecoder::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.source.read(buffer)
+    }
+}
+
+impl Latency for WavStreamDecoder {
+    /// The frames that have been decoded but not yet played.
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl Tail for WavStreamDecoder {}
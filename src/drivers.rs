@@ -1,7 +1,28 @@
-//! Sources and sinks that connect to hardware.
+//! Sources and sinks that connect to hardware or a host's audio callback,
+//! plus a mock sink for testing.
 
+mod adapt;
+#[cfg(any(feature = "sdl2", feature = "testing"))]
+mod clipper;
+#[cfg(any(feature = "sdl2", feature = "testing"))]
+mod crossfade;
+#[cfg(any(feature = "sdl2", feature = "testing"))]
+mod fader;
+#[cfg(feature = "testing")]
+mod mock;
+mod pull_output;
+#[cfg(feature = "sdl2")]
 mod sdl2_input;
+#[cfg(feature = "sdl2")]
 mod sdl2_output;
+mod underrun;
 
-pub use sdl2_input::Sdl2Input;
-pub use sdl2_output::Sdl2Output;
+pub use adapt::adapt;
+#[cfg(feature = "testing")]
+pub use mock::MockDriver;
+pub use pull_output::PullOutput;
+#[cfg(feature = "sdl2")]
+pub use sdl2_input::{CaptureDevice, Sdl2Input};
+#[cfg(feature = "sdl2")]
+pub use sdl2_output::{Sdl2Output, UnderrunStats};
+pub use underrun::UnderrunStrategy;
@@ -0,0 +1,67 @@
+use crate::{
+    effects::{Downmix, Resampler, ToMono, ToStereo},
+    AudioFormat, Error, IntoShared, SharedAudioSource,
+};
+
+use tracing::info;
+
+/// Convert a source to the given format, for a device that chose a different
+/// format than the source produces.
+///
+/// The channels are converted first: mono is upmixed to stereo with
+/// [`ToStereo`](crate::effects::ToStereo), any source is downmixed to mono
+/// with [`ToMono`](crate::effects::ToMono), and multichannel sources are
+/// downmixed to stereo with [`Downmix`](crate::effects::Downmix). Then the
+/// sample rate is converted with a [`Resampler`](crate::effects::Resampler).
+/// If the formats already match, `source` is returned unchanged.
+///
+/// # Arguments
+///
+/// * `source` -- The source to convert.
+/// * `format` -- The format to convert it to.
+///
+/// # Errors
+///
+/// [`UnsupportedFormat`](crate::Error::UnsupportedFormat) if there's no
+/// conversion between the channel counts, such as stereo to 5.1.
+///
+/// # Examples
+/// ```
+/// # use timbre::{drivers::adapt, generators::SineWave, AudioFormat, IntoShared};
+/// # fn main() -> Result<(), timbre::Error> {
+/// let source = SineWave::with_format(AudioFormat::MONO_CD, 0.5, 440.0).into_shared();
+/// let adapted = adapt(source, AudioFormat::STEREO_DVD)?;
+/// assert_eq!(adapted.lock().unwrap().format(), AudioFormat::STEREO_DVD);
+/// # Ok(())
+/// # }
+/// ```
+pub fn adapt(source: SharedAudioSource, format: AudioFormat) -> Result<SharedAudioSource, Error> {
+    let original = source.lock().unwrap().format();
+    if original == format {
+        return Ok(source);
+    }
+
+    let source = match (original.channels, format.channels) {
+        (from, to) if from == to => source,
+        (_, 1) => ToMono::new(source).into_shared(),
+        (1, 2) => ToStereo::new(source).into_shared(),
+        (_, 2) => Downmix::new(source).into_shared(),
+        (from, to) => {
+            return Err(Error::UnsupportedFormat(format!(
+                "Can't convert {} channels to {}.",
+                from, to
+            )))
+        }
+    };
+    let source = if original.sample_rate == format.sample_rate {
+        source
+    } else {
+        Resampler::new(source, format).into_shared()
+    };
+
+    info!(
+        "Adapted source from {} channels at {} Hz to {} channels at {} Hz.",
+        original.channels, original.sample_rate, format.channels, format.sample_rate
+    );
+    Ok(source)
+}
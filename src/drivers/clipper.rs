@@ -0,0 +1,19 @@
+use crate::Sample;
+
+/// Samples up to this magnitude pass through the clipper unchanged.
+const KNEE: f32 = 0.8;
+
+/// Softly saturate samples beyond [`KNEE`] so they approach, but never pass, ±1.0.
+///
+/// Above the knee, the remaining headroom is filled by a tanh curve that
+/// starts with a slope of 1.0, so the curve is smooth and the level of
+/// quieter material is left as it was, bit for bit.
+pub(crate) fn soft_clip(buffer: &mut [Sample]) {
+    for sample in buffer.iter_mut() {
+        let magnitude = sample.abs();
+        if magnitude > KNEE {
+            let over = (magnitude - KNEE) / (1.0 - KNEE);
+            *sample = sample.signum() * (KNEE + (1.0 - KNEE) * over.tanh());
+        }
+    }
+}
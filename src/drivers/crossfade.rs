@@ -0,0 +1,62 @@
+use crate::{AudioFormat, Sample, SharedAudioSource};
+
+use std::time::Duration;
+
+/// A source that was replaced and is being faded out under its replacement.
+///
+/// The outgoing source is kept alive, and read alongside the new source, until
+/// the fade completes.
+pub(crate) struct Outgoing {
+    source: SharedAudioSource,
+    frames: usize,
+    elapsed: usize,
+    scratch: Vec<Sample>,
+}
+
+impl Outgoing {
+    /// Start fading out `source` over `duration`.
+    ///
+    /// Returns `None` if the duration is shorter than a frame, in which case
+    /// the switch should just be a cut.
+    pub fn new(source: SharedAudioSource, duration: Duration, format: AudioFormat) -> Option<Self> {
+        let frames = format.duration_to_frames(duration) as usize;
+        if frames == 0 {
+            return None;
+        }
+
+        Some(Outgoing {
+            source,
+            frames,
+            elapsed: 0,
+            scratch: Vec::new(),
+        })
+    }
+
+    /// Blend the outgoing source into `buffer`, which holds the new source.
+    ///
+    /// The new source ramps linearly from silence to full volume while the
+    /// outgoing one ramps down. Returns true once the fade is complete and the
+    /// outgoing source can be dropped.
+    pub fn mix_into(&mut self, buffer: &mut [Sample], channels: usize) -> bool {
+        let frames = std::cmp::min(buffer.len() / channels, self.frames - self.elapsed);
+        let len = frames * channels;
+
+        // Anything the outgoing source can't provide is faded out as silence.
+        self.scratch.resize(len, 0.0);
+        let read = self.source.lock().unwrap().read(&mut self.scratch).read;
+        self.scratch[read..].iter_mut().for_each(|s| *s = 0.0);
+
+        for (new, old) in buffer[..len]
+            .chunks_exact_mut(channels)
+            .zip(self.scratch.chunks_exact(channels))
+        {
+            self.elapsed += 1;
+            let gain = self.elapsed as f32 / self.frames as f32;
+            new.iter_mut()
+                .zip(old)
+                .for_each(|(new, old)| *new = *new * gain + *old * (1.0 - gain));
+        }
+
+        self.elapsed >= self.frames
+    }
+}
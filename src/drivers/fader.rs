@@ -0,0 +1,53 @@
+use crate::{AudioFormat, Sample};
+
+use std::time::Duration;
+
+/// Ramps the output volume linearly toward a target, to pause and resume without pops.
+pub(crate) struct Fader {
+    pub gain: f32,
+    pub target: f32,
+    step: f32,
+}
+
+impl Fader {
+    pub fn new() -> Self {
+        Fader {
+            gain: 1.0,
+            target: 1.0,
+            step: 0.0,
+        }
+    }
+
+    /// Start ramping toward `target` from the current gain, at a rate that
+    /// covers the full range over `duration`.
+    pub fn fade_to(&mut self, target: f32, duration: Duration, format: AudioFormat) {
+        let frames = duration.as_secs_f32() * format.sample_rate as f32;
+        self.target = target;
+        if frames < 1.0 {
+            self.gain = target;
+        } else {
+            self.step = 1.0 / frames;
+        }
+    }
+
+    /// Returns true once the output has faded out completely.
+    pub fn is_silent(&self) -> bool {
+        self.gain == 0.0 && self.target == 0.0
+    }
+
+    /// Apply the gain to `buffer`, stepping it once per frame.
+    pub fn apply(&mut self, buffer: &mut [Sample], channels: usize) {
+        if self.gain == 1.0 && self.target == 1.0 {
+            return;
+        }
+
+        for frame in buffer.chunks_exact_mut(channels) {
+            if self.gain < self.target {
+                self.gain = f32::min(self.gain + self.step, self.target);
+            } else if self.gain > self.target {
+                self.gain = f32::max(self.gain - self.step, self.target);
+            }
+            frame.iter_mut().for_each(|s| *s *= self.gain);
+        }
+    }
+}
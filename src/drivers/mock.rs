@@ -0,0 +1,224 @@
+use crate::{
+    drivers::{
+        clipper,
+        crossfade::Outgoing,
+        fader::Fader,
+        underrun::{Concealer, UnderrunStrategy},
+    },
+    AudioFormat, Sample, SharedAudioSource, StreamState,
+};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// A sink that pulls audio on demand and records it, without any hardware.
+///
+/// `MockDriver` is the testing counterpart to
+/// [`Sdl2Output`](crate::drivers::Sdl2Output). Instead of a device pulling
+/// blocks on its own schedule, each call to [`pump`](MockDriver::pump) reads a
+/// fixed number of fixed-size blocks from the source, exactly as a device
+/// callback would: missing samples are filled according to the
+/// [`UnderrunStrategy`](crate::drivers::UnderrunStrategy), and a missing
+/// source plays silence. Everything read is appended to the recorded output,
+/// so time-based effects can be tested deterministically.
+///
+/// Only available with the `testing` feature.
+///
+/// # Examples
+/// ```
+/// # use timbre::{drivers::MockDriver, generators::SineWave, AudioFormat, IntoShared};
+/// let mut driver = MockDriver::new(AudioFormat::STEREO_CD, 512);
+/// driver.set_source(SineWave::new(0.5, 440.0).into_shared());
+///
+/// driver.pump(4);
+/// assert_eq!(driver.output().len(), 4 * 512 * 2);
+/// assert!(driver.output().iter().all(|s| s.abs() <= 0.5));
+/// ```
+pub struct MockDriver {
+    format: AudioFormat,
+    block_frames: usize,
+    source: Option<SharedAudioSource>,
+    outgoing: Option<Outgoing>,
+    fader: Fader,
+    concealer: Concealer,
+    output_clipper: bool,
+    output: Vec<Sample>,
+    underruns: u64,
+    disconnected: bool,
+}
+
+impl MockDriver {
+    /// Construct a `MockDriver`.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format of the simulated device. Sources should produce this format.
+    /// * `block_frames` -- The number of frames in each block, like a device's buffer size.
+    ///
+    /// # Panics
+    ///
+    /// If `block_frames` is zero.
+    pub fn new(format: AudioFormat, block_frames: usize) -> Self {
+        assert!(block_frames > 0, "Blocks must contain at least one frame.");
+        MockDriver {
+            format,
+            block_frames,
+            source: None,
+            outgoing: None,
+            fader: Fader::new(),
+            concealer: Concealer::new(UnderrunStrategy::default()),
+            output_clipper: false,
+            output: Vec::new(),
+            underruns: 0,
+            disconnected: false,
+        }
+    }
+
+    /// Set the source of audio to output.
+    ///
+    /// This cuts instantly, including from a source being crossfaded out.
+    pub fn set_source(&mut self, source: SharedAudioSource) {
+        self.source = Some(source);
+        self.outgoing = None;
+    }
+
+    /// Switch to a new source, crossfading from the current one over `duration`.
+    ///
+    /// This matches
+    /// [`Sdl2Output::crossfade_to_source`](crate::drivers::Sdl2Output::crossfade_to_source):
+    /// the old source keeps being read, ramping down while the new one ramps
+    /// up, until the fade completes.
+    pub fn crossfade_to_source(&mut self, source: SharedAudioSource, duration: Duration) {
+        let old = self.source.replace(source);
+        self.outgoing = old.and_then(|old| Outgoing::new(old, duration, self.format));
+    }
+
+    /// Remove the source of audio, so that silence is output.
+    ///
+    /// Any source being crossfaded out is removed too.
+    pub fn clear_source(&mut self) {
+        self.source = None;
+        self.outgoing = None;
+    }
+
+    /// Set how to fill the output when the source underruns.
+    pub fn set_underrun_strategy(&mut self, strategy: UnderrunStrategy) {
+        self.concealer.strategy = strategy;
+    }
+
+    pub fn underrun_strategy(&self) -> UnderrunStrategy {
+        self.concealer.strategy
+    }
+
+    /// Set whether to softly saturate the output, as
+    /// [`Sdl2Output::set_output_clipper`](crate::drivers::Sdl2Output::set_output_clipper) does.
+    pub fn set_output_clipper(&mut self, enabled: bool) {
+        self.output_clipper = enabled;
+    }
+
+    pub fn output_clipper(&self) -> bool {
+        self.output_clipper
+    }
+
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    pub fn block_frames(&self) -> usize {
+        self.block_frames
+    }
+
+    /// Fade out over `duration`, after which silence is output and the source
+    /// is no longer read, as
+    /// [`Sdl2Output::pause_with_fade`](crate::drivers::Sdl2Output::pause_with_fade) does.
+    pub fn pause_with_fade(&mut self, duration: Duration) {
+        self.fader.fade_to(0.0, duration, self.format);
+    }
+
+    /// Fade back in to full volume over `duration`, as
+    /// [`Sdl2Output::resume_with_fade`](crate::drivers::Sdl2Output::resume_with_fade) does.
+    pub fn resume_with_fade(&mut self, duration: Duration) {
+        self.fader.fade_to(1.0, duration, self.format);
+    }
+
+    /// Simulate the device being lost, e.g. because it was unplugged.
+    ///
+    /// Like a disconnected [`Sdl2Output`](crate::drivers::Sdl2Output), the
+    /// driver then stops pulling audio, so [`pump`](MockDriver::pump) records
+    /// nothing and the source isn't read, until [`reopen`](MockDriver::reopen).
+    pub fn disconnect(&mut self) {
+        self.disconnected = true;
+    }
+
+    /// Returns true if the device has been lost; see [`disconnect`](MockDriver::disconnect).
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected
+    }
+
+    /// Open the device again, keeping the current source, as
+    /// [`Sdl2Output::reopen`](crate::drivers::Sdl2Output::reopen) does.
+    ///
+    /// The underrun strategy, output clipper and statistics carry over, but
+    /// a crossfade in progress is cut off.
+    pub fn reopen(&mut self) {
+        self.disconnected = false;
+        self.outgoing = None;
+        self.concealer = Concealer::new(self.concealer.strategy);
+    }
+
+    /// Read `blocks` blocks from the source, appending them to the recorded output.
+    #[instrument(name = "MockDriver::pump", skip(self))]
+    pub fn pump(&mut self, blocks: usize) {
+        let channels = self.format.channels as usize;
+        let block = self.block_frames * channels;
+        if self.disconnected {
+            return;
+        }
+
+        for _ in 0..blocks {
+            let start = self.output.len();
+            self.output.resize(start + block, 0.0);
+            let buffer = &mut self.output[start..];
+            if self.fader.is_silent() {
+                // Faded out, so behave as if paused.
+                continue;
+            }
+
+            if let Some(source) = &self.source {
+                let result = source.lock().unwrap().read(buffer);
+                if result.state == StreamState::Underrun {
+                    self.underruns += 1;
+                }
+                self.concealer
+                    .fill(buffer, result.read, result.state, channels);
+            }
+
+            if let Some(outgoing) = &mut self.outgoing {
+                if outgoing.mix_into(buffer, channels) {
+                    self.outgoing = None;
+                }
+            }
+
+            self.fader.apply(buffer, channels);
+            if self.output_clipper {
+                clipper::soft_clip(buffer);
+            }
+        }
+    }
+
+    /// Returns everything output so far.
+    pub fn output(&self) -> &[Sample] {
+        &self.output
+    }
+
+    /// Returns everything output so far, clearing the recording.
+    pub fn take_output(&mut self) -> Vec<Sample> {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Returns the number of blocks in which the source underran.
+    pub fn underruns(&self) -> u64 {
+        self.underruns
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    drivers::underrun::{Concealer, UnderrunStrategy},
+    AudioFormat, Sample, SharedAudioSource, StreamState,
+};
+
+use tracing::instrument;
+
+/// An output that is driven by its host, for platforms without SDL.
+///
+/// Where [`Sdl2Output`](crate::drivers::Sdl2Output) owns a device that asks
+/// for audio on its own schedule, `PullOutput` leaves scheduling to its host:
+/// call [`process`](PullOutput::process) from whatever callback the platform
+/// provides, such as the `process` method of a Web Audio `AudioWorklet`. Each
+/// call fills the buffer from the source the same way the SDL callback does:
+/// missing samples are filled according to the
+/// [`UnderrunStrategy`](crate::drivers::UnderrunStrategy), and a missing
+/// source plays silence.
+///
+/// `PullOutput` doesn't depend on SDL, so it's available without the `sdl2` feature.
+///
+/// # Examples
+/// ```
+/// # use timbre::{drivers::PullOutput, generators::SineWave, AudioFormat, AudioSource, IntoShared};
+/// let mut output = PullOutput::new(AudioFormat::STEREO_DVD);
+/// output.set_source(SineWave::with_format(AudioFormat::STEREO_DVD, 0.5, 440.0).into_shared());
+///
+/// // The host calls process with its own buffers.
+/// let mut block = vec![0.0; 128 * 2];
+/// output.process(&mut block);
+///
+/// let mut expected = vec![0.0; 128 * 2];
+/// SineWave::with_format(AudioFormat::STEREO_DVD, 0.5, 440.0).read(&mut expected);
+/// assert_eq!(block, expected);
+/// ```
+pub struct PullOutput {
+    format: AudioFormat,
+    source: Option<SharedAudioSource>,
+    concealer: Concealer,
+    underruns: u64,
+}
+
+impl PullOutput {
+    /// Construct a `PullOutput`.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format the host expects. Sources should produce this format.
+    pub fn new(format: AudioFormat) -> Self {
+        PullOutput {
+            format,
+            source: None,
+            concealer: Concealer::new(UnderrunStrategy::default()),
+            underruns: 0,
+        }
+    }
+
+    /// Set the source of audio to output.
+    pub fn set_source(&mut self, source: SharedAudioSource) {
+        self.source = Some(source);
+    }
+
+    /// Remove the source of audio, so that silence is output.
+    pub fn clear_source(&mut self) {
+        self.source = None;
+    }
+
+    /// Set how to fill the output when the source underruns.
+    pub fn set_underrun_strategy(&mut self, strategy: UnderrunStrategy) {
+        self.concealer.strategy = strategy;
+    }
+
+    pub fn underrun_strategy(&self) -> UnderrunStrategy {
+        self.concealer.strategy
+    }
+
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    /// Returns the number of calls to [`process`](PullOutput::process) in which
+    /// the source underran.
+    pub fn underruns(&self) -> u64 {
+        self.underruns
+    }
+
+    /// Fill `out` with audio from the source.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` -- The host's buffer of interleaved samples, in the output's format.
+    ///
+    /// # Panics
+    ///
+    /// If `out.len()` is not a multiple of the number of channels.
+    #[instrument(name = "PullOutput::process", skip(self, out))]
+    pub fn process(&mut self, out: &mut [Sample]) {
+        let channels = self.format.channels as usize;
+        assert!(
+            out.len().is_multiple_of(channels),
+            "The buffer must hold a whole number of frames."
+        );
+
+        match &self.source {
+            Some(source) => {
+                let result = source.lock().unwrap().read(out);
+                if result.state == StreamState::Underrun {
+                    self.underruns += 1;
+                }
+                self.concealer
+                    .fill(out, result.read, result.state, channels);
+            }
+            None => out.iter_mut().for_each(|sample| *sample = 0.0),
+        }
+    }
+}
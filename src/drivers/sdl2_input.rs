@@ -1,6 +1,5 @@
-use crate::{core::SharedAudioSource, AudioFormat, AudioSource, Error, ReadResult, Sample};
+use crate::{core::SharedAudioSource, ring_buffer, AudioFormat, Error, Producer};
 
-use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
 use sdl2::audio::{AudioCallback, AudioSpecDesired};
@@ -26,24 +25,86 @@ use tracing::{info, instrument};
 /// ```
 pub struct Sdl2Input {
     device: sdl2::audio::AudioDevice<Callback>,
-    buffer: Arc<Mutex<VecDeque<f32>>>,
+    source: SharedAudioSource,
+    format: AudioFormat,
 }
 
-struct Callback {
-    pub format: AudioFormat,
-    pub buffer: Arc<Mutex<VecDeque<f32>>>,
+/// The most captured frames buffered until they're read, about three seconds
+/// at 44.1 kHz. Anything captured while the buffer is full is dropped.
+const CAPTURE_BUFFER_FRAMES: usize = 1 << 17;
+
+/// A capture device reported by SDL.
+///
+/// See [`Sdl2Input::capture_devices`](crate::drivers::Sdl2Input::capture_devices).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CaptureDevice {
+    /// The name SDL uses for the device.
+    pub name: String,
+    /// True if the device captures what's being played, rather than a
+    /// microphone or line input, e.g. a PulseAudio monitor or "Stereo Mix".
+    pub is_loopback: bool,
 }
 
-struct AudioSourceImpl {
-    pub format: AudioFormat,
-    pub buffer: Arc<Mutex<VecDeque<f32>>>,
+impl CaptureDevice {
+    /// Describe the capture device with the given name, deciding from the
+    /// name whether it's a loopback device.
+    ///
+    /// SDL doesn't report this directly, so this relies on the names platforms
+    /// give these devices: PulseAudio and PipeWire name them "Monitor of ..."
+    /// or "... .monitor", and Windows drivers often call them "Stereo Mix" or
+    /// "What U Hear".
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::drivers::CaptureDevice;
+    /// let loopback = [
+    ///     "Monitor of Built-in Audio Analog Stereo",
+    ///     "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor",
+    ///     "Stereo Mix (Realtek High Definition Audio)",
+    ///     "What U Hear (Sound Blaster Audigy)",
+    ///     "Loopback Audio",
+    /// ];
+    /// for name in loopback.iter() {
+    ///     assert!(CaptureDevice::new(*name).is_loopback, "{}", name);
+    /// }
+    ///
+    /// let microphones = [
+    ///     "Built-in Audio Analog Stereo",
+    ///     "Microphone (Realtek High Definition Audio)",
+    ///     "MacBook Pro Microphone",
+    ///     "USB PnP Sound Device Mono",
+    /// ];
+    /// for name in microphones.iter() {
+    ///     assert!(!CaptureDevice::new(*name).is_loopback, "{}", name);
+    /// }
+    /// ```
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        CaptureDevice {
+            is_loopback: is_loopback_name(&name),
+            name,
+        }
+    }
+}
+
+/// Returns true if a capture device's name suggests it records the output.
+fn is_loopback_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["monitor of", "loopback", "stereo mix", "what u hear"]
+        .iter()
+        .any(|pattern| name.contains(pattern))
+        || name.ends_with(".monitor")
+}
+
+struct Callback {
+    pub producer: Producer,
 }
 
 impl AudioCallback for Callback {
     type Channel = f32;
     #[instrument(name = "Sdl2Input::callback", skip(self, samples))]
     fn callback(&mut self, samples: &mut [Self::Channel]) {
-        self.buffer.lock().unwrap().extend(samples.iter().cloned());
+        self.producer.push(samples);
     }
 }
 
@@ -105,6 +166,90 @@ impl Sdl2Input {
     pub fn with_format(
         subsystem: &sdl2::AudioSubsystem,
         format: AudioFormat,
+    ) -> Result<Self, Error> {
+        Sdl2Input::open(subsystem, None, format)
+    }
+
+    /// List the capture devices SDL knows about.
+    ///
+    /// Some drivers can't list their devices, in which case this is empty.
+    ///
+    /// # Errors
+    ///
+    /// If SDL fails to get the name of a device.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::drivers::Sdl2Input;
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let devices = Sdl2Input::capture_devices(&audio)?;
+    /// for device in &devices {
+    ///     println!("{} (loopback: {})", device.name, device.is_loopback);
+    /// }
+    ///
+    /// // A loopback device can be opened exactly when one is flagged.
+    /// let has_loopback = devices.iter().any(|device| device.is_loopback);
+    /// assert_eq!(Sdl2Input::loopback(&audio).is_ok(), has_loopback);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn capture_devices(subsystem: &sdl2::AudioSubsystem) -> Result<Vec<CaptureDevice>, Error> {
+        let count = subsystem.num_audio_capture_devices().unwrap_or(0);
+        (0..count)
+            .map(|index| {
+                let name = subsystem
+                    .audio_capture_device_name(index)
+                    .map_err(Error::from_sdl)?;
+                Ok(CaptureDevice::new(name))
+            })
+            .collect()
+    }
+
+    /// Construct an `Sdl2Input` that captures what's being played, with the default format.
+    ///
+    /// This opens the first capture device that looks like a loopback device,
+    /// such as the monitor of the default output under PulseAudio, so that
+    /// system audio can be recorded or processed.
+    ///
+    /// # Errors
+    ///
+    /// [`DeviceNotFound`](crate::Error::DeviceNotFound) if there's no loopback
+    /// device, which is common on platforms other than Linux, or an SDL error
+    /// if it fails to open.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Input, Error};
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// match Sdl2Input::loopback(&audio) {
+    ///     Ok(mut system_audio) => system_audio.resume(),
+    ///     Err(Error::DeviceNotFound(_)) => println!("Can't record system audio here."),
+    ///     Err(error) => return Err(error.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn loopback(subsystem: &sdl2::AudioSubsystem) -> Result<Self, Error> {
+        let device = Sdl2Input::capture_devices(subsystem)?
+            .into_iter()
+            .find(|device| device.is_loopback)
+            .ok_or_else(|| Error::DeviceNotFound("No loopback capture device.".to_string()))?;
+        info!("Opening loopback device {:?}.", device.name);
+        Sdl2Input::open(subsystem, Some(&device.name), AudioFormat::default())
+    }
+
+    fn open(
+        subsystem: &sdl2::AudioSubsystem,
+        device: Option<&str>,
+        format: AudioFormat,
     ) -> Result<Self, Error> {
         let desired_spec = AudioSpecDesired {
             freq: Some(format.sample_rate as i32),
@@ -112,20 +257,24 @@ impl Sdl2Input {
             samples: Some(1024),
         };
 
-        let buffer = Arc::new(Mutex::new(VecDeque::new()));
-
+        // The ring buffer needs the format SDL chooses, so it's created in the callback.
+        let mut source = None;
         let device = subsystem
-            .open_capture(None, &desired_spec, |spec| {
+            .open_capture(device, &desired_spec, |spec| {
                 info!("Input Spec: {:?}", spec);
 
-                Callback {
-                    buffer: buffer.clone(),
-                    format: spec.into(),
-                }
+                let (producer, consumer) = ring_buffer(CAPTURE_BUFFER_FRAMES, spec.into());
+                source = Some(consumer);
+                Callback { producer }
             })
             .map_err(Error::from_sdl)?;
+        let format = (*device.spec()).into();
 
-        Ok(Sdl2Input { device, buffer })
+        Ok(Sdl2Input {
+            device,
+            source: Arc::new(Mutex::new(source.unwrap())),
+            format,
+        })
     }
 
     /// Return the device's chosen format.
@@ -133,19 +282,19 @@ impl Sdl2Input {
     /// # Returns
     ///
     /// The format SDL chose for this input device, which may be different from the requested format.
-    pub fn format(&mut self) -> AudioFormat {
-        self.device.lock().format
+    /// It is fixed when the device is opened, so this doesn't need to lock the device.
+    pub fn format(&self) -> AudioFormat {
+        self.format
     }
 
     /// Get an AudioSource impl that reads from this input device.
     ///
     /// All AudioSource implementations returned by this method consume the same
-    /// buffer, so you probably only want one.
+    /// buffer, so you probably only want one. The device writes to the buffer
+    /// without locking, so reading the source never holds up the capture
+    /// callback.
     pub fn source(&mut self) -> SharedAudioSource {
-        Arc::new(Mutex::new(AudioSourceImpl {
-            buffer: Arc::clone(&self.buffer),
-            format: self.device.lock().format,
-        }))
+        Arc::clone(&self.source)
     }
 
     /// Start/resume this input device.
@@ -164,27 +313,3 @@ impl Sdl2Input {
         self.device.pause();
     }
 }
-
-impl AudioSource for AudioSourceImpl {
-    fn format(&self) -> AudioFormat {
-        self.format
-    }
-
-    #[instrument(name = "Sdl2Input::read", skip(self, buffer))]
-    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
-        let samples = buffer;
-        let mut buffer = self.buffer.lock().unwrap();
-
-        let mut i: usize = 0;
-        while i < samples.len() {
-            if let Some(sample) = buffer.pop_front() {
-                samples[i] = sample;
-            } else {
-                return ReadResult::underrun(i);
-            }
-            i += 1;
-        }
-
-        ReadResult::good(samples.len())
-    }
-}
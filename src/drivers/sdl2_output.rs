@@ -1,35 +1,124 @@
-use crate::{core::SharedAudioSource, AudioFormat, Error, StreamState};
+use crate::{
+    core::SharedAudioSource,
+    drivers::{
+        adapt, clipper,
+        crossfade::Outgoing,
+        fader::Fader,
+        underrun::{Concealer, UnderrunStrategy},
+    },
+    AudioFormat, Error, StreamState,
+};
 
-use sdl2::audio::{AudioCallback, AudioFormatNum, AudioSpecDesired};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
+use sdl2::audio::{AudioCallback, AudioFormatNum, AudioSpecDesired, AudioStatus};
 use tracing::{info, instrument, warn};
 
 struct Callback {
     pub format: AudioFormat,
     pub source: Option<SharedAudioSource>,
+    pub outgoing: Option<Outgoing>,
+    pub concealer: Concealer,
+    pub fader: Fader,
+    pub clipped: Arc<AtomicU64>,
+    pub output_clipper: bool,
+    pub last_clip_warning: Option<Instant>,
+    pub underruns: Arc<AtomicU64>,
+    pub underrun_samples: Arc<AtomicU64>,
+    pub last_underrun_warning: Option<Instant>,
+}
+
+/// Statistics about underruns in an [`Sdl2Output`](crate::drivers::Sdl2Output).
+///
+/// An underrun occurs when the source can't provide enough data to fill the
+/// device's buffer, which is heard as a glitch.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct UnderrunStats {
+    /// The number of times the source underran.
+    pub underruns: u64,
+    /// The total number of samples that were filled in because of underruns.
+    pub samples: u64,
+}
+
+/// Returns true at most once per second, to keep warnings from flooding the log.
+fn should_warn(last_warning: &mut Option<Instant>) -> bool {
+    let now = Instant::now();
+    if let Some(last) = *last_warning {
+        if now.duration_since(last) < Duration::from_secs(1) {
+            return false;
+        }
+    }
+
+    *last_warning = Some(now);
+    true
+}
+
+impl Callback {
+    fn detect_clipping(&mut self, buffer: &[f32]) {
+        let clipped = buffer.iter().filter(|s| s.abs() > 1.0).count();
+        if clipped == 0 {
+            return;
+        }
+
+        self.clipped.fetch_add(clipped as u64, Ordering::Relaxed);
+
+        if should_warn(&mut self.last_clip_warning) {
+            warn!("Clipping detected: {} samples out of range.", clipped);
+        }
+    }
 }
 
 impl AudioCallback for Callback {
     type Channel = f32;
     #[instrument(name = "Sdl2Output::callback", skip(self, buffer))]
     fn callback(&mut self, buffer: &mut [Self::Channel]) {
+        if self.fader.is_silent() {
+            // Faded out, so behave as if paused.
+            for sample in buffer.iter_mut() {
+                *sample = AudioFormatNum::SILENCE;
+            }
+            return;
+        }
+
         if let Some(source) = &self.source {
             let mut source = source.lock().unwrap();
 
             let result = source.read(buffer);
 
             if result.state == StreamState::Underrun {
-                warn!("Underrun detected.");
+                let missing = (buffer.len() - result.read) as u64;
+                self.underruns.fetch_add(1, Ordering::Relaxed);
+                self.underrun_samples.fetch_add(missing, Ordering::Relaxed);
+
+                if should_warn(&mut self.last_underrun_warning) {
+                    warn!("Underrun detected.");
+                }
             }
 
-            buffer
-                .iter_mut()
-                .skip(result.read)
-                .for_each(|s| *s = AudioFormatNum::SILENCE);
+            let channels = self.format.channels as usize;
+            self.concealer
+                .fill(buffer, result.read, result.state, channels);
         } else {
             for sample in buffer.iter_mut() {
                 *sample = AudioFormatNum::SILENCE;
             }
         }
+
+        if let Some(outgoing) = &mut self.outgoing {
+            if outgoing.mix_into(buffer, self.format.channels as usize) {
+                self.outgoing = None;
+            }
+        }
+
+        self.fader.apply(buffer, self.format.channels as usize);
+        self.detect_clipping(buffer);
+        if self.output_clipper {
+            clipper::soft_clip(buffer);
+        }
     }
 }
 
@@ -53,6 +142,16 @@ impl AudioCallback for Callback {
 /// ```
 pub struct Sdl2Output {
     device: sdl2::audio::AudioDevice<Callback>,
+    format: AudioFormat,
+    requested: AudioFormat,
+    source: Option<SharedAudioSource>,
+    auto_adapt: bool,
+    underrun_strategy: UnderrunStrategy,
+    output_clipper: bool,
+    playing: bool,
+    clipped: Arc<AtomicU64>,
+    underruns: Arc<AtomicU64>,
+    underrun_samples: Arc<AtomicU64>,
 }
 
 impl Sdl2Output {
@@ -113,48 +212,494 @@ impl Sdl2Output {
         subsystem: &sdl2::AudioSubsystem,
         format: AudioFormat,
     ) -> Result<Self, Error> {
-        let desired_spec = AudioSpecDesired {
-            freq: Some(format.sample_rate as i32),
-            channels: Some(format.channels),
-            samples: Some(1024),
-        };
-
-        let device = subsystem
-            .open_playback(None, &desired_spec, |spec| {
-                info!("Output Spec: {:?}", spec);
-
-                Callback {
-                    format: spec.into(),
-                    source: None,
-                }
-            })
-            .map_err(Error::from_sdl)?;
+        let clipped = Arc::new(AtomicU64::new(0));
+        let underruns = Arc::new(AtomicU64::new(0));
+        let underrun_samples = Arc::new(AtomicU64::new(0));
+
+        let device = open(subsystem, format, &clipped, &underruns, &underrun_samples)?;
 
-        Ok(Sdl2Output { device })
+        Ok(Sdl2Output {
+            format: (*device.spec()).into(),
+            device,
+            requested: format,
+            source: None,
+            auto_adapt: false,
+            underrun_strategy: UnderrunStrategy::default(),
+            output_clipper: false,
+            playing: false,
+            clipped,
+            underruns,
+            underrun_samples,
+        })
+    }
+
+    /// Returns true if the device is playing.
+    ///
+    /// This is false while paused, and also if the device has been
+    /// disconnected; see [`is_disconnected`](Sdl2Output::is_disconnected).
+    pub fn is_running(&self) -> bool {
+        self.device.status() == AudioStatus::Playing
+    }
+
+    /// Returns true if the device has been lost, e.g. because it was unplugged.
+    ///
+    /// SDL stops a device when it is disconnected, and it never recovers. SDL
+    /// also sends an [`AudioDeviceRemoved`](sdl2::event::Event::AudioDeviceRemoved)
+    /// event, which is a good time to check this and call
+    /// [`reopen`](Sdl2Output::reopen).
+    pub fn is_disconnected(&self) -> bool {
+        self.device.status() == AudioStatus::Stopped
+    }
+
+    /// Open the default device again, keeping the current source.
+    ///
+    /// This is used to recover after the device is disconnected. The format
+    /// originally requested is requested again, but the new device may choose
+    /// a different one. Playback resumes if the device was playing before, and
+    /// statistics such as [`underrun_stats`](Sdl2Output::underrun_stats) carry over.
+    ///
+    /// # Errors
+    ///
+    /// If SDL fails to open the device. The old device is kept in that case.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Output, generators::SineWave, IntoShared};
+    /// # use sdl2::event::Event;
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let mut speaker = Sdl2Output::new(&audio)?;
+    /// speaker.set_source(SineWave::new(0.5, 440.0).into_shared());
+    /// assert!(!speaker.is_running());
+    /// speaker.resume();
+    /// assert!(speaker.is_running());
+    /// assert!(!speaker.is_disconnected());
+    ///
+    /// // In the event loop, recover when the output device goes away.
+    /// for event in sdl.event_pump()?.poll_iter() {
+    ///     if let Event::AudioDeviceRemoved { iscapture: false, .. } = event {
+    ///         if speaker.is_disconnected() {
+    ///             speaker.reopen(&audio)?;
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// // Reopening keeps playing the same source.
+    /// speaker.reopen(&audio)?;
+    /// assert!(speaker.is_running());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reopen(&mut self, subsystem: &sdl2::AudioSubsystem) -> Result<(), Error> {
+        let device = open(
+            subsystem,
+            self.requested,
+            &self.clipped,
+            &self.underruns,
+            &self.underrun_samples,
+        )?;
+        info!("Reopened output device.");
+
+        let old = std::mem::replace(&mut self.device, device);
+        let callback = old.close_and_get_callback();
+        let format: AudioFormat = (*self.device.spec()).into();
+        let changed = format != self.format;
+        self.format = format;
+        let mut lock = self.device.lock();
+        lock.source = callback.source;
+        lock.concealer.strategy = self.underrun_strategy;
+        lock.output_clipper = self.output_clipper;
+        drop(lock);
+
+        // The new device may have chosen a different format.
+        if self.auto_adapt && changed {
+            self.apply_source();
+        }
+
+        if self.playing {
+            self.device.resume();
+        }
+        Ok(())
     }
 
     /// Set the source of audio to output.
+    ///
+    /// The source should produce the device's [`format`](Sdl2Output::format),
+    /// unless [`set_auto_adapt`](Sdl2Output::set_auto_adapt) is enabled. This
+    /// cuts instantly, including from a source being crossfaded out.
     pub fn set_source(&mut self, source: SharedAudioSource) {
-        self.device.lock().source = Some(source);
+        self.source = Some(source);
+        self.apply_source();
+    }
+
+    /// Switch to a new source, crossfading from the current one over `duration`.
+    ///
+    /// [`set_source`](Sdl2Output::set_source) cuts instantly, which clicks if
+    /// both sources are mid-signal. This instead keeps reading the old source
+    /// inside the callback, ramping it down while the new source ramps up, so
+    /// that e.g. switching tracks in a player is glitch-free. The old source is
+    /// dropped once the fade completes. If a crossfade is already in progress,
+    /// the source it was fading out is cut off.
+    ///
+    /// If there is no current source, the new source starts immediately.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Output, generators::SineWave, IntoShared};
+    /// # use std::time::Duration;
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let mut speaker = Sdl2Output::new(&audio)?;
+    /// speaker.set_source(SineWave::new(0.5, 440.0).into_shared());
+    /// speaker.resume();
+    /// speaker.crossfade_to_source(SineWave::new(0.5, 660.0).into_shared(), Duration::from_millis(50));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn crossfade_to_source(&mut self, source: SharedAudioSource, duration: Duration) {
+        self.source = Some(source);
+        let source = self.adapted_source();
+        let format = self.format;
+        let mut callback = self.device.lock();
+        let old = std::mem::replace(&mut callback.source, source);
+        callback.outgoing = old.and_then(|old| Outgoing::new(old, duration, format));
+    }
+
+    /// Set whether to convert sources that don't match the device's format.
+    ///
+    /// The device may not choose the format that was requested, e.g. it may
+    /// run at 48 kHz when 44.1 kHz was asked for. When enabled, sources with a
+    /// different sample rate or number of channels are converted with
+    /// [`adapt`](crate::drivers::adapt), including the current source and
+    /// the source after a [`reopen`](Sdl2Output::reopen). This is disabled by
+    /// default.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Output, generators::SineWave, AudioFormat, IntoShared};
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let mut speaker = Sdl2Output::with_format(&audio, AudioFormat::STEREO_DVD)?;
+    /// speaker.set_auto_adapt(true);
+    /// assert!(speaker.auto_adapt());
+    ///
+    /// // Whatever rate the device chose, the tone plays at 440 Hz.
+    /// let source = SineWave::with_format(AudioFormat::MONO_CD, 0.5, 440.0);
+    /// speaker.set_source(source.into_shared());
+    /// speaker.resume();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_auto_adapt(&mut self, auto_adapt: bool) {
+        self.auto_adapt = auto_adapt;
+        self.apply_source();
+    }
+
+    pub fn auto_adapt(&self) -> bool {
+        self.auto_adapt
+    }
+
+    /// Hand the source to the callback, adapting it if enabled, and cut off
+    /// any crossfade in progress.
+    fn apply_source(&mut self) {
+        let source = self.adapted_source();
+        let mut callback = self.device.lock();
+        callback.source = source;
+        callback.outgoing = None;
+    }
+
+    /// Returns the source, adapted to the device's format if enabled.
+    fn adapted_source(&self) -> Option<SharedAudioSource> {
+        match &self.source {
+            Some(source) if self.auto_adapt => match adapt(Arc::clone(source), self.format) {
+                Ok(adapted) => Some(adapted),
+                Err(error) => {
+                    warn!("Can't adapt source to the output format: {}", error);
+                    Some(Arc::clone(source))
+                }
+            },
+            source => source.clone(),
+        }
+    }
+
+    /// Set how to fill the output when the source underruns.
+    ///
+    /// The default is [`UnderrunStrategy::Silence`](crate::drivers::UnderrunStrategy::Silence).
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::drivers::{Sdl2Output, UnderrunStrategy};
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let mut speaker = Sdl2Output::new(&audio)?;
+    /// speaker.set_underrun_strategy(UnderrunStrategy::HoldLast);
+    /// assert_eq!(speaker.underrun_strategy(), UnderrunStrategy::HoldLast);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_underrun_strategy(&mut self, strategy: UnderrunStrategy) {
+        self.underrun_strategy = strategy;
+        self.device.lock().concealer.strategy = strategy;
+    }
+
+    pub fn underrun_strategy(&self) -> UnderrunStrategy {
+        self.underrun_strategy
+    }
+
+    /// Set whether to softly saturate the output just before it reaches the device.
+    ///
+    /// This is a last line of defense against rogue peaks: samples beyond
+    /// ±0.8 are bent smoothly toward ±1.0 instead of being hard clipped by the
+    /// hardware, which sounds much less harsh. Quieter samples are left
+    /// exactly as they are, and unlike a lookahead limiter, this adds no
+    /// latency. Samples beyond ±1.0 still count toward
+    /// [`clipped_samples`](Sdl2Output::clipped_samples), so the problem can be
+    /// found and fixed. This is disabled by default, and the output is then
+    /// bit-exact.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Output, generators::SineWave, IntoShared};
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let mut speaker = Sdl2Output::new(&audio)?;
+    /// speaker.set_output_clipper(true);
+    /// assert!(speaker.output_clipper());
+    /// speaker.set_source(SineWave::new(1.5, 440.0).into_shared());
+    /// speaker.resume();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_output_clipper(&mut self, enabled: bool) {
+        self.output_clipper = enabled;
+        self.device.lock().output_clipper = enabled;
+    }
+
+    pub fn output_clipper(&self) -> bool {
+        self.output_clipper
     }
 
     /// Get the driver's chosen audio format.
-    pub fn format(&mut self) -> AudioFormat {
-        self.device.lock().format
+    ///
+    /// The format is fixed when the device is opened, so this doesn't need to
+    /// lock the device.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Output, AudioFormat};
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// fn describe(speaker: &Sdl2Output) -> String {
+    ///     let format = speaker.format();
+    ///     format!("{} channels at {} Hz", format.channels, format.sample_rate)
+    /// }
+    ///
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let speaker = Sdl2Output::new(&audio)?;
+    /// assert!(!describe(&speaker).is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    /// Get the number of output samples that have exceeded the range [-1.0, 1.0].
+    ///
+    /// Samples outside this range are clipped by the hardware, which sounds harsh.
+    /// If this count is increasing, consider lowering the volume of the sources,
+    /// e.g. with a [`BasicMixer`](crate::effects::BasicMixer) coefficient.
+    ///
+    /// This is updated from the audio thread, so it may be called at any time.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Output, generators::SineWave, IntoShared};
+    /// # use std::time::{Duration, Instant};
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let mut speaker = Sdl2Output::new(&audio)?;
+    /// speaker.set_source(SineWave::new(2.0, 440.0).into_shared());
+    /// speaker.resume();
+    ///
+    /// let start = Instant::now();
+    /// while speaker.clipped_samples() == 0 && start.elapsed() < Duration::from_secs(5) {
+    ///     std::thread::sleep(Duration::from_millis(10));
+    /// }
+    /// assert!(speaker.clipped_samples() > 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clipped_samples(&self) -> u64 {
+        self.clipped.load(Ordering::Relaxed)
+    }
+
+    /// Get statistics about how often the source has underrun.
+    ///
+    /// This is updated from the audio thread, so it may be called at any time,
+    /// e.g. to display a glitch counter.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::drivers::{Sdl2Input, Sdl2Output};
+    /// # use std::time::{Duration, Instant};
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// // The microphone is never resumed, so it has no data and underruns.
+    /// let mut microphone = Sdl2Input::new(&audio)?;
+    /// let mut speaker = Sdl2Output::new(&audio)?;
+    /// speaker.set_source(microphone.source());
+    /// speaker.resume();
+    ///
+    /// let start = Instant::now();
+    /// while speaker.underrun_stats().underruns == 0 && start.elapsed() < Duration::from_secs(5) {
+    ///     std::thread::sleep(Duration::from_millis(10));
+    /// }
+    /// assert!(speaker.underrun_stats().underruns > 0);
+    /// assert!(speaker.underrun_stats().samples > 0);
+    ///
+    /// speaker.pause();
+    /// speaker.reset_stats();
+    /// assert_eq!(speaker.underrun_stats().underruns, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn underrun_stats(&self) -> UnderrunStats {
+        UnderrunStats {
+            underruns: self.underruns.load(Ordering::Relaxed),
+            samples: self.underrun_samples.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reset the underrun statistics and clipped sample count to zero.
+    pub fn reset_stats(&self) {
+        self.underruns.store(0, Ordering::Relaxed);
+        self.underrun_samples.store(0, Ordering::Relaxed);
+        self.clipped.store(0, Ordering::Relaxed);
     }
 
     /// Pause playback for this device.
     ///
     /// While paused, this device will not consume data from its source.
     pub fn pause(&mut self) {
+        self.playing = false;
         self.device.pause();
     }
 
+    /// Pause playback for this device after fading out.
+    ///
+    /// Stopping abruptly can cause an audible pop if the signal isn't near zero.
+    /// This instead ramps the volume down over `duration`, after which the device
+    /// outputs silence and no longer consumes data from its source.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use timbre::{drivers::Sdl2Output, generators::SineWave, IntoShared};
+    /// # use std::time::Duration;
+    /// # std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    /// let sdl = sdl2::init()?;
+    /// let audio = sdl.audio()?;
+    ///
+    /// let mut speaker = Sdl2Output::new(&audio)?;
+    /// speaker.set_source(SineWave::new(0.5, 440.0).into_shared());
+    /// speaker.resume_with_fade(Duration::from_millis(5));
+    /// speaker.pause_with_fade(Duration::from_millis(5));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pause_with_fade(&mut self, duration: Duration) {
+        self.playing = false;
+        let format = self.format;
+        self.device.lock().fader.fade_to(0.0, duration, format);
+    }
+
     /// Start/resume playback for this device.
     ///
     /// The device starts in the paused state, and must be resumed for
     /// playback from an audio source to begin.
     pub fn resume(&mut self) {
+        {
+            let mut callback = self.device.lock();
+            callback.fader.gain = 1.0;
+            callback.fader.target = 1.0;
+        }
+        self.playing = true;
+        self.device.resume();
+    }
+
+    /// Start/resume playback for this device, fading in over `duration`.
+    ///
+    /// If the device was paused, playback starts from silence and ramps up to
+    /// full volume, avoiding the pop an abrupt start can cause.
+    pub fn resume_with_fade(&mut self, duration: Duration) {
+        let playing = self.device.status() == AudioStatus::Playing;
+        let format = self.format;
+        {
+            let mut callback = self.device.lock();
+            if !playing {
+                callback.fader.gain = 0.0;
+            }
+            callback.fader.fade_to(1.0, duration, format);
+        }
+        self.playing = true;
         self.device.resume();
     }
 }
+
+fn open(
+    subsystem: &sdl2::AudioSubsystem,
+    format: AudioFormat,
+    clipped: &Arc<AtomicU64>,
+    underruns: &Arc<AtomicU64>,
+    underrun_samples: &Arc<AtomicU64>,
+) -> Result<sdl2::audio::AudioDevice<Callback>, Error> {
+    let desired_spec = AudioSpecDesired {
+        freq: Some(format.sample_rate as i32),
+        channels: Some(format.channels),
+        samples: Some(1024),
+    };
+
+    subsystem
+        .open_playback(None, &desired_spec, |spec| {
+            info!("Output Spec: {:?}", spec);
+
+            Callback {
+                format: spec.into(),
+                source: None,
+                outgoing: None,
+                concealer: Concealer::new(UnderrunStrategy::default()),
+                fader: Fader::new(),
+                clipped: Arc::clone(clipped),
+                output_clipper: false,
+                last_clip_warning: None,
+                underruns: Arc::clone(underruns),
+                underrun_samples: Arc::clone(underrun_samples),
+                last_underrun_warning: None,
+            }
+        })
+        .map_err(Error::from_sdl)
+}
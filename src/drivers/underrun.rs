@@ -0,0 +1,63 @@
+use crate::{Sample, StreamState};
+
+/// How an output fills the rest of its buffer when its source underruns.
+///
+/// # Examples
+/// ```
+/// # use timbre::drivers::UnderrunStrategy;
+/// assert_eq!(UnderrunStrategy::default(), UnderrunStrategy::Silence);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum UnderrunStrategy {
+    /// Fill with silence.
+    #[default]
+    Silence,
+    /// Repeat the last sample received on each channel.
+    ///
+    /// This avoids the click of dropping abruptly to zero, which can sound
+    /// less jarring for short glitches, e.g. in network audio.
+    HoldLast,
+}
+
+/// Fills in the samples a source failed to provide.
+pub(crate) struct Concealer {
+    pub strategy: UnderrunStrategy,
+    last: Vec<Sample>,
+}
+
+impl Concealer {
+    pub fn new(strategy: UnderrunStrategy) -> Self {
+        Concealer {
+            strategy,
+            last: Vec::new(),
+        }
+    }
+
+    /// Fill `buffer` after the first `read` samples, according to the strategy.
+    ///
+    /// Only underruns are concealed; once a source has finished, or if there is
+    /// no source, the rest of the buffer is silent.
+    pub fn fill(
+        &mut self,
+        buffer: &mut [Sample],
+        read: usize,
+        state: StreamState,
+        channels: usize,
+    ) {
+        self.last.resize(channels, 0.0);
+        if read >= channels {
+            let end = read - read % channels;
+            self.last.copy_from_slice(&buffer[end - channels..end]);
+        }
+
+        let missing = &mut buffer[read..];
+        if state == StreamState::Underrun && self.strategy == UnderrunStrategy::HoldLast {
+            let offset = read % channels;
+            for (i, sample) in missing.iter_mut().enumerate() {
+                *sample = self.last[(offset + i) % channels];
+            }
+        } else {
+            missing.iter_mut().for_each(|s| *s = 0.0);
+        }
+    }
+}
@@ -1,11 +1,88 @@
 //! Effects that transform or combine [`AudioSource`](crate::AudioSource)s.
 
+mod allpass;
+mod auto_wah;
+mod balance;
+mod band_pass;
 mod basic_mixer;
+mod bass_control;
+pub(crate) mod biquad;
+mod biquad_raw;
+mod bypass;
+mod channel_mapper;
+mod comb;
+mod crossfeed;
+pub(crate) mod crossover;
+mod downmix;
 mod echo;
+mod effect_chain;
+mod envelope;
+mod fade;
+mod gain;
+mod gain_automation;
+mod haas;
 mod high_pass;
+mod inspect;
+mod interpolator;
+mod invert;
+mod limiter;
+mod loop_region;
+mod looping;
 mod low_pass;
+mod meter;
+mod modulated_low_pass;
+mod multiband_compressor;
+mod oversample;
+mod pan;
+mod rechunk;
+mod resampler;
+mod reverb;
+mod shelf;
+pub(crate) mod smoothed;
+mod speed;
+mod tee;
+mod to_mono;
+mod to_stereo;
+mod treble_control;
 
+pub use allpass::Allpass;
+pub use auto_wah::AutoWah;
+pub use balance::Balance;
+pub use band_pass::BandPass;
 pub use basic_mixer::{BasicMixer, BasicMixerSource};
+pub use bass_control::BassControl;
+pub use biquad::Biquad;
+pub use biquad_raw::BiquadRaw;
+pub use bypass::{Bypass, DryTap};
+pub use channel_mapper::ChannelMapper;
+pub use comb::Comb;
+pub use crossfeed::Crossfeed;
+pub use crossover::Crossover;
+pub use downmix::Downmix;
 pub use echo::Echo;
+pub use effect_chain::EffectChain;
+pub use fade::Fade;
+pub use gain::Gain;
+pub use gain_automation::GainAutomation;
+pub use haas::{Haas, Side};
 pub use high_pass::HighPass;
+pub use inspect::Inspect;
+pub use interpolator::Quality;
+pub use invert::{ChannelSelection, Invert};
+pub use limiter::Limiter;
+pub use loop_region::LoopRegion;
+pub use looping::Looping;
 pub use low_pass::LowPass;
+pub use meter::{Meter, MeterHandle};
+pub use modulated_low_pass::ModulatedLowPass;
+pub use multiband_compressor::{CompressorSettings, MultibandCompressor};
+pub use oversample::{Oversample, Upsample};
+pub use pan::{Pan, PanLaw};
+pub use rechunk::Rechunk;
+pub use resampler::Resampler;
+pub use reverb::Reverb;
+pub use speed::Speed;
+pub use tee::Tee;
+pub use to_mono::ToMono;
+pub use to_stereo::ToStereo;
+pub use treble_control::TrebleControl;
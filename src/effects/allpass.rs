@@ -0,0 +1,114 @@
+use crate::{
+    core::AudioSource,
+    effects::comb::{decay_repetitions, delay_line_length, read_or_ring},
+    flush_denormal, Latency, ReadResult, Sample, Tail,
+};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// A Schroeder allpass filter, used to diffuse echoes in reverbs.
+///
+/// Like a [`Comb`](crate::effects::Comb), this repeats its input at intervals
+/// of `delay`, but it also subtracts a scaled copy of the input so that every
+/// frequency passes with the same gain. It smears transients in time without
+/// coloring the sound.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Allpass, Comb}, generators::SineWave, AudioSource, IntoShared};
+/// # use std::time::Duration;
+/// // Feed a comb through a couple of allpasses, as in a Schroeder reverb.
+/// let sin = SineWave::new(0.5, 440.0);
+/// let comb = Comb::new(sin, Duration::from_secs_f32(0.0297), 0.77);
+/// let allpass = Allpass::new(comb, Duration::from_secs_f32(0.005), 0.7);
+/// let mut allpass = Allpass::new(allpass, Duration::from_secs_f32(0.0017), 0.7);
+///
+/// let mut samples = vec![0.0; 1024];
+/// allpass.read(&mut samples);
+/// ```
+pub struct Allpass<S: AudioSource> {
+    source: S,
+    delay: Duration,
+    feedback: f32,
+    buffer: Vec<f32>,
+    position: usize,
+    finished: bool,
+}
+
+impl<S: AudioSource> Allpass<S> {
+    /// Construct an allpass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `delay` -- The spacing between repetitions, rounded to the nearest frame.
+    /// * `feedback` -- The gain applied on each repetition. Should be between -1.0 and 1.0 to stay stable.
+    ///
+    /// # Panics
+    ///
+    /// If `delay` is shorter than one frame.
+    pub fn new(source: S, delay: Duration, feedback: f32) -> Self {
+        let length = delay_line_length(&source, delay);
+        Allpass {
+            source,
+            delay,
+            feedback,
+            buffer: vec![0.0; length],
+            position: 0,
+            finished: false,
+        }
+    }
+
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback;
+    }
+
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+impl<S: AudioSource> AudioSource for Allpass<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Allpass::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = read_or_ring(&mut self.source, &mut self.finished, buffer);
+
+        for sample in buffer[..result.read].iter_mut() {
+            let delayed = self.buffer[self.position];
+            let input = flush_denormal(*sample + self.feedback * delayed);
+            *sample = delayed - self.feedback * input;
+            self.buffer[self.position] = input;
+            self.position = (self.position + 1) % self.buffer.len();
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Allpass<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Allpass<S> {
+    /// The input comes out once more after `delay`, then repeats until it
+    /// decays below -60 dB, or forever if `feedback` is 1.0 or more in magnitude.
+    fn tail_frames(&self) -> u64 {
+        let delay = (self.buffer.len() / self.source.format().channels as usize) as u64;
+        let repetitions = decay_repetitions(self.feedback).saturating_add(1);
+        delay
+            .saturating_mul(repetitions)
+            .saturating_add(self.source.tail_frames())
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    core::AudioSource,
+    effects::{
+        biquad::{filter, Coefficients},
+        envelope::EnvelopeFollower,
+    },
+    Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// An effect that sweeps a resonant filter with the level of the signal.
+///
+/// `AutoWah` follows the amplitude envelope of its source and uses it to set
+/// the cutoff of a resonant low-pass filter, so louder notes open the filter
+/// and quieter ones close it, giving the classic funk "wah" sound:
+///
+/// `cutoff = base_cutoff + range * min(1.0, sensitivity * envelope)`
+///
+/// The cutoff is updated once per block, from the envelope at the end of the block.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::AutoWah, generators::{SineWave, VecSource}, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// // 100ms of a loud tone, followed by 1s of silence.
+/// let mut samples = vec![0.0; 4410 + 44100];
+/// SineWave::with_format(AudioFormat::MONO_CD, 1.0, 220.0).read(&mut samples[..4410]);
+/// let source = VecSource::new(samples, AudioFormat::MONO_CD);
+///
+/// let attack = Duration::from_millis(5);
+/// let release = Duration::from_millis(100);
+/// let mut wah = AutoWah::new(source, 300.0, 2000.0, 1.0, attack, release);
+/// assert_eq!(wah.cutoff(), 300.0);
+///
+/// // The tone sweeps the cutoff up...
+/// let mut block = vec![0.0; 441];
+/// wah.read(&mut block);
+/// assert!(wah.cutoff() > 1300.0);
+///
+/// // ...and it falls back to the base once the tone has been gone for a
+/// // few release times.
+/// for _ in 0..109 {
+///     wah.read(&mut block);
+/// }
+/// assert!((wah.cutoff() - 300.0).abs() < 1.0);
+/// ```
+pub struct AutoWah<S: AudioSource> {
+    source: S,
+    follower: EnvelopeFollower,
+    base_cutoff: f32,
+    range: f32,
+    sensitivity: f32,
+    cutoff: f32,
+    state: Vec<[f32; 2]>,
+}
+
+impl<S: AudioSource> AutoWah<S> {
+    /// The quality factor of the filter's resonant peak.
+    const Q: f32 = 4.0;
+
+    /// Construct an auto-wah.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `base_cutoff` -- The cutoff frequency, in Hz, when the source is silent.
+    /// * `range` -- How far, in Hz, the cutoff rises above `base_cutoff` at full level.
+    /// * `sensitivity` -- Multiplies the envelope, so quieter sources can still reach the top of the range.
+    /// * `attack` -- The time constant of the envelope when the level is rising.
+    /// * `release` -- The time constant of the envelope when the level is falling.
+    pub fn new(
+        source: S,
+        base_cutoff: f32,
+        range: f32,
+        sensitivity: f32,
+        attack: Duration,
+        release: Duration,
+    ) -> Self {
+        let channels = source.format().channels as usize;
+        AutoWah {
+            source,
+            follower: EnvelopeFollower::new(attack.as_secs_f32(), release.as_secs_f32()),
+            base_cutoff,
+            range,
+            sensitivity,
+            cutoff: base_cutoff,
+            state: vec![[0.0; 2]; channels],
+        }
+    }
+
+    pub fn set_base_cutoff(&mut self, base_cutoff: f32) {
+        self.base_cutoff = base_cutoff;
+    }
+
+    pub fn base_cutoff(&self) -> f32 {
+        self.base_cutoff
+    }
+
+    pub fn set_range(&mut self, range: f32) {
+        self.range = range;
+    }
+
+    pub fn range(&self) -> f32 {
+        self.range
+    }
+
+    pub fn set_sensitivity(&mut self, sensitivity: f32) {
+        self.sensitivity = sensitivity;
+    }
+
+    pub fn sensitivity(&self) -> f32 {
+        self.sensitivity
+    }
+
+    pub fn set_attack(&mut self, attack: Duration) {
+        self.follower.set_attack(attack.as_secs_f32());
+    }
+
+    pub fn attack(&self) -> Duration {
+        Duration::from_secs_f32(self.follower.attack())
+    }
+
+    pub fn set_release(&mut self, release: Duration) {
+        self.follower.set_release(release.as_secs_f32());
+    }
+
+    pub fn release(&self) -> Duration {
+        Duration::from_secs_f32(self.follower.release())
+    }
+
+    /// Returns the cutoff frequency applied to the most recent block.
+    pub fn cutoff(&self) -> f32 {
+        self.cutoff
+    }
+
+    fn reset(&mut self) {
+        self.follower.reset();
+        self.cutoff = self.base_cutoff;
+        self.state.iter_mut().for_each(|z| *z = [0.0; 2]);
+    }
+}
+
+impl<S: AudioSource> AudioSource for AutoWah<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "AutoWah::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let result = self.source.read(buffer);
+        let written = result.read;
+        if written == 0 {
+            return result;
+        }
+        let channels = format.channels as usize;
+        self.state.resize(channels, [0.0; 2]);
+
+        for frame in buffer[..written].chunks_exact(channels) {
+            let level = frame.iter().fold(0.0f32, |level, s| level.max(s.abs()));
+            self.follower.follow(level, format.sample_rate);
+        }
+
+        let envelope = (self.sensitivity * self.follower.envelope()).min(1.0);
+        self.cutoff = (self.base_cutoff + self.range * envelope).max(1.0);
+        let coefficients = Coefficients::low_pass(format.sample_rate, self.cutoff, Self::Q);
+        filter(&mut buffer[..written], &mut self.state, &coefficients);
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for AutoWah<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for AutoWah<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for AutoWah<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for AutoWah<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset();
+        self.source.seek_frame(frame)
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{core::AudioSource, Latency, Param, ReadResult, Sample, Tail};
+
+use tracing::instrument;
+
+/// An effect that adjusts the balance between the channels of a stereo source.
+///
+/// Like the balance knob on a hi-fi amplifier, turning the balance toward one
+/// side attenuates the other side, without mixing the channels together.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Balance, generators::SineWave, AudioSource};
+/// let mut expected = vec![0.0; 512];
+/// SineWave::new(1.0, 440.0).read(&mut expected);
+///
+/// // Centred, the audio passes through untouched.
+/// let mut balance = Balance::new(SineWave::new(1.0, 440.0), 0.0);
+/// let mut samples = vec![0.0; 512];
+/// balance.read(&mut samples);
+/// assert_eq!(samples, expected);
+///
+/// // Fully left, so the right channel is silent and the left is at full level.
+/// let mut balance = Balance::new(SineWave::new(1.0, 440.0), -1.0);
+/// balance.read(&mut samples);
+/// for (frame, expected) in samples.chunks_exact(2).zip(expected.chunks_exact(2)) {
+///     assert_eq!(frame, [expected[0], 0.0]);
+/// }
+/// ```
+pub struct Balance<S: AudioSource> {
+    source: S,
+    balance: Param,
+}
+
+impl<S: AudioSource> Balance<S> {
+    /// Construct a balance control.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `balance` -- From -1.0 (left only) to 1.0 (right only). 0.0 leaves both channels unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If `source` is not stereo.
+    pub fn new(source: S, balance: f32) -> Self {
+        assert_eq!(
+            source.format().channels,
+            2,
+            "Balance requires a stereo source."
+        );
+        Balance {
+            source,
+            balance: Param::new(balance.clamp(-1.0, 1.0)),
+        }
+    }
+
+    /// Set the balance, clamped to the range [-1.0, 1.0].
+    pub fn set_balance(&mut self, balance: f32) {
+        self.balance.set(balance.clamp(-1.0, 1.0));
+    }
+
+    pub fn balance(&self) -> f32 {
+        self.balance.get()
+    }
+
+    /// Returns a handle to the balance, which can be changed from any thread.
+    ///
+    /// Values set through the handle are clamped to the range [-1.0, 1.0] when read.
+    pub fn balance_param(&self) -> Param {
+        self.balance.clone()
+    }
+}
+
+impl<S: AudioSource> AudioSource for Balance<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Balance::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.source.read(buffer);
+
+        let balance = self.balance.get().clamp(-1.0, 1.0);
+        let left = f32::min(1.0, 1.0 - balance);
+        let right = f32::min(1.0, 1.0 + balance);
+        for frame in buffer[..result.read].chunks_exact_mut(2) {
+            frame[0] *= left;
+            frame[1] *= right;
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Balance<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Balance<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    core::AudioSource,
+    effects::biquad::{filter, Coefficients},
+    Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// An effect that passes only a band of frequencies, e.g. to isolate a voice or instrument.
+///
+/// `BandPass` is a second-order biquad filter, so it rolls off at 6 dB per
+/// octave on either side of the band, and frequencies at the center pass at
+/// full volume. Narrower bands make the filter more resonant. Each channel
+/// is filtered independently.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SineWave, effects::BandPass, AudioSource};
+/// let peak = |frequency: f32| {
+///     let mut band = BandPass::new(SineWave::new(1.0, frequency), 1000.0, 500.0);
+///     let mut samples = vec![0.0; 2 * 44100];
+///     band.read(&mut samples);
+///     samples[44100..].iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+/// };
+///
+/// assert!((peak(1000.0) - 1.0).abs() < 0.01);
+/// assert!(peak(100.0) < 0.1);
+/// assert!(peak(10000.0) < 0.1);
+/// ```
+pub struct BandPass<S: AudioSource> {
+    source: S,
+    center: f32,
+    bandwidth: f32,
+    state: Vec<[f32; 2]>,
+}
+
+impl<S: AudioSource> BandPass<S> {
+    /// Construct a band-pass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `center` -- The frequency at the center of the band, in Hz.
+    /// * `bandwidth` -- The width of the band between its -3 dB points, in Hz.
+    ///
+    /// # Panics
+    ///
+    /// If `center` or `bandwidth` isn't positive.
+    pub fn new(source: S, center: f32, bandwidth: f32) -> Self {
+        assert!(center > 0.0, "Center frequency must be positive.");
+        assert!(bandwidth > 0.0, "Bandwidth must be positive.");
+        let channels = source.format().channels as usize;
+        BandPass {
+            source,
+            center,
+            bandwidth,
+            state: vec![[0.0; 2]; channels],
+        }
+    }
+
+    /// Set the frequency at the center of the band, in Hz.
+    ///
+    /// # Panics
+    ///
+    /// If `center` isn't positive.
+    pub fn set_center(&mut self, center: f32) {
+        assert!(center > 0.0, "Center frequency must be positive.");
+        self.center = center;
+    }
+
+    pub fn center(&self) -> f32 {
+        self.center
+    }
+
+    /// Set the width of the band between its -3 dB points, in Hz.
+    ///
+    /// # Panics
+    ///
+    /// If `bandwidth` isn't positive.
+    pub fn set_bandwidth(&mut self, bandwidth: f32) {
+        assert!(bandwidth > 0.0, "Bandwidth must be positive.");
+        self.bandwidth = bandwidth;
+    }
+
+    pub fn bandwidth(&self) -> f32 {
+        self.bandwidth
+    }
+
+    fn reset(&mut self) {
+        self.state.iter_mut().for_each(|z| *z = [0.0; 2]);
+    }
+}
+
+impl<S: AudioSource> AudioSource for BandPass<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "BandPass::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let result = self.source.read(buffer);
+        let written = result.read;
+        if written == 0 {
+            return result;
+        }
+        self.state.resize(format.channels as usize, [0.0; 2]);
+
+        let q = self.center / self.bandwidth;
+        let coefficients = Coefficients::band_pass(format.sample_rate, self.center, q);
+        filter(&mut buffer[..written], &mut self.state, &coefficients);
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for BandPass<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for BandPass<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for BandPass<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for BandPass<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset();
+        self.source.seek_frame(frame)
+    }
+}
@@ -1,10 +1,16 @@
 use crate::{
     core::{AudioSource, SharedAudioSource},
-    ReadResult, Sample,
+    db_to_linear,
+    effects::envelope::EnvelopeFollower,
+    linear_to_db, ReadResult, Sample,
 };
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use slotmap::{DefaultKey, DenseSlotMap};
 
+use std::time::Duration;
+
 use tracing::instrument;
 
 /// A mixer that combines multiple [`AudioSource`](crate::AudioSource)s.
@@ -26,25 +32,152 @@ use tracing::instrument;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Default)]
+///
+/// # Parallel mixing
+///
+/// With the `rayon` feature, [`set_parallel`](BasicMixer::set_parallel) reads
+/// the sources on rayon's thread pool. The sources are still summed one at a
+/// time, in the same order as a serial mix, so the result is identical.
+///
+/// # Ducking
+///
+/// Sources added with [`add_duckable_source`](BasicMixer::add_duckable_source)
+/// are attenuated whenever the source given to
+/// [`set_duck_source`](BasicMixer::set_duck_source) is loud, e.g. so that voice
+/// ducks music. The duck source is only used to control the attenuation and is
+/// not mixed into the output; add it as a regular source too (via a
+/// [`Tee`](crate::effects::Tee)) to hear it.
+///
+/// ```
+/// # use timbre::{effects::BasicMixer, generators::{Ramp, SineWave}, AudioFormat, AudioSource, IntoShared};
+/// # use std::time::Duration;
+/// let constant = |value| Ramp::new(value, value, Duration::from_secs(0), AudioFormat::STEREO_CD);
+///
+/// let mut mixer = BasicMixer::new();
+/// mixer.add_source(constant(0.25).into_shared());
+/// mixer.add_duckable_source(constant(0.5).into_shared());
+///
+/// let mut samples = vec![0.0; 4410 * 2];
+/// mixer.read(&mut samples);
+/// assert_eq!(samples[0], 0.75);
+///
+/// // A loud duck source reduces only the duckable source.
+/// mixer.set_duck_source(SineWave::new(1.0, 440.0).into_shared());
+/// mixer.read(&mut samples);
+/// let last = samples[samples.len() - 1];
+/// assert!(last >= 0.25 && last < 0.5);
+/// ```
 pub struct BasicMixer {
     buffer: Vec<f32>,
     coefficient: Option<f32>,
     sources: DenseSlotMap<DefaultKey, SharedAudioSource>,
+    duckable: DenseSlotMap<DefaultKey, SharedAudioSource>,
+    duck_buffer: Vec<f32>,
+    duck_depth: f32,
+    ducker: Option<Ducker>,
+    auto_gain: Option<AutoGain>,
+    #[cfg(feature = "rayon")]
+    parallel: bool,
+    #[cfg(feature = "rayon")]
+    scratch: Vec<Vec<f32>>,
 }
 
 /// A key used to remove sources that have been added to [`BasicMixer`](crate::effects::BasicMixer).
 pub struct BasicMixerSource {
     key: DefaultKey,
+    duckable: bool,
+}
+
+struct Ducker {
+    source: SharedAudioSource,
+    buffer: Vec<f32>,
+    follower: EnvelopeFollower,
+}
+
+impl Ducker {
+    const ATTACK: f32 = 0.005;
+    const RELEASE: f32 = 0.25;
+
+    /// Follow the level of the duck source and attenuate `samples` accordingly.
+    fn apply(&mut self, samples: &mut [f32], depth: f32) {
+        let format = self.source.format();
+        let channels = format.channels as usize;
+        self.buffer.resize(samples.len(), 0.0);
+        let result = self.source.read(&mut self.buffer);
+        self.buffer[result.read..]
+            .iter_mut()
+            .for_each(|sample| *sample = 0.0);
+
+        for (frame, duck) in samples
+            .chunks_exact_mut(channels)
+            .zip(self.buffer.chunks_exact(channels))
+        {
+            let level = duck.iter().fold(0.0f32, |level, s| level.max(s.abs()));
+            let envelope = self.follower.follow(level, format.sample_rate);
+
+            let gain = 1.0 - depth * envelope.min(1.0);
+            frame.iter_mut().for_each(|sample| *sample *= gain);
+        }
+    }
+}
+
+/// Slowly steers the gain of the mix so its peak level approaches a target.
+struct AutoGain {
+    target_db: f32,
+    level: EnvelopeFollower,
+    gain_db: EnvelopeFollower,
+}
+
+impl AutoGain {
+    /// The most the gain will boost or cut, so silence isn't amplified without bound.
+    const MAX_GAIN_DB: f32 = 24.0;
+
+    fn new(target_db: f32, response: Duration) -> Self {
+        let response = response.as_secs_f32();
+        AutoGain {
+            target_db,
+            // Peaks are caught quickly, but the gain itself only moves at the response rate.
+            level: EnvelopeFollower::new(response / 10.0, response),
+            gain_db: EnvelopeFollower::new(response, response),
+        }
+    }
+
+    fn apply(&mut self, samples: &mut [f32], format: crate::AudioFormat) {
+        for frame in samples.chunks_exact_mut(format.channels as usize) {
+            let peak = frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+            let level_db = linear_to_db(self.level.follow(peak, format.sample_rate));
+
+            let desired = (self.target_db - level_db).clamp(-Self::MAX_GAIN_DB, Self::MAX_GAIN_DB);
+            let gain = db_to_linear(self.gain_db.follow(desired, format.sample_rate));
+            frame.iter_mut().for_each(|sample| *sample *= gain);
+        }
+    }
 }
 
 impl BasicMixer {
+    /// The default amount by which duckable sources are attenuated.
+    pub const DEFAULT_DUCK_DEPTH: f32 = 0.75;
+
+    /// The fewest sources that are read in parallel; smaller mixes aren't
+    /// worth the overhead. See [`set_parallel`](BasicMixer::set_parallel).
+    #[cfg(feature = "rayon")]
+    pub const PARALLEL_MIN_SOURCES: usize = 4;
+
     /// Construct a `BasicMixer` that simply adds samples and doesn't multiply by anything.
     pub fn new() -> Self {
         BasicMixer {
             coefficient: None,
             sources: DenseSlotMap::new(),
             buffer: Vec::new(),
+            duckable: DenseSlotMap::new(),
+            duck_buffer: Vec::new(),
+            duck_depth: BasicMixer::DEFAULT_DUCK_DEPTH,
+            ducker: None,
+            auto_gain: None,
+            #[cfg(feature = "rayon")]
+            parallel: false,
+            #[cfg(feature = "rayon")]
+            scratch: Vec::new(),
         }
     }
 
@@ -56,12 +189,45 @@ impl BasicMixer {
     /// * `coefficient` -- A number to multiply the final resulting samples by.
     pub fn with_coefficient(coefficient: f32) -> Self {
         BasicMixer {
-            buffer: Vec::new(),
             coefficient: Some(coefficient),
-            sources: DenseSlotMap::new(),
+            ..BasicMixer::new()
         }
     }
 
+    /// Set the coefficient the mixed samples are multiplied by.
+    ///
+    /// # Arguments
+    ///
+    /// * `coefficient` -- A number to multiply the final resulting samples by,
+    ///   or `None` to leave them unchanged.
+    pub fn set_coefficient(&mut self, coefficient: Option<f32>) {
+        self.coefficient = coefficient;
+    }
+
+    pub fn coefficient(&self) -> Option<f32> {
+        self.coefficient
+    }
+
+    /// Set the coefficient the mixed samples are multiplied by, in decibels.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::effects::BasicMixer;
+    /// let mut mixer = BasicMixer::new();
+    /// mixer.set_coefficient_db(-6.0);
+    /// assert!((mixer.coefficient().unwrap() - 0.501).abs() < 0.001);
+    /// ```
+    pub fn set_coefficient_db(&mut self, db: f32) {
+        self.coefficient = Some(db_to_linear(db));
+    }
+
+    /// Get the coefficient the mixed samples are multiplied by, in decibels.
+    ///
+    /// Returns 0.0 dB if there is no coefficient.
+    pub fn coefficient_db(&self) -> f32 {
+        self.coefficient.map_or(0.0, linear_to_db)
+    }
+
     /// Add a source to this mixer.
     ///
     /// # Arguments
@@ -72,12 +238,227 @@ impl BasicMixer {
     ///
     /// A key to be used in [`remove_source`](method.remove_source) to remove this source.
     pub fn add_source(&mut self, source: SharedAudioSource) -> BasicMixerSource {
-        assert!(self.sources.is_empty() || source.format() == self.format());
+        assert!(self.is_empty() || source.format() == self.format());
         BasicMixerSource {
             key: self.sources.insert(source),
+            duckable: false,
+        }
+    }
+
+    /// Add a source to this mixer that is attenuated while the duck source is loud.
+    ///
+    /// Without a duck source, this behaves like [`add_source`](BasicMixer::add_source).
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The audio source to add to this mixer.
+    ///
+    /// # Returns
+    ///
+    /// A key to be used in [`remove_source`](method.remove_source) to remove this source.
+    pub fn add_duckable_source(&mut self, source: SharedAudioSource) -> BasicMixerSource {
+        assert!(self.is_empty() || source.format() == self.format());
+        BasicMixerSource {
+            key: self.duckable.insert(source),
+            duckable: true,
         }
     }
 
+    /// Set the source whose level ducks the duckable sources.
+    ///
+    /// The duck source must have the same format as the other sources.
+    pub fn set_duck_source(&mut self, source: SharedAudioSource) {
+        assert!(self.is_empty() || source.format() == self.format());
+        self.ducker = Some(Ducker {
+            source,
+            buffer: Vec::new(),
+            follower: EnvelopeFollower::new(Ducker::ATTACK, Ducker::RELEASE),
+        });
+    }
+
+    /// Remove the duck source, so duckable sources are no longer attenuated.
+    pub fn clear_duck_source(&mut self) {
+        self.ducker = None;
+    }
+
+    /// Set how much duckable sources are attenuated when the duck source is at full level.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` -- From 0.0 (no ducking) to 1.0 (silenced).
+    pub fn set_duck_depth(&mut self, depth: f32) {
+        self.duck_depth = depth.clamp(0.0, 1.0);
+    }
+
+    pub fn duck_depth(&self) -> f32 {
+        self.duck_depth
+    }
+
+    /// Slowly adjust the level of the mix so its peaks approach `target_dbfs`.
+    ///
+    /// This acts like a slow automatic gain control on the mix bus: the peak
+    /// level of the mix is tracked, and the gain moves toward the value that
+    /// would bring it to the target, in decibels, with a time constant of
+    /// `response`. Because the gain changes gradually, individual transients
+    /// pass through undistorted; use a limiter after the mixer if they must
+    /// never exceed full scale. The gain is applied after the
+    /// [`coefficient`](BasicMixer::set_coefficient), and is limited to ±24 dB
+    /// so that silence isn't boosted without bound.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_dbfs` -- The desired peak level, in dB relative to full scale.
+    /// * `response` -- How quickly the gain adapts. A few seconds avoids audible pumping.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::BasicMixer, generators::Ramp, linear_to_db, AudioFormat, AudioSource, IntoShared};
+    /// # use std::time::Duration;
+    /// let constant = |value| Ramp::new(value, value, Duration::from_secs(0), AudioFormat::MONO_CD);
+    /// let mut mixer = BasicMixer::new();
+    /// mixer.add_source(constant(0.25).into_shared());
+    /// mixer.add_source(constant(0.25).into_shared());
+    /// mixer.set_auto_gain(-12.0, Duration::from_millis(100));
+    ///
+    /// // After ten response times, the mix has settled at the target.
+    /// let mut samples = vec![0.0; 44100];
+    /// mixer.read(&mut samples);
+    /// assert!((linear_to_db(samples[44099]) + 12.0).abs() < 0.5);
+    ///
+    /// // Doubling the number of sources doubles the level at first...
+    /// mixer.add_source(constant(0.25).into_shared());
+    /// mixer.add_source(constant(0.25).into_shared());
+    /// mixer.read(&mut samples);
+    /// assert!((linear_to_db(samples[0]) + 6.0).abs() < 0.5);
+    /// assert!(samples[441] > samples[4410]);
+    ///
+    /// // ...then settles back to the target over the response time.
+    /// assert!((linear_to_db(samples[44099]) + 12.0).abs() < 0.5);
+    /// ```
+    pub fn set_auto_gain(&mut self, target_dbfs: f32, response: Duration) {
+        self.auto_gain = Some(AutoGain::new(target_dbfs, response));
+    }
+
+    /// Stop adjusting the level of the mix.
+    pub fn clear_auto_gain(&mut self) {
+        self.auto_gain = None;
+    }
+
+    /// Set whether to read the sources in parallel.
+    ///
+    /// Each source is read into its own buffer on rayon's thread pool, then the
+    /// buffers are summed in the same order as a serial mix, so the output
+    /// doesn't change. This only pays off when there are many sources that do
+    /// a lot of work in `read`, so mixes of fewer than
+    /// [`PARALLEL_MIN_SOURCES`](BasicMixer::PARALLEL_MIN_SOURCES) sources are
+    /// always read serially. Duckable sources are always read serially.
+    ///
+    /// Only available with the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Read every regular source and add them into `buffer`, in order.
+    ///
+    /// # Returns
+    ///
+    /// The largest number of samples read from any source.
+    fn mix_sources(&mut self, buffer: &mut [Sample]) -> usize {
+        #[cfg(feature = "rayon")]
+        {
+            if self.parallel && self.sources.len() >= BasicMixer::PARALLEL_MIN_SOURCES {
+                return self.mix_sources_parallel(buffer);
+            }
+        }
+
+        // Reserve the scratch buffer once, rather than for each source.
+        self.buffer.resize(buffer.len(), 0.0);
+
+        let mut read = 0;
+        let mut iter = self.sources.iter_mut();
+        if let Some((_, first)) = iter.next() {
+            read = first.lock().unwrap().read(buffer).read;
+        }
+        buffer[read..].iter_mut().for_each(|sample| *sample = 0.0);
+
+        for (_, source) in iter {
+            let result = source.lock().unwrap().read(&mut self.buffer);
+            read = std::cmp::max(read, result.read);
+
+            buffer
+                .iter_mut()
+                .zip(self.buffer[..result.read].iter())
+                .for_each(|(a, b)| *a += *b);
+        }
+
+        read
+    }
+
+    #[cfg(feature = "rayon")]
+    fn mix_sources_parallel(&mut self, buffer: &mut [Sample]) -> usize {
+        self.scratch.resize_with(self.sources.len(), Vec::new);
+        let reads: Vec<usize> = self
+            .sources
+            .values()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .zip(self.scratch.par_iter_mut())
+            .map(|(source, scratch)| {
+                scratch.resize(buffer.len(), 0.0);
+                source.lock().unwrap().read(scratch).read
+            })
+            .collect();
+
+        // Sum in the same order as a serial mix, for identical results.
+        buffer.iter_mut().for_each(|sample| *sample = 0.0);
+        for (scratch, &read) in self.scratch.iter().zip(reads.iter()) {
+            buffer
+                .iter_mut()
+                .zip(scratch[..read].iter())
+                .for_each(|(a, b)| *a += *b);
+        }
+
+        reads.into_iter().max().unwrap_or(0)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.sources.is_empty() && self.duckable.is_empty()
+    }
+
+    fn mix_duckable(&mut self, buffer: &mut [Sample]) -> usize {
+        self.duck_buffer.clear();
+        self.duck_buffer.resize(buffer.len(), 0.0);
+        self.buffer.resize(buffer.len(), 0.0);
+
+        let mut read = 0;
+        for (_, source) in self.duckable.iter_mut() {
+            let result = source.lock().unwrap().read(&mut self.buffer);
+            read = std::cmp::max(read, result.read);
+
+            self.duck_buffer
+                .iter_mut()
+                .zip(self.buffer[..result.read].iter())
+                .for_each(|(a, b)| *a += *b);
+        }
+
+        if let Some(ducker) = &mut self.ducker {
+            ducker.apply(&mut self.duck_buffer, self.duck_depth);
+        }
+
+        buffer
+            .iter_mut()
+            .zip(self.duck_buffer.iter())
+            .for_each(|(a, b)| *a += *b);
+
+        read
+    }
+
     /// Removes the source indicated by `source`, if present.
     ///
     /// # Examples
@@ -89,45 +470,52 @@ impl BasicMixer {
     /// mixer.remove_source(sin);
     /// ```
     pub fn remove_source(&mut self, source: BasicMixerSource) {
-        self.sources.remove(source.key);
+        if source.duckable {
+            self.duckable.remove(source.key);
+        } else {
+            self.sources.remove(source.key);
+        }
+    }
+}
+
+impl Default for BasicMixer {
+    fn default() -> Self {
+        BasicMixer::new()
     }
 }
 
 impl AudioSource for BasicMixer {
     fn format(&self) -> crate::AudioFormat {
-        self.sources.iter().next().unwrap().1.format()
+        self.sources
+            .values()
+            .chain(self.duckable.values())
+            .next()
+            .unwrap()
+            .format()
     }
 
     #[instrument(name = "BasicMixer::read", skip(self, buffer))]
     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
-        if self.sources.is_empty() {
+        if self.is_empty() {
             buffer.iter_mut().for_each(|sample| *sample = 0.0);
             return ReadResult::good(buffer.len());
         }
 
-        let mut iter = self.sources.iter_mut();
-        let (_, first) = iter.next().unwrap();
-        let ReadResult {
-            mut read,
-            state: _state,
-        } = first.lock().unwrap().read(buffer);
-
-        for (_, source) in iter {
-            self.buffer.resize(buffer.len(), 0.0);
-
-            let result = source.lock().unwrap().read(&mut self.buffer);
-            read = std::cmp::max(read, result.read);
+        let mut read = self.mix_sources(buffer);
 
-            buffer
-                .iter_mut()
-                .zip(self.buffer.iter())
-                .for_each(|(a, b)| *a += *b);
+        if !self.duckable.is_empty() {
+            read = std::cmp::max(read, self.mix_duckable(buffer));
         }
 
         if let Some(coef) = self.coefficient {
             buffer.iter_mut().for_each(|sample| *sample *= coef);
         }
 
+        let format = self.format();
+        if let Some(auto_gain) = &mut self.auto_gain {
+            auto_gain.apply(buffer, format);
+        }
+
         if read < buffer.len() {
             ReadResult::underrun(read)
         } else {
@@ -0,0 +1,99 @@
+use crate::{
+    core::AudioSource,
+    effects::shelf::{Shelf, ShelfKind},
+    Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// An effect that boosts or cuts low frequencies, like the bass knob on a stereo.
+///
+/// `BassControl` is a low-shelf filter at a fixed 100 Hz. Frequencies below the
+/// shelf have their volume changed by the given gain, while higher frequencies
+/// pass through unchanged.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SineWave, effects::BassControl, AudioSource};
+/// let peak = |source: &mut dyn AudioSource| {
+///     let mut samples = vec![0.0; 2 * 44100];
+///     source.read(&mut samples);
+///     samples[44100..].iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+/// };
+///
+/// // 0 dB leaves everything alone.
+/// let mut flat = BassControl::new(SineWave::new(1.0, 40.0), 0.0);
+/// assert!((peak(&mut flat) - 1.0).abs() < 0.01);
+///
+/// // +6 dB roughly doubles the bass...
+/// let mut low = BassControl::new(SineWave::new(1.0, 40.0), 6.0);
+/// assert!(peak(&mut low) > 1.8);
+///
+/// // ...without affecting the treble.
+/// let mut high = BassControl::new(SineWave::new(1.0, 5000.0), 6.0);
+/// assert!((peak(&mut high) - 1.0).abs() < 0.05);
+/// ```
+pub struct BassControl<S: AudioSource> {
+    shelf: Shelf<S>,
+}
+
+impl<S: AudioSource> BassControl<S> {
+    /// The frequency of the shelf, in Hz.
+    const FREQUENCY: f32 = 100.0;
+
+    /// Construct a bass control.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `gain_db` -- The gain to apply to low frequencies, in decibels.
+    pub fn new(source: S, gain_db: f32) -> Self {
+        BassControl {
+            shelf: Shelf::new(source, ShelfKind::Low, Self::FREQUENCY, gain_db),
+        }
+    }
+
+    /// Set the gain to apply to low frequencies, in decibels.
+    pub fn set_gain_db(&mut self, gain_db: f32) {
+        self.shelf.gain_db = gain_db;
+    }
+
+    pub fn gain_db(&self) -> f32 {
+        self.shelf.gain_db
+    }
+}
+
+impl<S: AudioSource> AudioSource for BassControl<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.shelf.format()
+    }
+
+    #[instrument(name = "BassControl::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.shelf.read(buffer)
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for BassControl<S> {
+    fn latency_frames(&self) -> u64 {
+        self.shelf.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for BassControl<S> {
+    fn tail_frames(&self) -> u64 {
+        self.shelf.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for BassControl<S> {
+    fn rewind(&mut self) -> bool {
+        self.shelf.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for BassControl<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.shelf.seek_frame(frame)
+    }
+}
@@ -0,0 +1,324 @@
+use crate::flush_denormal;
+
+/// Coefficients for a biquad filter, normalized so that `a0` is 1.
+///
+/// Formulas are from Robert Bristow-Johnson's "Audio EQ Cookbook".
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Coefficients {
+    pub b0: f32,
+    pub b1: f32,
+    pub b2: f32,
+    pub a1: f32,
+    pub a2: f32,
+}
+
+impl Coefficients {
+    /// A low shelf that changes the volume below `frequency` by `gain_db`.
+    pub fn low_shelf(sample_rate: u32, frequency: f32, gain_db: f32) -> Self {
+        let (a, cos, alpha) = shelf_parameters(sample_rate, frequency, gain_db);
+        let sqrt_a = 2.0 * a.sqrt() * alpha;
+        Coefficients::normalize(
+            a * ((a + 1.0) - (a - 1.0) * cos + sqrt_a),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+            a * ((a + 1.0) - (a - 1.0) * cos - sqrt_a),
+            (a + 1.0) + (a - 1.0) * cos + sqrt_a,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+            (a + 1.0) + (a - 1.0) * cos - sqrt_a,
+        )
+    }
+
+    /// A high shelf that changes the volume above `frequency` by `gain_db`.
+    pub fn high_shelf(sample_rate: u32, frequency: f32, gain_db: f32) -> Self {
+        let (a, cos, alpha) = shelf_parameters(sample_rate, frequency, gain_db);
+        let sqrt_a = 2.0 * a.sqrt() * alpha;
+        Coefficients::normalize(
+            a * ((a + 1.0) + (a - 1.0) * cos + sqrt_a),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+            a * ((a + 1.0) + (a - 1.0) * cos - sqrt_a),
+            (a + 1.0) - (a - 1.0) * cos + sqrt_a,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos),
+            (a + 1.0) - (a - 1.0) * cos - sqrt_a,
+        )
+    }
+
+    /// A resonant low-pass filter at `frequency` with quality factor `q`.
+    pub fn low_pass(sample_rate: u32, frequency: f32, q: f32) -> Self {
+        let frequency = frequency.min(0.45 * sample_rate as f32);
+        let w0 = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        Coefficients::normalize(
+            (1.0 - cos) / 2.0,
+            1.0 - cos,
+            (1.0 - cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        )
+    }
+
+    /// A resonant high-pass filter at `frequency` with quality factor `q`.
+    pub fn high_pass(sample_rate: u32, frequency: f32, q: f32) -> Self {
+        let frequency = frequency.min(0.45 * sample_rate as f32);
+        let w0 = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        Coefficients::normalize(
+            (1.0 + cos) / 2.0,
+            -(1.0 + cos),
+            (1.0 + cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        )
+    }
+
+    /// A band-pass filter centered on `frequency` with quality factor `q`,
+    /// with a peak gain of 0 dB.
+    pub fn band_pass(sample_rate: u32, frequency: f32, q: f32) -> Self {
+        let frequency = frequency.min(0.45 * sample_rate as f32);
+        let w0 = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        Coefficients::normalize(alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+    }
+
+    /// A notch filter that removes `frequency`, with quality factor `q`.
+    pub fn notch(sample_rate: u32, frequency: f32, q: f32) -> Self {
+        let frequency = frequency.min(0.45 * sample_rate as f32);
+        let w0 = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        Coefficients::normalize(1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+    }
+
+    /// A peaking filter that changes the volume around `frequency` by `gain_db`.
+    pub fn peaking(sample_rate: u32, frequency: f32, q: f32, gain_db: f32) -> Self {
+        let frequency = frequency.min(0.45 * sample_rate as f32);
+        let w0 = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        let a = 10.0f32.powf(gain_db / 40.0);
+        Coefficients::normalize(
+            1.0 + alpha * a,
+            -2.0 * cos,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos,
+            1.0 - alpha / a,
+        )
+    }
+
+    /// An all-pass filter centered on `frequency` with quality factor `q`,
+    /// which shifts phase without changing the volume of any frequency.
+    pub fn all_pass(sample_rate: u32, frequency: f32, q: f32) -> Self {
+        let frequency = frequency.min(0.45 * sample_rate as f32);
+        let w0 = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        Coefficients::normalize(
+            1.0 - alpha,
+            -2.0 * cos,
+            1.0 + alpha,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        )
+    }
+
+    /// Divide all of the coefficients by `a0`.
+    pub fn normalize(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Coefficients {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+/// Returns `A`, `cos(w0)`, and `alpha` for a shelf with a slope of 1.
+fn shelf_parameters(sample_rate: u32, frequency: f32, gain_db: f32) -> (f32, f32, f32) {
+    // Keep the shelf below Nyquist so low sample rates still get a stable filter.
+    let frequency = frequency.min(0.45 * sample_rate as f32);
+    let w0 = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+    let a = 10.0f32.powf(gain_db / 40.0);
+    let alpha = w0.sin() / 2.0 * std::f32::consts::SQRT_2;
+    (a, w0.cos(), alpha)
+}
+
+/// A second-order filter that can be embedded in other effects.
+///
+/// `Biquad` holds a filter's coefficients along with its state for each
+/// channel, so it can filter a stream a buffer at a time. The designs are from
+/// Robert Bristow-Johnson's "Audio EQ Cookbook", and frequencies are limited
+/// to just below Nyquist so the filter is always stable. A `q` of
+/// `std::f32::consts::FRAC_1_SQRT_2` gives the flattest response; higher
+/// values resonate.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Biquad, generators::SineWave, AudioFormat, AudioSource};
+/// let mut notch = Biquad::notch(44100, 1000.0, 1.0);
+/// let mut sine = SineWave::with_format(AudioFormat::MONO_CD, 1.0, 1000.0);
+///
+/// let mut samples = vec![0.0; 44100];
+/// sine.read(&mut samples);
+/// notch.process(&mut samples, 1);
+/// assert!(samples[22050..].iter().all(|sample| sample.abs() < 0.01));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Biquad {
+    coefficients: Coefficients,
+    state: Vec<[f32; 2]>,
+}
+
+impl Biquad {
+    /// A resonant low-pass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` -- The sample rate of the audio to filter.
+    /// * `cutoff` -- The frequency above which volume will be reduced.
+    /// * `q` -- The quality factor; higher values resonate at the cutoff.
+    pub fn low_pass(sample_rate: u32, cutoff: f32, q: f32) -> Self {
+        Biquad::new(Coefficients::low_pass(sample_rate, cutoff, q))
+    }
+
+    /// A resonant high-pass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` -- The sample rate of the audio to filter.
+    /// * `cutoff` -- The frequency below which volume will be reduced.
+    /// * `q` -- The quality factor; higher values resonate at the cutoff.
+    pub fn high_pass(sample_rate: u32, cutoff: f32, q: f32) -> Self {
+        Biquad::new(Coefficients::high_pass(sample_rate, cutoff, q))
+    }
+
+    /// A band-pass filter, which passes `center` at full volume.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` -- The sample rate of the audio to filter.
+    /// * `center` -- The frequency at the center of the band.
+    /// * `q` -- The quality factor; the band is `center / q` wide.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::effects::{BandPass, Biquad};
+    /// # use timbre::{generators::SineWave, AudioSource};
+    /// let mut band = BandPass::new(SineWave::new(1.0, 3000.0), 1000.0, 250.0);
+    /// let mut biquad = Biquad::band_pass(44100, 1000.0, 4.0);
+    /// let mut sine = SineWave::new(1.0, 3000.0);
+    ///
+    /// let mut expected = vec![0.0; 1024];
+    /// let mut samples = vec![0.0; 1024];
+    /// band.read(&mut expected);
+    /// sine.read(&mut samples);
+    /// biquad.process(&mut samples, 2);
+    /// assert_eq!(samples, expected);
+    /// ```
+    pub fn band_pass(sample_rate: u32, center: f32, q: f32) -> Self {
+        Biquad::new(Coefficients::band_pass(sample_rate, center, q))
+    }
+
+    /// A notch filter, which removes `frequency`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` -- The sample rate of the audio to filter.
+    /// * `frequency` -- The frequency to remove.
+    /// * `q` -- The quality factor; higher values make the notch narrower.
+    pub fn notch(sample_rate: u32, frequency: f32, q: f32) -> Self {
+        Biquad::new(Coefficients::notch(sample_rate, frequency, q))
+    }
+
+    /// A peaking filter, which boosts or cuts the frequencies around `frequency`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` -- The sample rate of the audio to filter.
+    /// * `frequency` -- The frequency at the center of the peak.
+    /// * `q` -- The quality factor; higher values make the peak narrower.
+    /// * `gain_db` -- The gain at the center of the peak, in decibels.
+    pub fn peaking(sample_rate: u32, frequency: f32, q: f32, gain_db: f32) -> Self {
+        Biquad::new(Coefficients::peaking(sample_rate, frequency, q, gain_db))
+    }
+
+    /// A low shelf, which boosts or cuts the frequencies below `frequency`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` -- The sample rate of the audio to filter.
+    /// * `frequency` -- The midpoint of the shelf.
+    /// * `gain_db` -- The gain below the shelf, in decibels.
+    pub fn low_shelf(sample_rate: u32, frequency: f32, gain_db: f32) -> Self {
+        Biquad::new(Coefficients::low_shelf(sample_rate, frequency, gain_db))
+    }
+
+    /// A high shelf, which boosts or cuts the frequencies above `frequency`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` -- The sample rate of the audio to filter.
+    /// * `frequency` -- The midpoint of the shelf.
+    /// * `gain_db` -- The gain above the shelf, in decibels.
+    pub fn high_shelf(sample_rate: u32, frequency: f32, gain_db: f32) -> Self {
+        Biquad::new(Coefficients::high_shelf(sample_rate, frequency, gain_db))
+    }
+
+    fn new(coefficients: Coefficients) -> Self {
+        Biquad {
+            coefficients,
+            state: Vec::new(),
+        }
+    }
+
+    /// Filter interleaved `samples` in place, continuing from the previous call.
+    ///
+    /// # Panics
+    ///
+    /// If `channels` is zero, or `samples` isn't a whole number of frames.
+    pub fn process(&mut self, samples: &mut [f32], channels: u8) {
+        assert!(channels > 0, "There must be at least one channel.");
+        self.state.resize(channels as usize, [0.0; 2]);
+        filter(samples, &mut self.state, &self.coefficients);
+    }
+
+    /// Replace the coefficients with another filter's, keeping the current
+    /// state, so the filter can be changed while playing.
+    pub fn set_coefficients(&mut self, other: &Biquad) {
+        self.coefficients = other.coefficients;
+    }
+
+    /// Returns the normalized coefficients as `([b0, b1, b2], [a1, a2])`.
+    pub fn coefficients(&self) -> ([f32; 3], [f32; 2]) {
+        let Coefficients { b0, b1, b2, a1, a2 } = self.coefficients;
+        ([b0, b1, b2], [a1, a2])
+    }
+
+    /// Clear the filter's state, as if it had only ever seen silence.
+    pub fn reset(&mut self) {
+        self.state.iter_mut().for_each(|z| *z = [0.0; 2]);
+    }
+}
+
+/// Filter interleaved `samples` in transposed direct form II, where `state`
+/// holds the two delay elements for each channel.
+pub(crate) fn filter(samples: &mut [f32], state: &mut [[f32; 2]], coefficients: &Coefficients) {
+    let channels = state.len();
+    assert!(samples.len().is_multiple_of(channels));
+
+    let Coefficients { b0, b1, b2, a1, a2 } = *coefficients;
+    for frame in samples.chunks_exact_mut(channels) {
+        for (sample, z) in frame.iter_mut().zip(state.iter_mut()) {
+            let x = *sample;
+            let y = b0 * x + z[0];
+            z[0] = flush_denormal(b1 * x - a1 * y + z[1]);
+            z[1] = flush_denormal(b2 * x - a2 * y);
+            *sample = y;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    core::AudioSource,
+    effects::biquad::{filter, Coefficients},
+    Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// A biquad filter built directly from its coefficients.
+///
+/// The filter computes
+/// `y[n] = b0*x[n] + b1*x[n-1] + b2*x[n-2] - a1*y[n-1] - a2*y[n-2]`
+/// for each channel independently, in transposed direct form II. This lets
+/// coefficients from a filter design tool be used as-is, for designs timbre
+/// doesn't provide.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{BiquadRaw, LowPass}, generators::SineWave, AudioSource};
+/// // LowPass is a one-pole filter, y[n] = a*x[n] + (1 - a)*y[n-1].
+/// let dt = 1.0 / 44100.0;
+/// let rc = 1.0 / (2.0 * std::f32::consts::PI * 1000.0);
+/// let a = dt / (rc + dt);
+///
+/// let mut raw = BiquadRaw::new(SineWave::new(1.0, 3000.0), [a, 0.0, 0.0], [a - 1.0, 0.0]);
+/// let mut low_pass = LowPass::new(SineWave::new(1.0, 3000.0), 1000.0);
+///
+/// let mut samples = vec![0.0; 2048];
+/// let mut expected = vec![0.0; 2048];
+/// raw.read(&mut samples);
+/// low_pass.read(&mut expected);
+/// for (sample, expected) in samples.iter().zip(expected.iter()) {
+///     assert!((sample - expected).abs() < 1e-5);
+/// }
+/// ```
+pub struct BiquadRaw<S: AudioSource> {
+    source: S,
+    coefficients: Coefficients,
+    state: Vec<[f32; 2]>,
+}
+
+impl<S: AudioSource> BiquadRaw<S> {
+    /// Construct a filter from coefficients normalized so that `a0` is 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `b` -- The feedforward coefficients, `[b0, b1, b2]`.
+    /// * `a` -- The feedback coefficients, `[a1, a2]`.
+    pub fn new(source: S, b: [f32; 3], a: [f32; 2]) -> Self {
+        let channels = source.format().channels as usize;
+        BiquadRaw {
+            source,
+            coefficients: coefficients(b, [1.0, a[0], a[1]]),
+            state: vec![[0.0; 2]; channels],
+        }
+    }
+
+    /// Construct a filter from coefficients including `a0`, which are
+    /// normalized by dividing them all by `a0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `b` -- The feedforward coefficients, `[b0, b1, b2]`.
+    /// * `a` -- The feedback coefficients, `[a0, a1, a2]`.
+    ///
+    /// # Panics
+    ///
+    /// If `a0` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::BiquadRaw, generators::VecSource, AudioFormat, AudioSource};
+    /// let impulse = VecSource::new(vec![1.0, 0.0, 0.0], AudioFormat::MONO_CD);
+    /// let mut filter = BiquadRaw::with_a0(impulse, [2.0, 1.0, 0.0], [2.0, 0.0, 0.0]);
+    /// assert_eq!(filter.coefficients(), ([1.0, 0.5, 0.0], [0.0, 0.0]));
+    ///
+    /// let mut samples = vec![0.0; 3];
+    /// filter.read(&mut samples);
+    /// assert_eq!(samples, [1.0, 0.5, 0.0]);
+    /// ```
+    pub fn with_a0(source: S, b: [f32; 3], a: [f32; 3]) -> Self {
+        assert!(a[0] != 0.0, "a0 must not be zero.");
+        let channels = source.format().channels as usize;
+        BiquadRaw {
+            source,
+            coefficients: coefficients(b, a),
+            state: vec![[0.0; 2]; channels],
+        }
+    }
+
+    /// Replace the coefficients, normalized so that `a0` is 1. The filter's
+    /// state is kept, so this can be used to change the filter while playing.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` -- The feedforward coefficients, `[b0, b1, b2]`.
+    /// * `a` -- The feedback coefficients, `[a1, a2]`.
+    pub fn set_coefficients(&mut self, b: [f32; 3], a: [f32; 2]) {
+        self.coefficients = coefficients(b, [1.0, a[0], a[1]]);
+    }
+
+    /// Returns the normalized coefficients as `([b0, b1, b2], [a1, a2])`.
+    pub fn coefficients(&self) -> ([f32; 3], [f32; 2]) {
+        let Coefficients { b0, b1, b2, a1, a2 } = self.coefficients;
+        ([b0, b1, b2], [a1, a2])
+    }
+
+    fn reset(&mut self) {
+        self.state.iter_mut().for_each(|z| *z = [0.0; 2]);
+    }
+}
+
+fn coefficients(b: [f32; 3], a: [f32; 3]) -> Coefficients {
+    Coefficients::normalize(b[0], b[1], b[2], a[0], a[1], a[2])
+}
+
+impl<S: AudioSource> AudioSource for BiquadRaw<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "BiquadRaw::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.source.read(buffer);
+        let channels = self.source.format().channels as usize;
+        self.state.resize(channels, [0.0; 2]);
+        filter(
+            &mut buffer[..result.read],
+            &mut self.state,
+            &self.coefficients,
+        );
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for BiquadRaw<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for BiquadRaw<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for BiquadRaw<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for BiquadRaw<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset();
+        self.source.seek_frame(frame)
+    }
+}
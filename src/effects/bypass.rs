@@ -0,0 +1,248 @@
+use crate::{
+    core::AudioSource, effects::smoothed::Smoothed, AudioFormat, Latency, ReadResult, Rewind,
+    Sample, Seek, Tail,
+};
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use tracing::instrument;
+
+/// How far ahead of its output an effect may read before the oldest dry samples are dropped.
+const MAX_READ_AHEAD_FRAMES: usize = 8192;
+
+/// An adapter that can bypass any effect without a click.
+///
+/// While bypassed, the dry signal is played instead of the effect's output.
+/// The effect keeps running, so its internal state stays warm, e.g. an echo's
+/// tail is still there when the effect is switched back in. Toggling the
+/// bypass crossfades between the effect and the dry signal over a few
+/// milliseconds, so there's no discontinuity.
+///
+/// Like [`Oversample`](crate::effects::Oversample), the effect is constructed
+/// by a closure that receives the source, since the effect has to read from
+/// it. The effect must have the same format as the source, and the dry
+/// signal is aligned with the effect's reads, so any latency the effect adds
+/// isn't compensated for. If the effect keeps reading more from the source
+/// than it outputs, the oldest dry samples are dropped, so the dry signal
+/// never holds more than the larger of one read or 8192 frames.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Bypass, HighPass}, generators::SineWave, AudioFormat, AudioSource};
+/// let sine = || SineWave::with_format(AudioFormat::MONO_CD, 1.0, 100.0);
+/// let mut bypass = Bypass::new(sine(), |source| HighPass::new(source, 2000.0));
+/// let mut wet = HighPass::new(sine(), 2000.0);
+/// let mut dry = sine();
+///
+/// let mut samples = vec![0.0; 4410];
+/// let mut expected = vec![0.0; 4410];
+/// let mut previous = 0.0;
+/// let mut check = |samples: &[f32], previous: &mut f32| {
+///     // The 100 Hz tone is mostly filtered out, so a hard switch would jump.
+///     for &sample in samples {
+///         assert!((sample - *previous).abs() < 0.03);
+///         *previous = sample;
+///     }
+/// };
+///
+/// // Not bypassed, so this is the effect's output.
+/// bypass.read(&mut samples);
+/// wet.read(&mut expected);
+/// dry.read(&mut vec![0.0; 4410]);
+/// assert_eq!(samples, expected);
+/// check(&samples, &mut previous);
+///
+/// // Bypassing crossfades smoothly to the dry signal...
+/// bypass.set_bypass(true);
+/// bypass.read(&mut samples);
+/// dry.read(&mut expected);
+/// wet.read(&mut vec![0.0; 4410]);
+/// check(&samples, &mut previous);
+/// // ...which is all that's left once the crossfade is over.
+/// assert_eq!(samples[441..], expected[441..]);
+///
+/// // And back again.
+/// bypass.set_bypass(false);
+/// bypass.read(&mut samples);
+/// wet.read(&mut expected);
+/// check(&samples, &mut previous);
+/// assert_eq!(samples[441..], expected[441..]);
+/// ```
+pub struct Bypass<S: AudioSource, E: AudioSource> {
+    effect: E,
+    dry: Arc<Mutex<VecDeque<Sample>>>,
+    bypass: bool,
+    mix: Smoothed,
+    _source: std::marker::PhantomData<S>,
+}
+
+/// An [`AudioSource`](crate::AudioSource) that passes its source through,
+/// keeping a copy of the samples for [`Bypass`](crate::effects::Bypass).
+pub struct DryTap<S: AudioSource> {
+    source: S,
+    dry: Arc<Mutex<VecDeque<Sample>>>,
+}
+
+impl<S: AudioSource, E: AudioSource> Bypass<S, E> {
+    /// Construct a bypassable effect, initially not bypassed.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for the effect.
+    /// * `effect` -- Constructs the effect from the source.
+    ///
+    /// # Panics
+    ///
+    /// If the effect's format differs from the source's.
+    pub fn new<F: FnOnce(DryTap<S>) -> E>(source: S, effect: F) -> Self {
+        let format = source.format();
+        let dry = Arc::new(Mutex::new(VecDeque::new()));
+        let effect = effect(DryTap {
+            source,
+            dry: Arc::clone(&dry),
+        });
+        assert_eq!(
+            effect.format(),
+            format,
+            "A bypassed effect must not change the format."
+        );
+
+        Bypass {
+            effect,
+            dry,
+            bypass: false,
+            mix: Smoothed::new(0.0),
+            _source: std::marker::PhantomData,
+        }
+    }
+
+    /// Switch between the dry signal (`true`) and the effect (`false`),
+    /// crossfading over a few milliseconds.
+    pub fn set_bypass(&mut self, bypass: bool) {
+        self.bypass = bypass;
+        self.mix.set(if bypass { 1.0 } else { 0.0 });
+    }
+
+    pub fn is_bypassed(&self) -> bool {
+        self.bypass
+    }
+
+    /// Returns the effect, e.g. to change its parameters.
+    pub fn effect(&self) -> &E {
+        &self.effect
+    }
+
+    /// Returns the effect, e.g. to change its parameters.
+    pub fn effect_mut(&mut self) -> &mut E {
+        &mut self.effect
+    }
+}
+
+impl<S: AudioSource, E: AudioSource> AudioSource for Bypass<S, E> {
+    fn format(&self) -> AudioFormat {
+        self.effect.format()
+    }
+
+    #[instrument(name = "Bypass::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.effect.format();
+        let channels = format.channels as usize;
+        let result = self.effect.read(buffer);
+
+        let mut dry = self.dry.lock().unwrap();
+        if self.mix.current() == 0.0 && self.mix.target() == 0.0 {
+            let count = std::cmp::min(result.read, dry.len());
+            dry.drain(..count);
+        } else {
+            // Advance the crossfade frame by frame, so it takes the same time
+            // however large the reads are.
+            let frame_time = 1.0 / format.sample_rate as f32;
+            for frame in buffer[..result.read].chunks_exact_mut(channels) {
+                let (_, mix) = self.mix.advance(frame_time);
+                for sample in frame.iter_mut() {
+                    let dry = dry.pop_front().unwrap_or(0.0);
+                    *sample = *sample * (1.0 - mix) + dry * mix;
+                }
+            }
+        }
+
+        // An effect that keeps reading further ahead than it outputs would
+        // grow the dry signal forever, so drop the oldest samples.
+        let limit = std::cmp::max(
+            buffer.len(),
+            format.frames_to_samples(MAX_READ_AHEAD_FRAMES),
+        );
+        let excess = dry.len().saturating_sub(limit);
+        dry.drain(..excess);
+
+        result
+    }
+}
+
+impl<S: AudioSource, E: AudioSource + Latency> Latency for Bypass<S, E> {
+    fn latency_frames(&self) -> u64 {
+        self.effect.latency_frames()
+    }
+}
+
+impl<S: AudioSource, E: AudioSource + Tail> Tail for Bypass<S, E> {
+    fn tail_frames(&self) -> u64 {
+        self.effect.tail_frames()
+    }
+}
+
+impl<S: AudioSource> AudioSource for DryTap<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.source.read(buffer);
+        self.dry
+            .lock()
+            .unwrap()
+            .extend(buffer[..result.read].iter().copied());
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for DryTap<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for DryTap<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for DryTap<S> {
+    fn rewind(&mut self) -> bool {
+        self.dry.lock().unwrap().clear();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for DryTap<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.dry.lock().unwrap().clear();
+        self.source.seek_frame(frame)
+    }
+}
+
+impl<S: AudioSource, E: AudioSource + Rewind> Rewind for Bypass<S, E> {
+    fn rewind(&mut self) -> bool {
+        self.mix.finish();
+        self.effect.rewind()
+    }
+}
+
+impl<S: AudioSource, E: AudioSource + Seek> Seek for Bypass<S, E> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.mix.finish();
+        self.effect.seek_frame(frame)
+    }
+}
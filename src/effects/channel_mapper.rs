@@ -0,0 +1,163 @@
+use crate::{
+    core::AudioSource, effects::downmix::stereo_gains, AudioFormat, ChannelLayout, Latency,
+    ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// An adapter that converts a source to any number of channels, e.g. to wire a
+/// mono microphone into a stereo chain.
+///
+/// Each output channel is a weighted sum of the channels in one input frame:
+///
+/// * Mono is copied, unchanged, to every output channel.
+/// * Downmixing to mono averages the channels, as [`ToMono`](crate::effects::ToMono) does.
+/// * Surround layouts are downmixed to stereo as [`Downmix`](crate::effects::Downmix) does.
+/// * Otherwise, each channel is copied to the output channel with the same
+///   index; channels missing from the source are silent, and channels
+///   missing from the output are dropped.
+///
+/// The sample rate is unchanged. [`ReadResult::read`](crate::ReadResult)
+/// counts the samples produced, so a read consumes the same number of frames
+/// from the source as it produces.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::ChannelMapper, generators::VecSource, AudioFormat, AudioSource};
+/// let mono = VecSource::new(vec![0.1, 0.2], AudioFormat::MONO_CD);
+/// let mut stereo = ChannelMapper::new(mono, 2);
+/// assert_eq!(stereo.format(), AudioFormat::STEREO_CD);
+///
+/// let mut samples = vec![0.0; 4];
+/// assert_eq!(stereo.read(&mut samples).read, 4);
+/// assert_eq!(samples, [0.1, 0.1, 0.2, 0.2]);
+///
+/// let stereo = VecSource::new(vec![1.0, 0.0, 0.5, 0.25], AudioFormat::STEREO_CD);
+/// let mut mono = ChannelMapper::new(stereo, 1);
+/// assert_eq!(mono.format(), AudioFormat::MONO_CD);
+///
+/// let mut samples = vec![0.0; 2];
+/// assert_eq!(mono.read(&mut samples).read, 2);
+/// assert_eq!(samples, [0.5, 0.375]);
+/// ```
+pub struct ChannelMapper<S: AudioSource> {
+    source: S,
+    channels: u8,
+    /// The gain of each source channel in each output channel, by output channel.
+    gains: Vec<Vec<f32>>,
+    buffer: Vec<Sample>,
+}
+
+impl<S: AudioSource> ChannelMapper<S> {
+    /// Construct a channel mapper.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to convert.
+    /// * `target_channels` -- The number of channels to convert to.
+    ///
+    /// # Panics
+    ///
+    /// If `target_channels` is zero.
+    pub fn new(source: S, target_channels: u8) -> Self {
+        assert!(target_channels > 0, "There must be at least one channel.");
+        let gains = gains(source.format().channels, target_channels);
+        ChannelMapper {
+            source,
+            channels: target_channels,
+            gains,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<S: AudioSource> AudioSource for ChannelMapper<S> {
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            channels: self.channels,
+            ..self.source.format()
+        }
+    }
+
+    #[instrument(name = "ChannelMapper::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let from = self.source.format().channels as usize;
+        let to = self.channels as usize;
+        if from == to {
+            return self.source.read(buffer);
+        }
+        if self.gains.first().map_or(0, Vec::len) != from {
+            self.gains = gains(from as u8, self.channels);
+        }
+
+        let frames = buffer.len() / to;
+        self.buffer.resize(frames * from, 0.0);
+        let result = self.source.read(&mut self.buffer);
+
+        let input = self.buffer[..result.read].chunks_exact(from);
+        for (output, input) in buffer.chunks_exact_mut(to).zip(input) {
+            for (sample, gains) in output.iter_mut().zip(self.gains.iter()) {
+                *sample = input.iter().zip(gains.iter()).map(|(s, g)| s * g).sum();
+            }
+        }
+
+        ReadResult {
+            state: result.state,
+            read: result.read / from * to,
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for ChannelMapper<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for ChannelMapper<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for ChannelMapper<S> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for ChannelMapper<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
+
+/// Returns the gain of each of `from`'s channels in each of `to`'s channels.
+fn gains(from: u8, to: u8) -> Vec<Vec<f32>> {
+    let layout = ChannelLayout::from_channels(from);
+    let (from, to) = (from as usize, to as usize);
+    let speaker_gains = || layout.speakers().iter().map(|&s| stereo_gains(s));
+
+    if from == 1 {
+        vec![vec![1.0]; to]
+    } else if to == 1 && layout.is_positioned() {
+        vec![speaker_gains()
+            .map(|(left, right)| (left + right) / 2.0)
+            .collect()]
+    } else if to == 1 {
+        vec![vec![1.0 / from as f32; from]]
+    } else if to == 2 && from > 2 && layout.is_positioned() {
+        vec![
+            speaker_gains().map(|(left, _)| left).collect(),
+            speaker_gains().map(|(_, right)| right).collect(),
+        ]
+    } else {
+        (0..to)
+            .map(|output| {
+                (0..from)
+                    .map(|input| if input == output { 1.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect()
+    }
+}
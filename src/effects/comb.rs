@@ -0,0 +1,167 @@
+use crate::{core::AudioSource, flush_denormal, Latency, ReadResult, Sample, StreamState, Tail};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// A feedback comb filter, one of the building blocks of Schroeder reverbs.
+///
+/// Each output sample is the input plus the output from `delay` earlier,
+/// multiplied by `feedback`. An impulse becomes a train of spikes spaced by the
+/// delay, each quieter than the last, which gives a metallic resonance at
+/// multiples of `1 / delay`.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Comb, AudioFormat, AudioSource, ReadResult, Sample};
+/// # use std::time::Duration;
+/// // A single full-scale sample followed by silence.
+/// struct Impulse(bool);
+///
+/// impl AudioSource for Impulse {
+///     fn format(&self) -> AudioFormat { AudioFormat::MONO_CD }
+///     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+///         buffer.iter_mut().for_each(|s| *s = 0.0);
+///         if !self.0 {
+///             buffer[0] = 1.0;
+///             self.0 = true;
+///         }
+///         ReadResult::good(buffer.len())
+///     }
+/// }
+///
+/// // 10ms at 44.1 kHz is 441 frames.
+/// let mut comb = Comb::new(Impulse(false), Duration::from_millis(10), 0.5);
+/// let mut samples = vec![0.0; 2048];
+/// comb.read(&mut samples);
+///
+/// for (i, &sample) in samples.iter().enumerate() {
+///     if i % 441 == 0 {
+///         assert_eq!(sample, 0.5f32.powi((i / 441) as i32));
+///     } else {
+///         assert_eq!(sample, 0.0);
+///     }
+/// }
+/// ```
+pub struct Comb<S: AudioSource> {
+    source: S,
+    delay: Duration,
+    feedback: f32,
+    buffer: Vec<f32>,
+    position: usize,
+    finished: bool,
+}
+
+impl<S: AudioSource> Comb<S> {
+    /// Construct a feedback comb filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `delay` -- The spacing between repetitions, rounded to the nearest frame.
+    /// * `feedback` -- The gain applied on each repetition. Should be between -1.0 and 1.0 to stay stable.
+    ///
+    /// # Panics
+    ///
+    /// If `delay` is shorter than one frame.
+    pub fn new(source: S, delay: Duration, feedback: f32) -> Self {
+        let length = delay_line_length(&source, delay);
+        Comb {
+            source,
+            delay,
+            feedback,
+            buffer: vec![0.0; length],
+            position: 0,
+            finished: false,
+        }
+    }
+
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback;
+    }
+
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+impl<S: AudioSource> AudioSource for Comb<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Comb::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = read_or_ring(&mut self.source, &mut self.finished, buffer);
+
+        for sample in buffer[..result.read].iter_mut() {
+            let delayed = &mut self.buffer[self.position];
+            *delayed = flush_denormal(*sample + self.feedback * *delayed);
+            *sample = *delayed;
+            self.position = (self.position + 1) % self.buffer.len();
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Comb<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Comb<S> {
+    /// The repetitions continue until they decay below -60 dB, or forever if
+    /// `feedback` is 1.0 or more in magnitude.
+    fn tail_frames(&self) -> u64 {
+        let delay = (self.buffer.len() / self.source.format().channels as usize) as u64;
+        let repetitions = decay_repetitions(self.feedback);
+        delay
+            .saturating_mul(repetitions)
+            .saturating_add(self.source.tail_frames())
+    }
+}
+
+/// Read from `source`, or once it has finished, fill `buffer` with silence so a
+/// delay line can keep ringing.
+pub(crate) fn read_or_ring<S: AudioSource>(
+    source: &mut S,
+    finished: &mut bool,
+    buffer: &mut [Sample],
+) -> ReadResult {
+    let result = if *finished {
+        let written = buffer.len() - buffer.len() % source.format().channels as usize;
+        buffer[..written].iter_mut().for_each(|s| *s = 0.0);
+        ReadResult::finished(written)
+    } else {
+        source.read(buffer)
+    };
+    *finished = result.state == StreamState::Finished;
+    result
+}
+
+/// Returns how many times a repetition scaled by `feedback` each time takes to
+/// decay below -60 dB, or `u64::MAX` if it never does.
+pub(crate) fn decay_repetitions(feedback: f32) -> u64 {
+    let feedback = feedback.abs();
+    if feedback == 0.0 {
+        0
+    } else if feedback >= 1.0 {
+        u64::MAX
+    } else {
+        (0.001f32.ln() / feedback.ln()).ceil() as u64
+    }
+}
+
+/// Returns the length in samples of an interleaved delay line of `delay`.
+pub(crate) fn delay_line_length<S: AudioSource>(source: &S, delay: Duration) -> usize {
+    let format = source.format();
+    let frames = format.duration_to_frames(delay) as usize;
+    assert!(frames > 0, "Delay must be at least one frame.");
+    format.frames_to_samples(frames)
+}
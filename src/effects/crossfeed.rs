@@ -0,0 +1,150 @@
+use crate::{
+    core::AudioSource, flush_denormal, AudioFormat, Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// The cutoff of the low-pass filter on the crossfeed path, in Hz. The head
+/// shadows high frequencies from the far ear.
+const CUTOFF: f32 = 700.0;
+/// The extra time sound from a speaker takes to reach the far ear, in seconds.
+const DELAY_SECONDS: f32 = 0.0003;
+
+/// An effect that makes headphone listening more like listening to speakers.
+///
+/// With speakers, each ear also hears the opposite speaker, slightly later
+/// and with the highs shadowed by the head. Headphones lose this, so
+/// hard-panned mixes sound unnaturally wide and can be tiring. `Crossfeed`
+/// bleeds a low-passed, slightly delayed copy of each channel into the other.
+///
+/// The output is scaled by `1 / (1 + amount)` so that centered sounds, which
+/// are in both channels, don't get louder.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Crossfeed, Pan}, generators::SineWave, render_frames, AudioFormat};
+/// // A low tone panned hard left.
+/// let panned = || Pan::new(SineWave::with_format(AudioFormat::MONO_CD, 0.5, 200.0), -1.0);
+/// let difference = |samples: &[f32]| -> f32 {
+///     samples.chunks_exact(2).map(|frame| (frame[0] - frame[1]).powi(2)).sum()
+/// };
+///
+/// // With no crossfeed, the source passes through untouched.
+/// let (dry, _) = render_frames(panned(), 44100);
+/// let (passthrough, _) = render_frames(Crossfeed::new(panned(), 0.0), 44100);
+/// assert_eq!(passthrough, dry);
+///
+/// // Crossfeed narrows the image.
+/// let (crossfed, _) = render_frames(Crossfeed::new(panned(), 0.5), 44100);
+/// assert!(difference(&crossfed) < 0.5 * difference(&dry));
+/// ```
+pub struct Crossfeed<S: AudioSource> {
+    source: S,
+    amount: f32,
+    delay_line: Vec<[Sample; 2]>,
+    position: usize,
+    filtered: [Sample; 2],
+}
+
+impl<S: AudioSource> Crossfeed<S> {
+    /// Construct a crossfeed effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The stereo source of audio for this effect.
+    /// * `amount` -- How much of each channel to feed into the other, from
+    ///   0.0 (none) to 1.0. Around 0.3 is subtle; higher values are more speaker-like.
+    ///
+    /// # Panics
+    ///
+    /// If `source` is not stereo.
+    pub fn new(source: S, amount: f32) -> Self {
+        assert_eq!(
+            source.format().channels,
+            2,
+            "Crossfeed requires a stereo source."
+        );
+        Crossfeed {
+            source,
+            amount: amount.clamp(0.0, 1.0),
+            delay_line: Vec::new(),
+            position: 0,
+            filtered: [0.0; 2],
+        }
+    }
+
+    /// Set how much of each channel to feed into the other, clamped to the range [0.0, 1.0].
+    pub fn set_amount(&mut self, amount: f32) {
+        self.amount = amount.clamp(0.0, 1.0);
+    }
+
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    fn clear(&mut self) {
+        self.delay_line.clear();
+        self.position = 0;
+        self.filtered = [0.0; 2];
+    }
+}
+
+impl<S: AudioSource> AudioSource for Crossfeed<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Crossfeed::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let sample_rate = self.source.format().sample_rate as f32;
+        let delay = std::cmp::max(1, (DELAY_SECONDS * sample_rate).round() as usize);
+        self.delay_line.resize(delay, [0.0; 2]);
+        self.position %= delay;
+        let a = 1.0 - (-2.0 * std::f32::consts::PI * CUTOFF / sample_rate).exp();
+        let scale = 1.0 / (1.0 + self.amount);
+
+        let result = self.source.read(buffer);
+        for frame in buffer[..result.read].chunks_exact_mut(2) {
+            let delayed =
+                std::mem::replace(&mut self.delay_line[self.position], [frame[0], frame[1]]);
+            self.position = (self.position + 1) % delay;
+
+            // Each channel hears the other's delayed, low-passed signal.
+            for (filtered, &input) in self.filtered.iter_mut().zip(delayed.iter().rev()) {
+                *filtered = flush_denormal(*filtered + a * (input - *filtered));
+            }
+            frame[0] = (frame[0] + self.amount * self.filtered[0]) * scale;
+            frame[1] = (frame[1] + self.amount * self.filtered[1]) * scale;
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Crossfeed<S> {
+    /// Only the signal bled into the opposite channel is delayed, as sound
+    /// reaching the far ear would be. Each channel's own signal isn't.
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Crossfeed<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Crossfeed<S> {
+    fn rewind(&mut self) -> bool {
+        self.clear();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Crossfeed<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.clear();
+        self.source.seek_frame(frame)
+    }
+}
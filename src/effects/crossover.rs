@@ -0,0 +1,170 @@
+use crate::{
+    core::{AudioSource, SharedAudioSource},
+    effects::{
+        biquad::{filter, Coefficients},
+        Tee,
+    },
+    AudioFormat, ReadResult, Sample,
+};
+
+use std::sync::{Arc, Mutex};
+
+use tracing::instrument;
+
+/// Splits a source into low and high frequency bands, e.g. for bi-amping or
+/// multiband processing.
+///
+/// Each band is filtered by a 4th-order Linkwitz-Riley filter: two cascaded
+/// Butterworth biquads. At the crossover frequency both bands are 6 dB down
+/// and in phase, so adding the bands back together gives a flat magnitude
+/// response. The sum is not identical to the input, though: like any
+/// crossover, it shifts the phase of frequencies near the cutoff.
+///
+/// The bands read from the source through a [`Tee`](crate::effects::Tee), so
+/// the same buffering caveats apply: a band that stops being read causes the
+/// buffer to grow.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Crossover, generators::SineWave, AudioFormat, AudioSource};
+/// let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+///
+/// // Adding the bands back together is flat at, above, and below the crossover.
+/// for &frequency in [100.0, 500.0, 1000.0, 2000.0, 8000.0].iter() {
+///     let sine = SineWave::with_format(AudioFormat::MONO_CD, 0.5, frequency);
+///     let crossover = Crossover::new(sine, 1000.0);
+///     let low = crossover.low();
+///     let high = crossover.high();
+///
+///     let mut low_samples = vec![0.0; 8192];
+///     let mut high_samples = vec![0.0; 8192];
+///     low.lock().unwrap().read(&mut low_samples);
+///     high.lock().unwrap().read(&mut high_samples);
+///     let sum: Vec<f32> = low_samples.iter().zip(high_samples.iter()).map(|(l, h)| l + h).collect();
+///
+///     // Skip the filters' start-up transient.
+///     assert!((peak(&sum[4096..]) - 0.5).abs() < 0.005);
+///     if frequency == 1000.0 {
+///         // Each band is 6 dB down at the crossover.
+///         assert!((peak(&low_samples[4096..]) - 0.25).abs() < 0.005);
+///         assert!((peak(&high_samples[4096..]) - 0.25).abs() < 0.005);
+///     }
+/// }
+/// ```
+pub struct Crossover<S: AudioSource + Send + 'static> {
+    tee: Tee<S>,
+    frequency: f32,
+}
+
+impl<S: AudioSource + Send + 'static> Crossover<S> {
+    /// Construct a crossover.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to split.
+    /// * `frequency` -- The crossover frequency, in Hz.
+    pub fn new(source: S, frequency: f32) -> Self {
+        Crossover {
+            tee: Tee::new(source),
+            frequency,
+        }
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Create a source that plays the frequencies below the crossover.
+    ///
+    /// Like [`Tee::branch`](crate::effects::Tee::branch), each call creates a
+    /// new reader of the source.
+    pub fn low(&self) -> SharedAudioSource {
+        self.band(Band::Low)
+    }
+
+    /// Create a source that plays the frequencies above the crossover.
+    ///
+    /// Like [`Tee::branch`](crate::effects::Tee::branch), each call creates a
+    /// new reader of the source.
+    pub fn high(&self) -> SharedAudioSource {
+        self.band(Band::High)
+    }
+
+    fn band(&self, band: Band) -> SharedAudioSource {
+        let source = self.tee.branch();
+        let channels = source.format().channels as usize;
+        Arc::new(Mutex::new(BandSource {
+            source,
+            filter: LinkwitzRiley::new(band, self.frequency, channels),
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Band {
+    Low,
+    High,
+}
+
+/// A 4th-order Linkwitz-Riley filter, made of two cascaded Butterworth biquads.
+pub(crate) struct LinkwitzRiley {
+    band: Band,
+    frequency: f32,
+    stages: [Vec<[f32; 2]>; 2],
+}
+
+impl LinkwitzRiley {
+    /// The Q of a Butterworth biquad.
+    pub const Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+    pub fn new(band: Band, frequency: f32, channels: usize) -> Self {
+        LinkwitzRiley {
+            band,
+            frequency,
+            stages: [vec![[0.0; 2]; channels], vec![[0.0; 2]; channels]],
+        }
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Filter interleaved `samples` in place.
+    pub fn process(&mut self, samples: &mut [Sample], format: AudioFormat) {
+        let coefficients = match self.band {
+            Band::Low => Coefficients::low_pass(format.sample_rate, self.frequency, Self::Q),
+            Band::High => Coefficients::high_pass(format.sample_rate, self.frequency, Self::Q),
+        };
+        for stage in self.stages.iter_mut() {
+            stage.resize(format.channels as usize, [0.0; 2]);
+            filter(samples, stage, &coefficients);
+        }
+    }
+
+    /// Clear the filter's state, as if it had only ever seen silence.
+    pub fn reset(&mut self) {
+        for stage in self.stages.iter_mut() {
+            stage.iter_mut().for_each(|z| *z = [0.0; 2]);
+        }
+    }
+}
+
+/// One band of a [`Crossover`], reading from its own branch of the source.
+struct BandSource {
+    source: SharedAudioSource,
+    filter: LinkwitzRiley,
+}
+
+impl AudioSource for BandSource {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Crossover::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let result = self.source.lock().unwrap().read(buffer);
+        self.filter.process(&mut buffer[..result.read], format);
+        result
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    core::AudioSource, AudioFormat, ChannelLayout, Latency, ReadResult, Rewind, Sample, Seek,
+    Speaker, Tail,
+};
+
+use tracing::instrument;
+
+/// The -3 dB gain used for center and surround channels.
+const MINUS_3_DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Returns the `(left, right)` gains with which a speaker is mixed into
+/// stereo, following the usual ITU-R BS.775 coefficients: front channels go
+/// to their side unchanged, center and surrounds are mixed at -3 dB, and the
+/// LFE channel is dropped.
+pub(crate) fn stereo_gains(speaker: Speaker) -> (f32, f32) {
+    match speaker {
+        Speaker::FrontLeft | Speaker::FrontLeftOfCenter => (1.0, 0.0),
+        Speaker::FrontRight | Speaker::FrontRightOfCenter => (0.0, 1.0),
+        Speaker::FrontCenter => (MINUS_3_DB, MINUS_3_DB),
+        Speaker::LowFrequency => (0.0, 0.0),
+        Speaker::BackLeft | Speaker::SideLeft => (MINUS_3_DB, 0.0),
+        Speaker::BackRight | Speaker::SideRight => (0.0, MINUS_3_DB),
+        Speaker::BackCenter => (0.5, 0.5),
+        Speaker::Other => (MINUS_3_DB, MINUS_3_DB),
+    }
+}
+
+/// An adapter that downmixes a multichannel source, such as 5.1 surround, to stereo.
+///
+/// Each channel is mixed into the left and right outputs according to the
+/// speaker it's meant for: front left and right pass through, the center and
+/// surround channels are mixed in at -3 dB, and the LFE channel is dropped.
+/// If any channel's position is unknown, every channel is instead mixed
+/// equally into both outputs.
+///
+/// The coefficients aren't normalized, so loud surround content can exceed
+/// full scale after downmixing.
+///
+/// Because the output has two samples per frame, each read consumes
+/// `channels / 2` times as many samples from the source as it produces.
+/// [`ReadResult::read`](crate::ReadResult) counts the stereo samples produced.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Downmix, generators::VecSource, AudioFormat, AudioSource};
+/// # use std::f32::consts::FRAC_1_SQRT_2;
+/// // One frame of 5.1: left, right, center, LFE, back left, back right.
+/// let format = AudioFormat { channels: 6, sample_rate: 48000 };
+/// let surround = VecSource::new(vec![0.1, 0.2, 0.3, 1.0, 0.4, 0.5], format);
+/// let mut stereo = Downmix::new(surround);
+/// assert_eq!(stereo.format(), AudioFormat::STEREO_DVD);
+///
+/// let mut samples = vec![0.0; 2];
+/// assert_eq!(stereo.read(&mut samples).read, 2);
+///
+/// // The center and surrounds are mixed in at -3 dB; the LFE is dropped.
+/// let left = 0.1 + FRAC_1_SQRT_2 * 0.3 + FRAC_1_SQRT_2 * 0.4;
+/// let right = 0.2 + FRAC_1_SQRT_2 * 0.3 + FRAC_1_SQRT_2 * 0.5;
+/// assert!((samples[0] - left).abs() < 1e-6);
+/// assert!((samples[1] - right).abs() < 1e-6);
+/// ```
+pub struct Downmix<S: AudioSource> {
+    source: S,
+    gains: Vec<(f32, f32)>,
+    buffer: Vec<Sample>,
+}
+
+impl<S: AudioSource> Downmix<S> {
+    /// Construct a stereo downmix, using the default layout for the source's
+    /// number of channels (see [`ChannelLayout::from_channels`](crate::ChannelLayout::from_channels)).
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to downmix.
+    pub fn new(source: S) -> Self {
+        let layout = ChannelLayout::from_channels(source.format().channels);
+        Downmix::with_layout(source, layout)
+    }
+
+    /// Construct a stereo downmix of a source with the given channel layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to downmix.
+    /// * `layout` -- The speaker each of the source's channels is meant for.
+    ///
+    /// # Panics
+    ///
+    /// If `layout` doesn't have the same number of channels as `source`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::Downmix, generators::VecSource, AudioFormat, AudioSource, ChannelLayout};
+    /// // A 3.1 file: left, right, center, LFE.
+    /// let format = AudioFormat { channels: 4, sample_rate: 48000 };
+    /// let source = VecSource::new(vec![0.0, 0.0, 0.0, 1.0], format);
+    /// let mut stereo = Downmix::with_layout(source, ChannelLayout::from_mask(0xF, 4));
+    ///
+    /// let mut samples = vec![1.0; 2];
+    /// stereo.read(&mut samples);
+    /// assert_eq!(samples, [0.0, 0.0]);
+    /// ```
+    pub fn with_layout(source: S, layout: ChannelLayout) -> Self {
+        assert_eq!(
+            layout.channels(),
+            source.format().channels as usize,
+            "The channel layout must match the source's channels."
+        );
+        let gains = if layout.is_positioned() {
+            layout.speakers().iter().map(|&s| stereo_gains(s)).collect()
+        } else {
+            let gain = 1.0 / layout.channels() as f32;
+            vec![(gain, gain); layout.channels()]
+        };
+        Downmix {
+            source,
+            gains,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<S: AudioSource> AudioSource for Downmix<S> {
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            channels: 2,
+            ..self.source.format()
+        }
+    }
+
+    #[instrument(name = "Downmix::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.gains.len();
+        let frames = buffer.len() / 2;
+        self.buffer.resize(frames * channels, 0.0);
+        let result = self.source.read(&mut self.buffer);
+
+        let input = self.buffer[..result.read].chunks_exact(channels);
+        for (output, input) in buffer.chunks_exact_mut(2).zip(input) {
+            let (left, right) = input
+                .iter()
+                .zip(self.gains.iter())
+                .fold((0.0, 0.0), |(left, right), (sample, gain)| {
+                    (left + sample * gain.0, right + sample * gain.1)
+                });
+            output[0] = left;
+            output[1] = right;
+        }
+
+        ReadResult {
+            state: result.state,
+            read: result.read / channels * 2,
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Downmix<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Downmix<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Downmix<S> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Downmix<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
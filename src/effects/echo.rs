@@ -1,4 +1,8 @@
-use crate::{core::AudioSource, ReadResult, Sample};
+use crate::{
+    core::AudioSource, flush_denormal, Latency, ReadResult, Rewind, Sample, Seek, StreamState, Tail,
+};
+
+use std::time::Duration;
 
 use tracing::instrument;
 
@@ -11,12 +15,42 @@ use tracing::instrument;
 /// let sin = SineWave::new(1.0, 440.0);
 /// let echo = Echo::new(sin, Duration::from_secs_f32(0.5), 0.8);
 /// ```
+///
+/// An impulse repeats exactly `delay` frames later, whether reads are shorter
+/// than, equal to, or longer than the delay:
+/// ```
+/// # use timbre::{effects::Echo, generators::VecSource, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// let delay = Duration::from_secs_f64(100.0 / 44100.0);
+/// for &block in [1, 37, 100, 256, 1000].iter() {
+///     let mut impulse = vec![0.0; 1000];
+///     impulse[0] = 1.0;
+///     let source = VecSource::new(impulse, AudioFormat::MONO_CD);
+///     let mut echo = Echo::new(source, delay, 0.5);
+///     assert_eq!(echo.delay_samples(), 100);
+///
+///     let mut output = Vec::new();
+///     let mut samples = vec![0.0; block];
+///     while output.len() < 1000 {
+///         let read = echo.read(&mut samples).read;
+///         output.extend_from_slice(&samples[..read]);
+///     }
+///
+///     for (i, &sample) in output.iter().enumerate() {
+///         let expected = if i % 100 == 0 { 0.5f32.powi(i as i32 / 100) } else { 0.0 };
+///         assert_eq!(sample, expected, "block size {}, sample {}", block, i);
+///     }
+/// }
+/// ```
 pub struct Echo<S: AudioSource> {
     source: S,
-    delay: f32,
+    delay: Duration,
     decay: f32,
+    mix: f32,
+    wet_only: bool,
     buffer: Vec<f32>,
     position: usize,
+    finished: bool,
 }
 
 impl<S: AudioSource> Echo<S> {
@@ -28,15 +62,157 @@ impl<S: AudioSource> Echo<S> {
     /// * `delay` -- The length of time before the echo plays back.
     /// * `decay` -- The amount by which to decay the echo on each repitition. Should
     ///              be between 0.0 and 1.0, unless you like feedback.
-    pub fn new(source: S, delay: std::time::Duration, decay: f32) -> Self {
-        let delay = delay.as_secs_f32();
+    pub fn new(source: S, delay: Duration, decay: f32) -> Self {
+        Echo::with_mix(source, delay, decay, 1.0)
+    }
+
+    /// Construct a new `Echo` effect with the given wet/dry mix.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `delay` -- The length of time before the echo plays back.
+    /// * `decay` -- The amount by which to decay the echo on each repitition.
+    /// * `mix` -- How much of the echoed signal to output, from 0.0 (dry only) to 1.0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::Echo, generators::{SineWave, VecSource}, AudioFormat, AudioSource};
+    /// # use std::time::Duration;
+    /// let delay = Duration::from_millis(10);
+    /// let mut dry = SineWave::new(1.0, 440.0);
+    /// let mut passthrough = Echo::with_mix(SineWave::new(1.0, 440.0), delay, 0.5, 0.0);
+    /// let mut full = Echo::new(SineWave::new(1.0, 440.0), delay, 0.5);
+    /// let mut wet = Echo::with_mix(SineWave::new(1.0, 440.0), delay, 0.5, 1.0);
+    ///
+    /// let read = |source: &mut dyn AudioSource| {
+    ///     let mut samples = vec![0.0; 4096];
+    ///     source.read(&mut samples);
+    ///     samples
+    /// };
+    ///
+    /// // With no wet signal, the source passes through untouched.
+    /// assert_eq!(read(&mut passthrough), read(&mut dry));
+    /// // Fully wet is the default.
+    /// assert_eq!(read(&mut wet), read(&mut full));
+    ///
+    /// // At an even mix, an impulse plays in full, and each repetition plays at
+    /// // half of its decayed level: 1.0 * 0.5 * 0.5 after one delay.
+    /// let mut impulse = vec![0.0; 300];
+    /// impulse[0] = 1.0;
+    /// let source = VecSource::new(impulse, AudioFormat::MONO_CD);
+    /// let mut half = Echo::with_mix(source, Duration::from_secs_f64(100.0 / 44100.0), 0.5, 0.5);
+    /// let samples = read(&mut half);
+    /// assert_eq!(samples[0], 1.0);
+    /// assert_eq!(samples[100], 0.25);
+    /// assert_eq!(samples[200], 0.125);
+    /// assert_eq!(samples[..300].iter().filter(|&&sample| sample != 0.0).count(), 3);
+    /// ```
+    pub fn with_mix(source: S, delay: Duration, decay: f32, mix: f32) -> Self {
         Echo {
             source,
             delay,
             decay,
+            mix: mix.clamp(0.0, 1.0),
+            wet_only: false,
             buffer: Vec::new(),
             position: 0,
+            finished: false,
+        }
+    }
+
+    /// Set how much of the echoed signal to output, clamped to the range [0.0, 1.0].
+    ///
+    /// The output is `dry * (1.0 - mix) + echoed * mix`, where the echoed
+    /// signal includes the source plus its repetitions.
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix = mix.clamp(0.0, 1.0);
+    }
+
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Set whether to output only the repetitions, without the dry signal.
+    ///
+    /// This is for aux sends: mix the dry source and a wet-only `Echo` with a
+    /// [`BasicMixer`](crate::effects::BasicMixer) to blend them at any ratio.
+    /// The repetitions are still scaled by [`mix`](Echo::mix), so a dry source
+    /// plus a wet-only echo matches a single echo with the same mix.
+    pub fn set_wet_only(&mut self, wet_only: bool) {
+        self.wet_only = wet_only;
+    }
+
+    pub fn wet_only(&self) -> bool {
+        self.wet_only
+    }
+
+    /// Set the length of time before the echo plays back.
+    ///
+    /// Changing the delay discards any echoes that haven't played yet.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+        self.buffer.clear();
+        self.position = 0;
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Returns the length of the delay line in samples, for the source's current format.
+    ///
+    /// The delay is rounded to the nearest whole frame.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::Echo};
+    /// # use std::time::Duration;
+    /// let sin = SineWave::new(1.0, 440.0);
+    /// let echo = Echo::new(sin, Duration::from_secs_f32(0.5), 0.8);
+    /// assert_eq!(echo.delay_samples(), 22050 * 2);
+    /// ```
+    pub fn delay_samples(&self) -> usize {
+        let format = self.source.format();
+        format.frames_to_samples(format.duration_to_frames(self.delay) as usize)
+    }
+
+    /// Returns a copy of the delay line, oldest sample first.
+    ///
+    /// Each entry is the source plus its decayed repetitions, as it will be
+    /// mixed into the output once the delay has elapsed: the first sample plays
+    /// back on the next read, and the last is the most recent input. The copy
+    /// has [`delay_samples`](Echo::delay_samples) samples, which are all zero
+    /// before the first read.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::Echo, generators::VecSource, AudioFormat, AudioSource};
+    /// # use std::time::Duration;
+    /// let mut impulse = vec![0.0; 10];
+    /// impulse[0] = 1.0;
+    /// let source = VecSource::new(impulse, AudioFormat::MONO_CD);
+    /// let mut echo = Echo::new(source, Duration::from_secs_f64(100.0 / 44100.0), 0.5);
+    /// assert_eq!(echo.delay_buffer_snapshot(), vec![0.0; 100]);
+    ///
+    /// // After 10 samples, the impulse is 10 samples from the end of the line.
+    /// echo.read(&mut vec![0.0; 10]);
+    /// let snapshot = echo.delay_buffer_snapshot();
+    /// assert_eq!(snapshot.len(), 100);
+    /// assert_eq!(snapshot[90], 1.0);
+    /// assert_eq!(snapshot.iter().filter(|&&sample| sample != 0.0).count(), 1);
+    /// ```
+    pub fn delay_buffer_snapshot(&self) -> Vec<f32> {
+        let delay = self.delay_samples();
+        if self.buffer.len() != delay {
+            return vec![0.0; delay];
         }
+
+        let position = self.position % delay.max(1);
+        let mut snapshot = Vec::with_capacity(delay);
+        snapshot.extend_from_slice(&self.buffer[position..]);
+        snapshot.extend_from_slice(&self.buffer[..position]);
+        snapshot
     }
 }
 
@@ -47,14 +223,28 @@ impl<S: AudioSource> AudioSource for Echo<S> {
 
     #[instrument(name = "Echo::read", skip(self, buffer))]
     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
-        let format = self.source.format();
-        let delay: usize =
-            (format.sample_rate as f32 * self.delay).ceil() as usize * format.channels as usize;
+        let delay = self.delay_samples();
         self.buffer.resize(delay, 0.0);
 
-        let status = self.source.read(buffer);
+        // Once the source has finished, keep playing the repetitions of what
+        // it left behind, as if it were followed by silence.
+        let status = if self.finished {
+            let written = buffer.len() - buffer.len() % self.source.format().channels as usize;
+            buffer[..written].iter_mut().for_each(|s| *s = 0.0);
+            ReadResult::finished(written)
+        } else {
+            self.source.read(buffer)
+        };
+        self.finished = status.state == StreamState::Finished;
         let written = status.read;
+        if delay == 0 {
+            return status;
+        }
+        self.position %= delay;
 
+        // The output is the dry signal plus the repetitions scaled by the mix,
+        // which is the same as crossfading from dry to echoed.
+        let dry_gain = if self.wet_only { 0.0 } else { 1.0 };
         echo(
             &mut self.buffer,
             buffer,
@@ -62,29 +252,80 @@ impl<S: AudioSource> AudioSource for Echo<S> {
             &mut self.position,
             delay,
             self.decay,
+            (dry_gain, self.mix),
         );
 
         status
     }
 }
 
+impl<S: AudioSource + Latency> Latency for Echo<S> {
+    /// Unless [`wet_only`](Echo::set_wet_only) is set, the dry signal passes
+    /// straight through. A wet-only `Echo` is silent for the first `delay`, but
+    /// the repetitions are the effect itself, so it reports only the source's
+    /// latency either way.
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Echo<S> {
+    /// The repetitions continue until they decay below -60 dB, or forever if
+    /// `decay` is 1.0 or more.
+    fn tail_frames(&self) -> u64 {
+        let delay = (self.delay_samples() / self.source.format().channels as usize) as u64;
+        if delay == 0 || self.decay <= 0.0 {
+            return self.source.tail_frames();
+        }
+        if self.decay >= 1.0 {
+            return u64::MAX;
+        }
+
+        let repetitions = (0.001f32.ln() / self.decay.ln()).ceil() as u64;
+        (repetitions * delay).saturating_add(self.source.tail_frames())
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Echo<S> {
+    fn rewind(&mut self) -> bool {
+        self.buffer.clear();
+        self.position = 0;
+        self.finished = false;
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Echo<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.buffer.clear();
+        self.position = 0;
+        self.finished = false;
+        self.source.seek_frame(frame)
+    }
+}
+
 fn echo(
-    buffer: &mut Vec<f32>,
+    buffer: &mut [f32],
     samples: &mut [f32],
     written: usize,
     position: &mut usize,
     delay: usize,
     decay: f32,
+    (dry_gain, wet_gain): (f32, f32),
 ) {
+    // Process the samples in runs that end at either the end of the delay
+    // line or the end of the samples, so reads of any length wrap correctly.
     let mut i = 0;
     while i < written {
         let count = std::cmp::min(delay - *position, written - i);
-        (&mut buffer[*position..delay])
+        buffer[*position..*position + count]
             .iter_mut()
-            .zip((&mut samples[i..written]).iter_mut())
+            .zip(samples[i..i + count].iter_mut())
             .for_each(|(b, s)| {
-                *b = *b * decay + *s;
-                *s = *b;
+                let dry = *s;
+                let repeated = *b * decay;
+                *b = flush_denormal(repeated + dry);
+                *s = dry * dry_gain + repeated * wet_gain;
             });
 
         i += count;
@@ -0,0 +1,102 @@
+use crate::{core::AudioSource, AudioFormat, ReadResult, Sample};
+
+use tracing::instrument;
+
+/// A chain of effects whose stages are chosen at runtime.
+///
+/// Composing effects directly nests their types, e.g. `Echo<LowPass<HighPass<S>>>`,
+/// which can't depend on runtime configuration. `EffectChain` boxes each stage
+/// instead, so a chain can be built from a config file or user input.
+///
+/// Each stage is constructed by a closure that receives the chain so far as a
+/// boxed source, in the same way as [`Oversample`](crate::effects::Oversample).
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{EffectChain, Echo, HighPass, LowPass}, generators::SineWave, AudioSource};
+/// # use std::time::Duration;
+/// // Effect descriptors, e.g. parsed from a config file.
+/// enum Stage {
+///     LowPass(f32),
+///     HighPass(f32),
+///     Echo(f32, f32),
+/// }
+///
+/// let config = vec![Stage::HighPass(200.0), Stage::LowPass(4000.0)];
+///
+/// let chain = config.into_iter().fold(
+///     EffectChain::new(SineWave::new(1.0, 440.0)),
+///     |chain, stage| match stage {
+///         Stage::LowPass(cutoff) => chain.push(|source| LowPass::new(source, cutoff)),
+///         Stage::HighPass(cutoff) => chain.push(|source| HighPass::new(source, cutoff)),
+///         Stage::Echo(delay, decay) => {
+///             chain.push(|source| Echo::new(source, Duration::from_secs_f32(delay), decay))
+///         }
+///     },
+/// );
+/// assert_eq!(chain.len(), 2);
+///
+/// // The chain matches the same effects composed statically.
+/// let mut chain = chain;
+/// let mut expected = LowPass::new(HighPass::new(SineWave::new(1.0, 440.0), 200.0), 4000.0);
+///
+/// let mut a = vec![0.0; 1024];
+/// let mut b = vec![0.0; 1024];
+/// chain.read(&mut a);
+/// expected.read(&mut b);
+/// assert_eq!(a, b);
+/// ```
+pub struct EffectChain {
+    source: Box<dyn AudioSource + Send>,
+    stages: usize,
+}
+
+impl EffectChain {
+    /// Construct an empty chain, which passes `source` through unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for the first stage.
+    pub fn new<S: AudioSource + Send + 'static>(source: S) -> Self {
+        EffectChain {
+            source: Box::new(source),
+            stages: 0,
+        }
+    }
+
+    /// Add an effect to the end of the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `effect` -- Constructs the effect from the output of the chain so far.
+    pub fn push<E, F>(self, effect: F) -> Self
+    where
+        E: AudioSource + Send + 'static,
+        F: FnOnce(Box<dyn AudioSource + Send>) -> E,
+    {
+        EffectChain {
+            source: Box::new(effect(self.source)),
+            stages: self.stages + 1,
+        }
+    }
+
+    /// Returns the number of effects added to the chain.
+    pub fn len(&self) -> usize {
+        self.stages
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages == 0
+    }
+}
+
+impl AudioSource for EffectChain {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "EffectChain::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.source.read(buffer)
+    }
+}
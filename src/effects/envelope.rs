@@ -0,0 +1,76 @@
+use crate::flush_denormal;
+
+/// Tracks the level of a signal, rising at the attack rate and falling at the release rate.
+///
+/// The envelope moves toward each new level by a one-pole filter whose time
+/// constant is the attack time when the level is rising and the release time
+/// when it is falling.
+pub(crate) struct EnvelopeFollower {
+    attack: f32,
+    release: f32,
+    envelope: f32,
+    sample_rate: u32,
+    attack_rate: f32,
+    release_rate: f32,
+}
+
+impl EnvelopeFollower {
+    /// Construct an envelope follower with attack and release times in seconds.
+    pub fn new(attack: f32, release: f32) -> Self {
+        EnvelopeFollower {
+            attack,
+            release,
+            envelope: 0.0,
+            sample_rate: 0,
+            attack_rate: 0.0,
+            release_rate: 0.0,
+        }
+    }
+
+    pub fn set_attack(&mut self, attack: f32) {
+        self.attack = attack;
+        self.sample_rate = 0;
+    }
+
+    pub fn attack(&self) -> f32 {
+        self.attack
+    }
+
+    pub fn set_release(&mut self, release: f32) {
+        self.release = release;
+        self.sample_rate = 0;
+    }
+
+    pub fn release(&self) -> f32 {
+        self.release
+    }
+
+    pub fn envelope(&self) -> f32 {
+        self.envelope
+    }
+
+    pub fn reset(&mut self) {
+        self.envelope = 0.0;
+    }
+
+    /// Advance by one frame whose level is `level`, returning the new envelope.
+    pub fn follow(&mut self, level: f32, sample_rate: u32) -> f32 {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.attack_rate = rate(self.attack, sample_rate);
+            self.release_rate = rate(self.release, sample_rate);
+        }
+
+        let rate = if level > self.envelope {
+            self.attack_rate
+        } else {
+            self.release_rate
+        };
+        self.envelope = flush_denormal(self.envelope + rate * (level - self.envelope));
+        self.envelope
+    }
+}
+
+fn rate(time: f32, sample_rate: u32) -> f32 {
+    1.0 - (-1.0 / (time * sample_rate as f32)).exp()
+}
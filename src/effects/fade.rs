@@ -0,0 +1,147 @@
+use crate::{core::AudioSource, Latency, ReadResult, Rewind, Sample, Seek, Tail};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// An effect that fades a source in or out, e.g. for transitions between tracks.
+///
+/// The source plays at full volume until a fade is scheduled with
+/// [`fade_in`](Fade::fade_in) or [`fade_out`](Fade::fade_out). The fade starts
+/// at the next sample read, and the gain changes linearly, sample by sample,
+/// until it's over. Once a fade out is complete, the source keeps being read
+/// but the output is silent, until the next fade in.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Fade, generators::Ramp, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// // A constant signal of 1.0, so the output is the gain itself.
+/// let ones = Ramp::new(1.0, 1.0, Duration::from_secs(0), AudioFormat::MONO_CD);
+/// let mut fade = Fade::new(ones);
+///
+/// fade.fade_in(Duration::from_secs(1));
+/// let mut samples = vec![0.0; 44100];
+/// fade.read(&mut samples);
+/// assert_eq!(samples[0], 0.0);
+/// assert!((samples[22050] - 0.5).abs() < 1e-4);
+/// assert!(!fade.is_fading());
+///
+/// fade.fade_out(Duration::from_millis(500));
+/// fade.read(&mut samples);
+/// assert!((samples[11025] - 0.5).abs() < 1e-4);
+/// assert!(fade.is_faded_out());
+/// assert!(samples[22050..].iter().all(|&sample| sample == 0.0));
+/// ```
+pub struct Fade<S: AudioSource> {
+    source: S,
+    from: f32,
+    to: f32,
+    length: u64,
+    position: u64,
+}
+
+impl<S: AudioSource> Fade<S> {
+    /// Construct a fade, which plays `source` at full volume until a fade is scheduled.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    pub fn new(source: S) -> Self {
+        Fade {
+            source,
+            from: 1.0,
+            to: 1.0,
+            length: 0,
+            position: 0,
+        }
+    }
+
+    /// Fade in from silence to full volume over `duration`, starting with the next read.
+    pub fn fade_in(&mut self, duration: Duration) {
+        self.start(0.0, 1.0, duration);
+    }
+
+    /// Fade out to silence over `duration`, starting with the next read.
+    ///
+    /// If a fade in is still in progress, the fade out starts from the
+    /// current volume, so there's no jump.
+    pub fn fade_out(&mut self, duration: Duration) {
+        self.start(self.gain(), 0.0, duration);
+    }
+
+    /// Returns true while a fade is in progress.
+    pub fn is_fading(&self) -> bool {
+        self.position < self.length
+    }
+
+    /// Returns true once a fade out is complete, and the output is silent.
+    pub fn is_faded_out(&self) -> bool {
+        !self.is_fading() && self.to == 0.0
+    }
+
+    /// Returns the current linear gain.
+    pub fn gain(&self) -> f32 {
+        self.gain_at(self.position)
+    }
+
+    fn start(&mut self, from: f32, to: f32, duration: Duration) {
+        self.from = from;
+        self.to = to;
+        self.length = self.source.format().duration_to_frames(duration);
+        self.position = 0;
+    }
+
+    fn gain_at(&self, position: u64) -> f32 {
+        if position >= self.length {
+            self.to
+        } else {
+            let progress = position as f32 / self.length as f32;
+            self.from + (self.to - self.from) * progress
+        }
+    }
+}
+
+impl<S: AudioSource> AudioSource for Fade<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Fade::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.source.format().channels as usize;
+        let result = self.source.read(buffer);
+
+        for frame in buffer[..result.read].chunks_exact_mut(channels) {
+            let gain = self.gain_at(self.position);
+            frame.iter_mut().for_each(|sample| *sample *= gain);
+            self.position = std::cmp::min(self.position + 1, self.length);
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Fade<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Fade<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Fade<S> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Fade<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    core::AudioSource,
+    db_to_linear,
+    effects::smoothed::{ramp, Smoothed},
+    linear_to_db, Latency, Param, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// An effect that changes the volume of a source.
+///
+/// Every sample is multiplied by the gain. Changes to the gain ramp over a few
+/// milliseconds, so the volume can be changed while playing without clicks.
+/// The gain can also be changed from another thread through
+/// [`gain_param`](Gain::gain_param).
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Gain, generators::VecSource, AudioFormat, AudioSource};
+/// let source = VecSource::new(vec![0.5, -0.25, 1.0], AudioFormat::MONO_CD);
+/// let mut quieter = Gain::new(source, 0.5);
+///
+/// let mut samples = vec![0.0; 3];
+/// assert_eq!(quieter.read(&mut samples).read, 3);
+/// assert_eq!(samples, [0.25, -0.125, 0.5]);
+/// ```
+pub struct Gain<S: AudioSource> {
+    source: S,
+    gain: Param,
+    applied: Smoothed,
+}
+
+impl<S: AudioSource> Gain<S> {
+    /// Construct a gain effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `gain` -- The linear multiplier to apply, e.g. 0.5 for half the amplitude.
+    pub fn new(source: S, gain: f32) -> Self {
+        Gain {
+            source,
+            gain: Param::new(gain),
+            applied: Smoothed::new(gain),
+        }
+    }
+
+    /// Construct a gain effect from a gain in decibels.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `db` -- The gain in decibels, e.g. -6.0 for about half the amplitude.
+    pub fn with_db(source: S, db: f32) -> Self {
+        Gain::new(source, db_to_linear(db))
+    }
+
+    /// Set the linear multiplier to apply. The gain ramps to the new value
+    /// over a few milliseconds.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain.set(gain);
+    }
+
+    /// Returns the linear gain, or the gain being ramped toward.
+    pub fn gain(&self) -> f32 {
+        self.gain.get()
+    }
+
+    /// Set the gain in decibels.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::Gain, generators::SineWave};
+    /// let mut gain = Gain::new(SineWave::new(1.0, 440.0), 1.0);
+    /// gain.set_gain_db(-6.0);
+    /// assert!((gain.gain() - 0.501).abs() < 1e-3);
+    /// assert!((gain.gain_db() + 6.0).abs() < 1e-4);
+    /// ```
+    pub fn set_gain_db(&mut self, db: f32) {
+        self.set_gain(db_to_linear(db));
+    }
+
+    pub fn gain_db(&self) -> f32 {
+        linear_to_db(self.gain())
+    }
+
+    /// Returns a handle to the linear gain, which can be changed from any thread.
+    pub fn gain_param(&self) -> Param {
+        self.gain.clone()
+    }
+}
+
+impl<S: AudioSource> AudioSource for Gain<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Gain::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let channels = format.channels as usize;
+        let result = self.source.read(buffer);
+
+        let gain = self.gain.get();
+        if gain != self.applied.target() {
+            self.applied.set(gain);
+        }
+
+        let frames = result.read / channels;
+        let gains = self
+            .applied
+            .advance(frames as f32 / format.sample_rate as f32);
+        for (i, frame) in buffer[..result.read].chunks_exact_mut(channels).enumerate() {
+            let gain = ramp(gains, i + 1, frames);
+            frame.iter_mut().for_each(|sample| *sample *= gain);
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Gain<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Gain<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Gain<S> {
+    fn rewind(&mut self) -> bool {
+        self.applied.finish();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Gain<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.applied.finish();
+        self.source.seek_frame(frame)
+    }
+}
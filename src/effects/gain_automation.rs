@@ -0,0 +1,158 @@
+use crate::{core::AudioSource, Latency, ReadResult, Rewind, Sample, Seek, Tail};
+
+use tracing::instrument;
+
+/// An effect that changes the volume of a source along a timeline.
+///
+/// The gain is given as `(time, gain)` breakpoints, where `time` is in
+/// seconds from the start of the stream and `gain` is a linear multiplier.
+/// Between breakpoints, the gain is interpolated linearly; before the first
+/// breakpoint and after the last, the gain is held at their values.
+///
+/// This is the building block for fade curves and mix automation.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::GainAutomation, generators::Ramp, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// // A constant signal of 1.0, so the output is the gain itself.
+/// let ones = Ramp::new(1.0, 1.0, Duration::from_secs(0), AudioFormat::MONO_CD);
+/// let mut fade = GainAutomation::new(ones, vec![(0.0, 1.0), (2.0, 0.0)]);
+///
+/// let mut samples = vec![0.0; 3 * 44100];
+/// fade.read(&mut samples);
+///
+/// // Halfway through the fade, the gain is halfway between the breakpoints.
+/// assert!((samples[44100] - 0.5).abs() < 1e-4);
+/// // After the last breakpoint, its gain is held.
+/// assert_eq!(samples[2 * 44100 + 100], 0.0);
+/// ```
+pub struct GainAutomation<S: AudioSource> {
+    source: S,
+    breakpoints: Vec<(f32, f32)>,
+    position: u64,
+}
+
+impl<S: AudioSource> GainAutomation<S> {
+    /// Construct a gain automation.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `breakpoints` -- `(time, gain)` pairs, with `time` in seconds and `gain` as a linear multiplier.
+    ///
+    /// # Panics
+    ///
+    /// If `breakpoints` is empty or its times are not in increasing order.
+    pub fn new(source: S, breakpoints: Vec<(f32, f32)>) -> Self {
+        let mut automation = GainAutomation {
+            source,
+            breakpoints: Vec::new(),
+            position: 0,
+        };
+        automation.set_breakpoints(breakpoints);
+        automation
+    }
+
+    /// Replace the breakpoints. The timeline is unaffected, so the new
+    /// breakpoints apply from the current position.
+    ///
+    /// # Panics
+    ///
+    /// If `breakpoints` is empty or its times are not in increasing order.
+    pub fn set_breakpoints(&mut self, breakpoints: Vec<(f32, f32)>) {
+        assert!(
+            !breakpoints.is_empty(),
+            "GainAutomation requires at least one breakpoint."
+        );
+        assert!(
+            breakpoints.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+            "Breakpoints must be in order of time."
+        );
+        self.breakpoints = breakpoints;
+    }
+
+    pub fn breakpoints(&self) -> &[(f32, f32)] {
+        &self.breakpoints
+    }
+
+    /// Returns the gain at `time` seconds from the start of the stream.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::GainAutomation, generators::SineWave};
+    /// let sin = SineWave::new(1.0, 440.0);
+    /// let automation = GainAutomation::new(sin, vec![(1.0, 0.0), (2.0, 1.0), (4.0, 0.5)]);
+    ///
+    /// assert_eq!(automation.gain_at(0.0), 0.0);
+    /// assert_eq!(automation.gain_at(1.5), 0.5);
+    /// assert_eq!(automation.gain_at(3.0), 0.75);
+    /// assert_eq!(automation.gain_at(10.0), 0.5);
+    /// ```
+    pub fn gain_at(&self, time: f32) -> f32 {
+        let next = self.breakpoints.partition_point(|&(t, _)| t <= time);
+        if next == 0 {
+            return self.breakpoints[0].1;
+        }
+        if next == self.breakpoints.len() {
+            return self.breakpoints[next - 1].1;
+        }
+
+        let (t0, g0) = self.breakpoints[next - 1];
+        let (t1, g1) = self.breakpoints[next];
+        g0 + (g1 - g0) * (time - t0) / (t1 - t0)
+    }
+
+    /// Returns the current position on the timeline, in seconds.
+    pub fn time(&self) -> f32 {
+        (self.position as f64 / self.source.format().sample_rate as f64) as f32
+    }
+}
+
+impl<S: AudioSource> AudioSource for GainAutomation<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "GainAutomation::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let result = self.source.read(buffer);
+
+        let sample_rate = format.sample_rate as f64;
+        for frame in buffer[..result.read].chunks_exact_mut(format.channels as usize) {
+            let gain = self.gain_at((self.position as f64 / sample_rate) as f32);
+            frame.iter_mut().for_each(|s| *s *= gain);
+            self.position += 1;
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for GainAutomation<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for GainAutomation<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for GainAutomation<S> {
+    fn rewind(&mut self) -> bool {
+        self.position = 0;
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for GainAutomation<S> {
+    /// Seek the source, moving to the same position on the timeline.
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.position = self.source.seek_frame(frame);
+        self.position
+    }
+}
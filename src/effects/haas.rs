@@ -0,0 +1,176 @@
+use crate::{
+    core::AudioSource, effects::comb::read_or_ring, AudioFormat, Latency, ReadResult, Rewind,
+    Sample, Seek, Tail,
+};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// The channel of a stereo stream.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// An effect that widens a stereo image by delaying one channel slightly.
+///
+/// When the same sound reaches both ears a few milliseconds apart, it's heard
+/// as a single sound coming from the side that arrived first (the precedence
+/// or Haas effect). Delaying one channel of a mono-ish stereo source by 1-30 ms
+/// makes it sound wider; longer delays are heard as a distinct echo.
+///
+/// The source must be stereo; use [`ToStereo`](crate::effects::ToStereo) to
+/// widen a mono source.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Haas, Side, ToStereo}, generators::SineWave, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// let mono = SineWave::with_format(AudioFormat::MONO_CD, 1.0, 440.0);
+/// let mut haas = Haas::new(ToStereo::new(mono), Duration::from_millis(10), Side::Right);
+/// assert_eq!(haas.delay_frames(), 441);
+///
+/// // Read in blocks shorter than the delay, so it spans several reads.
+/// let mut samples = vec![0.0; 2 * 4410];
+/// for block in samples.chunks_mut(2 * 100) {
+///     haas.read(block);
+/// }
+///
+/// // The right channel is exactly 441 frames behind the left.
+/// let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+/// let right: Vec<f32> = samples.iter().skip(1).step_by(2).copied().collect();
+/// assert!(right[..441].iter().all(|&s| s == 0.0));
+/// assert_eq!(right[441..], left[..4410 - 441]);
+/// ```
+pub struct Haas<S: AudioSource> {
+    source: S,
+    side: Side,
+    delay: Duration,
+    buffer: Vec<Sample>,
+    position: usize,
+    finished: bool,
+}
+
+impl<S: AudioSource> Haas<S> {
+    /// Construct a Haas effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The stereo source of audio for this effect.
+    /// * `delay` -- How far to delay the channel, typically 1-30 ms.
+    /// * `side` -- The channel to delay. The image shifts toward the other side.
+    ///
+    /// # Panics
+    ///
+    /// If `source` is not stereo.
+    pub fn new(source: S, delay: Duration, side: Side) -> Self {
+        assert_eq!(
+            source.format().channels,
+            2,
+            "Haas requires a stereo source."
+        );
+        Haas {
+            source,
+            side,
+            delay,
+            buffer: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Set how far to delay the channel.
+    ///
+    /// Changing the delay discards the samples in the delay line.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+        self.clear();
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Set which channel to delay.
+    pub fn set_side(&mut self, side: Side) {
+        self.side = side;
+        self.clear();
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Returns the delay in frames, for the source's current format.
+    ///
+    /// The delay is rounded to the nearest whole frame.
+    pub fn delay_frames(&self) -> usize {
+        self.source.format().duration_to_frames(self.delay) as usize
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.position = 0;
+        self.finished = false;
+    }
+}
+
+impl<S: AudioSource> AudioSource for Haas<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Haas::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let delay = self.delay_frames();
+        self.buffer.resize(delay, 0.0);
+
+        let result = read_or_ring(&mut self.source, &mut self.finished, buffer);
+        if delay == 0 {
+            return result;
+        }
+        self.position %= delay;
+
+        let channel = match self.side {
+            Side::Left => 0,
+            Side::Right => 1,
+        };
+        for frame in buffer[..result.read].chunks_exact_mut(2) {
+            std::mem::swap(&mut frame[channel], &mut self.buffer[self.position]);
+            self.position = (self.position + 1) % delay;
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Haas<S> {
+    /// Delaying one channel is the effect itself; the other channel stays in
+    /// step with the source, so only the source's latency is reported.
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Haas<S> {
+    /// The delayed channel plays on for `delay` after the source finishes.
+    fn tail_frames(&self) -> u64 {
+        (self.delay_frames() as u64).saturating_add(self.source.tail_frames())
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Haas<S> {
+    fn rewind(&mut self) -> bool {
+        self.clear();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Haas<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.clear();
+        self.source.seek_frame(frame)
+    }
+}
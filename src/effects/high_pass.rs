@@ -1,4 +1,8 @@
-use crate::{core::AudioSource, ReadResult, Sample};
+use crate::{
+    core::{normalized_frequency, AudioSource},
+    effects::smoothed::{ramp, Smoothed},
+    flush_denormal, midi_to_frequency, Latency, Param, ReadResult, Rewind, Sample, Seek, Tail,
+};
 
 use tracing::instrument;
 
@@ -7,6 +11,11 @@ use tracing::instrument;
 /// `HighPass` reduces the volume of frequencies below the given cutoff.
 /// This can create the impression of sound played on old speakers or a radio.
 ///
+/// Changes to the cutoff ramp over a few milliseconds rather than taking effect
+/// immediately, so the cutoff can be swept in real time without clicks. The
+/// cutoff can also be changed from another thread through
+/// [`cutoff_param`](HighPass::cutoff_param).
+///
 /// # Examples
 /// ```
 /// # use timbre::{generators::SineWave, effects::HighPass, IntoShared};
@@ -14,11 +23,38 @@ use tracing::instrument;
 /// let sin = SineWave::new(1.0, 440.0);
 /// let high_pass = HighPass::new(sin, 4000.0);
 /// ```
+///
+/// Any number of channels is supported, and each is filtered independently:
+/// ```
+/// # use timbre::{generators::{SineWave, VecSource}, effects::HighPass, AudioFormat, AudioSource};
+/// // Six channels, each carrying a different tone.
+/// let tone = |i| SineWave::with_format(AudioFormat::MONO_CD, 1.0, 300.0 * (i + 1) as f32);
+/// let mut channels = vec![vec![0.0; 1024]; 6];
+/// for (i, channel) in channels.iter_mut().enumerate() {
+///     tone(i).read(channel);
+/// }
+/// let interleaved = (0..6 * 1024).map(|n| channels[n % 6][n / 6]).collect();
+/// let format = AudioFormat { channels: 6, sample_rate: 44100 };
+/// let mut surround = HighPass::new(VecSource::new(interleaved, format), 1000.0);
+/// let mut samples = vec![0.0; 6 * 1024];
+/// surround.read(&mut samples);
+///
+/// for i in 0..6 {
+///     let mut mono = HighPass::new(tone(i), 1000.0);
+///     let mut expected = vec![0.0; 1024];
+///     mono.read(&mut expected);
+///
+///     let channel: Vec<f32> = samples.iter().skip(i).step_by(6).copied().collect();
+///     assert_eq!(channel, expected);
+/// }
+/// ```
 pub struct HighPass<S: AudioSource> {
-    buffer: Vec<f32>,
-    rc: f32,
+    cutoff: Param,
+    applied: f32,
+    rc: Smoothed,
     source: S,
-    prev: [f32; 2],
+    inputs: Vec<f32>,
+    outputs: Vec<f32>,
 }
 
 impl<S: AudioSource> HighPass<S> {
@@ -29,15 +65,122 @@ impl<S: AudioSource> HighPass<S> {
     /// * `source` -- The source of audio for this effect.
     /// * `cutoff` -- The frequency below which volume will be reduced.
     pub fn new(source: S, cutoff: f32) -> Self {
-        let buffer = Vec::new();
-        let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff);
+        let channels = source.format().channels as usize;
+        let rc = Smoothed::new(time_constant(cutoff));
         HighPass {
-            buffer,
+            cutoff: Param::new(cutoff),
+            applied: cutoff,
             rc,
             source,
-            prev: [0.0, 0.0],
+            inputs: vec![0.0; channels],
+            outputs: vec![0.0; channels],
         }
     }
+
+    /// Set the frequency below which volume will be reduced.
+    ///
+    /// The filter ramps to the new cutoff over a few milliseconds.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::HighPass, AudioSource};
+    /// let sin = SineWave::new(1.0, 440.0);
+    /// let mut high_pass = HighPass::new(sin, 10.0);
+    ///
+    /// let mut samples = vec![0.0; 8192];
+    /// high_pass.read(&mut samples);
+    /// let mut previous = samples[samples.len() - 2];
+    ///
+    /// // Jump from nearly unfiltered to heavily filtered.
+    /// high_pass.set_cutoff(10000.0);
+    /// high_pass.read(&mut samples);
+    ///
+    /// // A 440 Hz sine never changes by more than ~0.063 between samples,
+    /// // so any larger step would be a click.
+    /// for frame in samples.chunks_exact(2) {
+    ///     assert!((frame[0] - previous).abs() < 0.1);
+    ///     previous = frame[0];
+    /// }
+    /// ```
+    pub fn set_cutoff(&mut self, cutoff: f32) {
+        self.cutoff.set(cutoff);
+    }
+
+    /// Set the cutoff from a value between 0.0 and 1.0, e.g. from a slider.
+    ///
+    /// The value is mapped logarithmically, from 20 Hz at 0.0 to 20 kHz at
+    /// 1.0, so that equal steps are equal musical intervals. Values outside
+    /// that range are clamped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::HighPass};
+    /// let mut filter = HighPass::new(SineWave::new(1.0, 440.0), 1000.0);
+    ///
+    /// // Halfway is the geometric mean of 20 Hz and 20 kHz.
+    /// filter.set_cutoff_normalized(0.5);
+    /// assert!((filter.cutoff() - (20.0f32 * 20000.0).sqrt()).abs() < 0.01);
+    ///
+    /// filter.set_cutoff_normalized(0.0);
+    /// assert!((filter.cutoff() - 20.0).abs() < 1e-3);
+    /// filter.set_cutoff_normalized(1.0);
+    /// assert!((filter.cutoff() - 20000.0).abs() < 0.1);
+    /// ```
+    pub fn set_cutoff_normalized(&mut self, normalized: f32) {
+        self.set_cutoff(normalized_frequency(normalized));
+    }
+
+    /// Set the cutoff to the frequency of a MIDI note, with A4 (note 69) at 440 Hz.
+    ///
+    /// This lets the cutoff track the notes being played, e.g. in a synthesizer.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::HighPass};
+    /// let mut filter = HighPass::new(SineWave::new(1.0, 440.0), 1000.0);
+    /// filter.set_cutoff_note(69.0);
+    /// assert_eq!(filter.cutoff(), 440.0);
+    /// ```
+    pub fn set_cutoff_note(&mut self, note: f32) {
+        self.set_cutoff(midi_to_frequency(note));
+    }
+
+    /// Returns the cutoff frequency, or the frequency being ramped toward.
+    pub fn cutoff(&self) -> f32 {
+        self.cutoff.get()
+    }
+
+    /// Returns a handle to the cutoff frequency, which can be changed from any thread.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::HighPass};
+    /// let sin = SineWave::new(1.0, 440.0);
+    /// let filter = HighPass::new(sin, 1000.0);
+    ///
+    /// let cutoff = filter.cutoff_param();
+    /// std::thread::spawn(move || cutoff.set(500.0)).join().unwrap();
+    /// assert_eq!(filter.cutoff(), 500.0);
+    /// ```
+    pub fn cutoff_param(&self) -> Param {
+        self.cutoff.clone()
+    }
+
+    /// Start ramping toward the cutoff if it has changed since the last read.
+    fn update_cutoff(&mut self) {
+        let cutoff = self.cutoff.get();
+        if cutoff != self.applied {
+            self.applied = cutoff;
+            self.rc.set(time_constant(cutoff));
+        }
+    }
+
+    fn reset(&mut self) {
+        self.inputs.iter_mut().for_each(|s| *s = 0.0);
+        self.outputs.iter_mut().for_each(|s| *s = 0.0);
+        self.update_cutoff();
+        self.rc.finish();
+    }
 }
 
 impl<S: AudioSource> AudioSource for HighPass<S> {
@@ -47,84 +190,85 @@ impl<S: AudioSource> AudioSource for HighPass<S> {
 
     #[instrument(name = "HighPass::read", skip(self, buffer))]
     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        // Adapters sometimes ask for nothing; don't pass that on to the source.
+        if buffer.is_empty() {
+            return ReadResult::good(0);
+        }
+
         let format = self.source.format();
         let result = self.source.read(buffer);
         let written = result.read;
         if written == 0 {
             return result;
         }
-        self.buffer.resize(buffer.len(), 0.0);
-
-        match format.channels {
-            1 => {
-                let dt = 1.0 / format.sample_rate as f32;
-                self.prev = filter_mono(
-                    &mut buffer[..written],
-                    &mut self.buffer[..written],
-                    dt,
-                    self.rc,
-                    self.prev,
-                );
-            }
-            2 => {
-                let dt = 1.0 / format.sample_rate as f32;
-                self.prev = filter_stereo(
-                    &mut buffer[..written],
-                    &mut self.buffer[..written],
-                    dt,
-                    self.rc,
-                    self.prev,
-                );
-            }
-            _ => panic!("Unsupported channel count."),
-        }
+        self.inputs.resize(format.channels as usize, 0.0);
+        self.outputs.resize(format.channels as usize, 0.0);
+
+        let frames = written / format.channels as usize;
+        self.update_cutoff();
+        let rc = self.rc.advance(frames as f32 / format.sample_rate as f32);
+        let dt = 1.0 / format.sample_rate as f32;
+        filter(
+            &mut buffer[..written],
+            &mut self.inputs,
+            &mut self.outputs,
+            dt,
+            rc,
+        );
 
         result
     }
 }
 
-fn filter_mono(
-    samples: &mut [f32],
-    buffer: &mut [f32],
-    dt: f32,
-    rc: f32,
-    prev: [f32; 2],
-) -> [f32; 2] {
-    assert!(!samples.is_empty() && !buffer.is_empty());
-    assert!(buffer.len() >= samples.len());
-
-    let a = rc / (rc + dt);
-    let res = [samples[samples.len() - 1], 0.0];
-
-    buffer[0] = a * (buffer[buffer.len() - 1] + samples[0] - prev[0]);
-    for i in 1..buffer.len() {
-        buffer[i] = a * (buffer[i - 1] + samples[i] - samples[i - 1]);
+impl<S: AudioSource + Latency> Latency for HighPass<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
     }
-    samples.copy_from_slice(&buffer[..samples.len()]);
+}
+
+impl<S: AudioSource + Tail> Tail for HighPass<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for HighPass<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset();
+        self.source.rewind()
+    }
+}
 
-    res
+impl<S: AudioSource + Seek> Seek for HighPass<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset();
+        self.source.seek_frame(frame)
+    }
 }
 
-fn filter_stereo(
-    samples: &mut [f32],
-    buffer: &mut [f32],
-    dt: f32,
-    rc: f32,
-    prev: [f32; 2],
-) -> [f32; 2] {
-    assert!(!samples.is_empty() && !buffer.is_empty());
-    assert!(samples.len() % 2 == 0 && buffer.len() % 2 == 0);
-    assert!(buffer.len() >= samples.len());
-
-    let a = rc / (rc + dt);
-    let res = [samples[samples.len() - 2], samples[samples.len() - 1]];
-
-    buffer[0] = a * (buffer[buffer.len() - 2] + samples[0] - prev[0]);
-    buffer[1] = a * (buffer[buffer.len() - 1] + samples[1] - prev[1]);
-    for i in 2..buffer.len() {
-        buffer[i] = a * (buffer[i - 2] + samples[i] - samples[i - 2]);
+/// Filter interleaved `samples`, where `inputs` and `outputs` hold the
+/// previous input and output for each channel.
+fn filter(samples: &mut [f32], inputs: &mut [f32], outputs: &mut [f32], dt: f32, rc: (f32, f32)) {
+    let channels = inputs.len();
+    assert!(samples.len().is_multiple_of(channels));
+
+    let frames = samples.len() / channels;
+    for (i, frame) in samples.chunks_exact_mut(channels).enumerate() {
+        let rc = ramp(rc, i + 1, frames);
+        let a = rc / (rc + dt);
+
+        for ((sample, input), output) in frame
+            .iter_mut()
+            .zip(inputs.iter_mut())
+            .zip(outputs.iter_mut())
+        {
+            *output = flush_denormal(a * (*output + *sample - *input));
+            *input = *sample;
+            *sample = *output;
+        }
     }
-    samples.copy_from_slice(&buffer[..samples.len()]);
+}
 
-    res
+fn time_constant(cutoff: f32) -> f32 {
+    1.0 / (2.0 * std::f32::consts::PI * cutoff)
 }
@@ -0,0 +1,91 @@
+use crate::{core::AudioSource, AudioFormat, Latency, ReadResult, Rewind, Sample, Seek, Tail};
+
+use tracing::instrument;
+
+/// An adapter that calls a closure with every block of audio read through it.
+///
+/// The closure receives the samples that were read and the
+/// [`ReadResult`](crate::ReadResult) of the read. Audio passes through
+/// unchanged, so `Inspect` can be inserted anywhere in a chain for custom
+/// logging, recording, or assertions in tests.
+///
+/// # Realtime Safety
+///
+/// When the chain is played through a driver, the closure runs on the audio
+/// thread, inside the read. It must not block: avoid locking mutexes that other
+/// threads hold for long, doing I/O, or allocating in a loop. To get data to
+/// another thread, push it into a bounded channel with
+/// [`try_send`](std::sync::mpsc::SyncSender::try_send) and drop it if full.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Inspect, generators::SineWave, AudioSource, StreamState};
+/// let mut blocks = Vec::new();
+/// let mut samples = vec![0.0; 256];
+/// {
+///     let sin = SineWave::new(1.0, 440.0);
+///     let mut inspect = Inspect::new(sin, |buffer, result| {
+///         assert_eq!(result.state, StreamState::Good);
+///         blocks.push(buffer.to_vec());
+///     });
+///     inspect.read(&mut samples);
+///     inspect.read(&mut samples[..128]);
+/// }
+///
+/// assert_eq!(blocks.len(), 2);
+/// assert_eq!(blocks[0].len(), 256);
+/// assert_eq!(blocks[1], &samples[..128]);
+/// ```
+pub struct Inspect<S: AudioSource, F: FnMut(&[Sample], &ReadResult)> {
+    source: S,
+    inspector: F,
+}
+
+impl<S: AudioSource, F: FnMut(&[Sample], &ReadResult)> Inspect<S, F> {
+    /// Construct an `Inspect` adapter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this adapter.
+    /// * `inspector` -- Called after each read with the samples read and the result.
+    pub fn new(source: S, inspector: F) -> Self {
+        Inspect { source, inspector }
+    }
+}
+
+impl<S: AudioSource, F: FnMut(&[Sample], &ReadResult)> AudioSource for Inspect<S, F> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Inspect::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.source.read(buffer);
+        (self.inspector)(&buffer[..result.read], &result);
+        result
+    }
+}
+
+impl<S: AudioSource + Latency, F: FnMut(&[Sample], &ReadResult)> Latency for Inspect<S, F> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail, F: FnMut(&[Sample], &ReadResult)> Tail for Inspect<S, F> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind, F: FnMut(&[Sample], &ReadResult)> Rewind for Inspect<S, F> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek, F: FnMut(&[Sample], &ReadResult)> Seek for Inspect<S, F> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{core::AudioSource, ReadResult, Sample, StreamState};
+
+/// The interpolation method used when changing the rate of a source.
+///
+/// Higher qualities suppress more of the images and aliasing introduced by
+/// rate conversion, at the cost of more CPU time per sample.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Quality {
+    /// Repeat the nearest input sample. Cheapest, and very noisy.
+    Nearest,
+    /// Interpolate linearly between the two nearest samples.
+    Linear,
+    /// Interpolate with a cubic Hermite (Catmull-Rom) spline through four samples.
+    Cubic,
+    /// Convolve with a windowed-sinc kernel spanning 32 samples.
+    Sinc,
+}
+
+impl Default for Quality {
+    /// Returns [`Quality::Linear`](crate::effects::Quality::Linear).
+    fn default() -> Self {
+        Quality::Linear
+    }
+}
+
+const SINC_ZERO_CROSSINGS: usize = 16;
+const SINC_PHASES: usize = 512;
+const READ_FRAMES: usize = 512;
+
+/// Reads from a source at a fractional rate, interpolating between its frames.
+///
+/// Shared by [`Resampler`](crate::effects::Resampler) and [`Speed`](crate::effects::Speed).
+pub(crate) struct Interpolator {
+    quality: Quality,
+    channels: usize,
+    input: Vec<f32>,
+    position: f64,
+    end: Option<usize>,
+    table: Vec<f32>,
+    table_cutoff: f32,
+}
+
+impl Interpolator {
+    pub fn new(quality: Quality, channels: usize) -> Self {
+        let mut interpolator = Interpolator {
+            quality,
+            channels,
+            input: Vec::new(),
+            position: 0.0,
+            end: None,
+            table: Vec::new(),
+            table_cutoff: 0.0,
+        };
+        interpolator.reset();
+        interpolator
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    pub fn set_quality(&mut self, quality: Quality) {
+        // Keep enough history before the current position for the new kernel.
+        let (old, _) = taps(self.quality);
+        let (new, _) = taps(quality);
+        if new > old {
+            let frames = new - old;
+            self.input.splice(0..0, vec![0.0; frames * self.channels]);
+            self.position += frames as f64;
+            self.end = self.end.map(|end| end + frames);
+        }
+        self.quality = quality;
+    }
+
+    /// Forget all buffered input, e.g. after the source is rewound.
+    pub fn reset(&mut self) {
+        let (before, _) = taps(self.quality);
+        self.input.clear();
+        self.input.resize(before * self.channels, 0.0);
+        self.position = before as f64;
+        self.end = None;
+    }
+
+    /// Fill `buffer`, advancing through the source by `step` frames per output frame.
+    pub fn read<S: AudioSource + ?Sized>(
+        &mut self,
+        source: &mut S,
+        buffer: &mut [Sample],
+        step: f64,
+    ) -> ReadResult {
+        if self.quality == Quality::Sinc {
+            self.update_table(step);
+        }
+
+        let (before, after) = taps(self.quality);
+        let mut state = StreamState::Good;
+        let mut written = 0;
+        for frame in buffer.chunks_exact_mut(self.channels) {
+            let index = self.position.floor() as usize;
+            if matches!(self.end, Some(end) if index >= end) {
+                state = StreamState::Finished;
+                break;
+            }
+            if !self.fill(source, index + after + 1) {
+                state = StreamState::Underrun;
+                break;
+            }
+
+            let t = (self.position - index as f64) as f32;
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                *sample = self.interpolate(index, channel, t);
+            }
+            self.position += step;
+            written += self.channels;
+        }
+
+        // Discard input that is no longer needed for history.
+        let consumed = (self.position.floor() as usize).saturating_sub(before);
+        let consumed = std::cmp::min(consumed, self.input.len() / self.channels);
+        self.input.drain(..consumed * self.channels);
+        self.position -= consumed as f64;
+        self.end = self.end.map(|end| end.saturating_sub(consumed));
+
+        ReadResult {
+            state,
+            read: written,
+        }
+    }
+
+    /// Read from the source until at least `frames` frames are buffered.
+    ///
+    /// After the source finishes, pads with silence. Returns false on underrun.
+    fn fill<S: AudioSource + ?Sized>(&mut self, source: &mut S, frames: usize) -> bool {
+        while self.input.len() < frames * self.channels {
+            if self.end.is_some() {
+                self.input.resize(frames * self.channels, 0.0);
+                break;
+            }
+
+            let start = self.input.len();
+            let wanted = std::cmp::max(frames * self.channels - start, READ_FRAMES * self.channels);
+            self.input.resize(start + wanted, 0.0);
+
+            let result = source.read(&mut self.input[start..]);
+            self.input.truncate(start + result.read);
+            match result.state {
+                StreamState::Good => {}
+                StreamState::Underrun => return self.input.len() >= frames * self.channels,
+                StreamState::Finished => self.end = Some(self.input.len() / self.channels),
+            }
+        }
+        true
+    }
+
+    fn interpolate(&self, index: usize, channel: usize, t: f32) -> f32 {
+        let x = |frame: usize| self.input[frame * self.channels + channel];
+        match self.quality {
+            Quality::Nearest => {
+                if t < 0.5 {
+                    x(index)
+                } else {
+                    x(index + 1)
+                }
+            }
+            Quality::Linear => x(index) + t * (x(index + 1) - x(index)),
+            Quality::Cubic => {
+                let (y0, y1, y2, y3) = (x(index - 1), x(index), x(index + 1), x(index + 2));
+                let c1 = 0.5 * (y2 - y0);
+                let c2 = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+                let c3 = 0.5 * (y3 - y0) + 1.5 * (y1 - y2);
+                ((c3 * t + c2) * t + c1) * t + y1
+            }
+            Quality::Sinc => {
+                let width = 2 * SINC_ZERO_CROSSINGS;
+                let phase = t * SINC_PHASES as f32;
+                let row = std::cmp::min(phase as usize, SINC_PHASES - 1);
+                let blend = phase - row as f32;
+                let a = &self.table[row * width..(row + 1) * width];
+                let b = &self.table[(row + 1) * width..(row + 2) * width];
+
+                let first = index + 1 - SINC_ZERO_CROSSINGS;
+                (0..width)
+                    .map(|k| (a[k] + blend * (b[k] - a[k])) * x(first + k))
+                    .sum()
+            }
+        }
+    }
+
+    /// Build the polyphase sinc table, lowering the cutoff when decimating.
+    fn update_table(&mut self, step: f64) {
+        let cutoff = (1.0 / step).min(1.0) as f32;
+        if cutoff == self.table_cutoff && !self.table.is_empty() {
+            return;
+        }
+
+        let n = SINC_ZERO_CROSSINGS as f32;
+        let width = 2 * SINC_ZERO_CROSSINGS;
+        self.table.clear();
+        for row in 0..=SINC_PHASES {
+            let t = row as f32 / SINC_PHASES as f32;
+            let start = self.table.len();
+            for k in 0..width {
+                // Distance from the interpolated position to this tap.
+                let d = k as f32 - (n - 1.0) - t;
+                let x = std::f32::consts::PI * cutoff * d;
+                let sinc = if x == 0.0 { 1.0 } else { x.sin() / x };
+                let w = std::f32::consts::PI * d / n;
+                let blackman = 0.42 + 0.5 * w.cos() + 0.08 * (2.0 * w).cos();
+                self.table.push(cutoff * sinc * blackman);
+            }
+
+            let sum: f32 = self.table[start..].iter().sum();
+            self.table[start..].iter_mut().for_each(|tap| *tap /= sum);
+        }
+        self.table_cutoff = cutoff;
+    }
+}
+
+/// The number of frames needed before and after the current position.
+fn taps(quality: Quality) -> (usize, usize) {
+    match quality {
+        Quality::Nearest | Quality::Linear => (0, 1),
+        Quality::Cubic => (1, 2),
+        Quality::Sinc => (SINC_ZERO_CROSSINGS - 1, SINC_ZERO_CROSSINGS),
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{core::AudioSource, Latency, ReadResult, Rewind, Sample, Seek, Tail};
+
+use tracing::instrument;
+
+/// Which channels of a source an effect applies to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChannelSelection {
+    /// Every channel.
+    All,
+    /// Only the channels with these indices, e.g. `[1]` for the right channel of a stereo source.
+    Only(Vec<usize>),
+}
+
+impl ChannelSelection {
+    /// Returns true if the channel with the given index is selected.
+    pub fn contains(&self, channel: usize) -> bool {
+        match self {
+            ChannelSelection::All => true,
+            ChannelSelection::Only(channels) => channels.contains(&channel),
+        }
+    }
+}
+
+/// An effect that inverts the polarity of some or all channels.
+///
+/// Each selected channel is negated, which fixes material that was recorded
+/// or wired out of phase. See [`polarity_check`](crate::polarity_check) to
+/// find out whether a pair of sources needs it.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{ChannelSelection, Invert}, generators::SineWave, AudioSource};
+/// let mut inverted = Invert::new(SineWave::new(0.5, 440.0), ChannelSelection::All);
+/// let mut original = SineWave::new(0.5, 440.0);
+///
+/// let mut samples = vec![0.0; 512];
+/// let mut expected = vec![0.0; 512];
+/// inverted.read(&mut samples);
+/// original.read(&mut expected);
+///
+/// // Both channels are exactly negated.
+/// assert!(samples.iter().zip(&expected).all(|(&s, &e)| s == -e));
+/// ```
+pub struct Invert<S: AudioSource> {
+    source: S,
+    channels: ChannelSelection,
+}
+
+impl<S: AudioSource> Invert<S> {
+    /// Construct a polarity inverter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `channels` -- The channels to invert. Other channels pass through unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If `channels` selects a channel the source doesn't have.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::{ChannelSelection, Invert}, generators::SineWave, AudioSource};
+    /// // Flip just the right channel.
+    /// let mut invert = Invert::new(SineWave::new(0.5, 440.0), ChannelSelection::Only(vec![1]));
+    ///
+    /// let mut samples = vec![0.0; 512];
+    /// invert.read(&mut samples);
+    /// assert!(samples.chunks_exact(2).all(|frame| frame[0] == -frame[1]));
+    /// ```
+    pub fn new(source: S, channels: ChannelSelection) -> Self {
+        check_channels(&channels, source.format().channels);
+        Invert { source, channels }
+    }
+
+    /// Set the channels to invert.
+    ///
+    /// # Panics
+    ///
+    /// If `channels` selects a channel the source doesn't have.
+    pub fn set_channels(&mut self, channels: ChannelSelection) {
+        check_channels(&channels, self.source.format().channels);
+        self.channels = channels;
+    }
+
+    pub fn channels(&self) -> &ChannelSelection {
+        &self.channels
+    }
+}
+
+fn check_channels(selection: &ChannelSelection, channels: u8) {
+    if let ChannelSelection::Only(selected) = selection {
+        assert!(
+            selected.iter().all(|&channel| channel < channels as usize),
+            "The source only has {} channels.",
+            channels
+        );
+    }
+}
+
+impl<S: AudioSource> AudioSource for Invert<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Invert::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.source.read(buffer);
+
+        let channels = self.source.format().channels as usize;
+        for frame in buffer[..result.read].chunks_exact_mut(channels) {
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                if self.channels.contains(channel) {
+                    *sample = -*sample;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Invert<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Invert<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Invert<S> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Invert<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
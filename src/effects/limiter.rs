@@ -0,0 +1,255 @@
+use crate::{
+    core::AudioSource, AudioFormat, Latency, ReadResult, Rewind, Sample, Seek, StreamState, Tail,
+};
+
+use std::{collections::VecDeque, time::Duration};
+
+use tracing::instrument;
+
+/// How far ahead the limiter looks for peaks.
+const LOOKAHEAD: Duration = Duration::from_millis(5);
+/// How long the gain takes to recover by about 63% once a peak has passed.
+const RELEASE: Duration = Duration::from_millis(100);
+/// A ceiling just below full scale, which leaves room for rounding when the
+/// output is converted to integers.
+const DEFAULT_CEILING: f32 = 0.99;
+
+/// An effect that keeps a source from ever exceeding a ceiling, e.g. to stop
+/// many mixed sources from clipping.
+///
+/// The limiter delays the audio by a 5ms lookahead, so it sees each peak
+/// coming and ramps the gain down smoothly in time to meet it, rather than
+/// distorting it. Once the peak has passed, the gain recovers gradually.
+/// The same gain is applied to every channel, so the stereo image doesn't
+/// shift. Quiet audio passes through unchanged, just delayed.
+///
+/// Because of the lookahead, the output lags the source by
+/// [`latency_frames`](crate::Latency::latency_frames), and when the source
+/// finishes, the last few milliseconds are flushed out before the limiter
+/// finishes too.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Limiter, generators::SineWave, AudioSource, Latency};
+/// let mut limiter = Limiter::with_default_ceiling(SineWave::new(2.0, 440.0));
+/// let mut samples = vec![0.0; 44100];
+/// limiter.read(&mut samples);
+/// assert!(samples.iter().all(|sample| sample.abs() <= 0.99));
+///
+/// // Quiet audio is just delayed.
+/// let mut quiet = Limiter::new(SineWave::new(0.5, 440.0), 0.99);
+/// let mut expected = vec![0.0; 4096];
+/// SineWave::new(0.5, 440.0).read(&mut expected);
+/// quiet.read(&mut samples[..4096]);
+/// let delay = 2 * quiet.latency_frames() as usize;
+/// assert_eq!(samples[delay..4096], expected[..4096 - delay]);
+/// ```
+pub struct Limiter<S: AudioSource> {
+    source: S,
+    ceiling: f32,
+    lookahead: usize,
+    release: f32,
+    /// The input waiting to be output, one lookahead of frames long.
+    delay: Vec<Sample>,
+    position: usize,
+    /// The index of the next frame, and the (index, gain) pairs that may yet
+    /// be the smallest gain required in the lookahead window, increasing.
+    frame: u64,
+    minimum: VecDeque<(u64, f32)>,
+    /// The held gain, and a running average of it over the lookahead.
+    held: f32,
+    history: Vec<f32>,
+    sum: f64,
+    /// The frames of delayed input left to flush once the source finishes.
+    flush: Option<usize>,
+}
+
+impl<S: AudioSource> Limiter<S> {
+    /// Construct a limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `ceiling` -- The largest absolute value of any output sample.
+    ///
+    /// # Panics
+    ///
+    /// If `ceiling` isn't positive.
+    pub fn new(source: S, ceiling: f32) -> Self {
+        assert!(ceiling > 0.0, "Ceiling must be positive.");
+        let format = source.format();
+        let lookahead = std::cmp::max(1, format.duration_to_frames(LOOKAHEAD) as usize);
+        let release = (-1.0 / format.duration_to_frames(RELEASE).max(1) as f32).exp();
+        let mut limiter = Limiter {
+            source,
+            ceiling,
+            lookahead,
+            release,
+            delay: Vec::new(),
+            position: 0,
+            frame: 0,
+            minimum: VecDeque::new(),
+            held: 1.0,
+            history: Vec::new(),
+            sum: 0.0,
+            flush: None,
+        };
+        limiter.reset(format);
+        limiter
+    }
+
+    /// Construct a limiter with a ceiling of 0.99, just below full scale, which
+    /// leaves room for rounding when the output is converted to integers.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    pub fn with_default_ceiling(source: S) -> Self {
+        Limiter::new(source, DEFAULT_CEILING)
+    }
+
+    /// Set the largest absolute value any output sample will have.
+    ///
+    /// # Panics
+    ///
+    /// If `ceiling` isn't positive.
+    pub fn set_ceiling(&mut self, ceiling: f32) {
+        assert!(ceiling > 0.0, "Ceiling must be positive.");
+        self.ceiling = ceiling;
+    }
+
+    pub fn ceiling(&self) -> f32 {
+        self.ceiling
+    }
+
+    fn reset(&mut self, format: AudioFormat) {
+        self.delay = vec![0.0; format.frames_to_samples(self.lookahead)];
+        self.position = 0;
+        self.frame = 0;
+        self.minimum.clear();
+        self.held = 1.0;
+        self.history = vec![1.0; self.lookahead];
+        self.sum = self.lookahead as f64;
+        self.flush = None;
+    }
+
+    /// Returns the gain to apply to the frame leaving the delay line, given
+    /// the frame entering it.
+    fn gain(&mut self, frame: &[Sample]) -> f32 {
+        let peak = frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let required = if peak > self.ceiling {
+            self.ceiling / peak
+        } else {
+            1.0
+        };
+
+        // The smallest gain required by any frame in the lookahead, which
+        // includes the frame leaving the delay line.
+        while matches!(self.minimum.back(), Some(&(_, gain)) if gain >= required) {
+            self.minimum.pop_back();
+        }
+        self.minimum.push_back((self.frame, required));
+        while self.minimum[0].0 + (self.lookahead as u64) < self.frame {
+            self.minimum.pop_front();
+        }
+        let minimum = self.minimum[0].1;
+
+        // Drop to the minimum at once, but recover slowly. Either way the
+        // held gain never exceeds the minimum.
+        self.held = if minimum < self.held {
+            minimum
+        } else {
+            minimum + (self.held - minimum) * self.release
+        };
+
+        // Averaging the held gain over the lookahead turns each drop into a
+        // ramp. Every gain averaged was held while the leaving frame was in
+        // the window, so the average is still low enough for that frame.
+        let index = self.frame as usize % self.lookahead;
+        self.sum += (self.held - self.history[index]) as f64;
+        self.history[index] = self.held;
+        self.frame += 1;
+        (self.sum / self.lookahead as f64) as f32
+    }
+}
+
+impl<S: AudioSource> AudioSource for Limiter<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Limiter::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let channels = format.channels as usize;
+        if self.delay.len() != format.frames_to_samples(self.lookahead) {
+            self.reset(format);
+        }
+
+        let requested = buffer.len() - buffer.len() % channels;
+        let (mut written, mut state) = match self.flush {
+            Some(_) => (0, StreamState::Finished),
+            None => {
+                let result = self.source.read(&mut buffer[..requested]);
+                (result.read, result.state)
+            }
+        };
+
+        // Once the source finishes, push silence through to flush out the
+        // input still in the delay line.
+        if state == StreamState::Finished {
+            let flush = self.flush.get_or_insert(self.lookahead);
+            let frames = std::cmp::min(*flush, (requested - written) / channels);
+            buffer[written..written + frames * channels]
+                .iter_mut()
+                .for_each(|s| *s = 0.0);
+            written += frames * channels;
+            *flush -= frames;
+            if *flush > 0 {
+                state = StreamState::Good;
+            }
+        }
+
+        for frame in buffer[..written].chunks_exact_mut(channels) {
+            let gain = self.gain(frame);
+            let delayed = &mut self.delay[self.position..self.position + channels];
+            for (sample, delayed) in frame.iter_mut().zip(delayed.iter_mut()) {
+                let output = (*delayed * gain).clamp(-self.ceiling, self.ceiling);
+                *delayed = *sample;
+                *sample = output;
+            }
+            self.position = (self.position + channels) % self.delay.len();
+        }
+
+        ReadResult {
+            state,
+            read: written,
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Limiter<S> {
+    fn latency_frames(&self) -> u64 {
+        self.lookahead as u64 + self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Limiter<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Limiter<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset(self.source.format());
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Limiter<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset(self.source.format());
+        self.source.seek_frame(frame)
+    }
+}
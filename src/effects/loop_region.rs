@@ -0,0 +1,156 @@
+use crate::{core::AudioSource, AudioFormat, Latency, ReadResult, Rewind, Sample, Seek, Tail};
+
+use tracing::instrument;
+
+/// An adapter that loops a region of a seekable source.
+///
+/// Playback starts at the beginning of the source. Whenever it reaches the
+/// end of the loop region, it seeks back to the start of the region, within
+/// the same read if need be, so there's no gap at the loop point. This is
+/// how sustained instrument samples are usually played: an attack, followed
+/// by a section that repeats for as long as the note is held.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::LoopRegion, generators::VecSource, AudioFormat, AudioSource, StreamState};
+/// // Each sample holds its own frame number.
+/// let samples = (0..100).map(|i| i as f32).collect();
+/// let source = VecSource::new(samples, AudioFormat::MONO_CD);
+/// let mut looped = LoopRegion::new(source, 20, 30);
+///
+/// // A single read spans the loop point twice.
+/// let mut buffer = vec![0.0; 50];
+/// let result = looped.read(&mut buffer);
+/// assert_eq!(result.state, StreamState::Good);
+/// assert_eq!(result.read, 50);
+///
+/// let expected: Vec<f32> = (0..30).chain(20..30).chain(20..30).map(|i| i as f32).collect();
+/// assert_eq!(buffer, expected);
+/// ```
+pub struct LoopRegion<S: AudioSource + Seek> {
+    source: S,
+    start: u64,
+    end: u64,
+    position: u64,
+}
+
+impl<S: AudioSource + Seek> LoopRegion<S> {
+    /// Construct a looping adapter, seeking `source` to its beginning.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to loop.
+    /// * `start_frame` -- The first frame of the loop region.
+    /// * `end_frame` -- The frame after the last frame of the loop region.
+    ///
+    /// # Panics
+    ///
+    /// If `start_frame` is not less than `end_frame`.
+    pub fn new(mut source: S, start_frame: u64, end_frame: u64) -> Self {
+        let position = source.seek_frame(0);
+        let mut looped = LoopRegion {
+            source,
+            start: 0,
+            end: 0,
+            position,
+        };
+        looped.set_loop_region(start_frame, end_frame);
+        looped
+    }
+
+    /// Set the region to loop. If playback is already past the end of the
+    /// region, it jumps to the start of the region on the next read.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_frame` -- The first frame of the loop region.
+    /// * `end_frame` -- The frame after the last frame of the loop region.
+    ///
+    /// # Panics
+    ///
+    /// If `start_frame` is not less than `end_frame`.
+    pub fn set_loop_region(&mut self, start_frame: u64, end_frame: u64) {
+        assert!(
+            start_frame < end_frame,
+            "The loop region must contain at least one frame."
+        );
+        self.start = start_frame;
+        self.end = end_frame;
+    }
+
+    /// Returns the loop region as `(start_frame, end_frame)`.
+    pub fn loop_region(&self) -> (u64, u64) {
+        (self.start, self.end)
+    }
+
+    /// Returns the frame of the source that will be read next.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<S: AudioSource + Seek> AudioSource for LoopRegion<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "LoopRegion::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.source.format().channels as usize;
+        let mut written = 0;
+
+        while written < buffer.len() {
+            if self.position >= self.end {
+                self.position = self.source.seek_frame(self.start);
+                if self.position != self.start {
+                    // The source is shorter than the loop region.
+                    return ReadResult::finished(written);
+                }
+            }
+
+            let frames = (self.end - self.position) as usize;
+            let len = std::cmp::min(buffer.len() - written, frames * channels);
+            let result = self.source.read(&mut buffer[written..written + len]);
+            written += result.read;
+            self.position += (result.read / channels) as u64;
+
+            if result.read < len {
+                return ReadResult {
+                    state: result.state,
+                    read: written,
+                };
+            }
+        }
+
+        ReadResult::good(written)
+    }
+}
+
+impl<S: AudioSource + Seek + Latency> Latency for LoopRegion<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Seek + Tail> Tail for LoopRegion<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Seek + Rewind> Rewind for LoopRegion<S> {
+    fn rewind(&mut self) -> bool {
+        let rewound = self.source.rewind();
+        if rewound {
+            self.position = 0;
+        }
+        rewound
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for LoopRegion<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.position = self.source.seek_frame(frame);
+        self.position
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    core::AudioSource, AudioFormat, Latency, ReadResult, Rewind, Sample, StreamState, Tail,
+};
+
+use tracing::instrument;
+
+/// An adapter that plays a source over and over, e.g. for background music.
+///
+/// Whenever the source finishes, it's [rewound](crate::Rewind) and reading
+/// continues within the same read, so the buffer is filled right across the
+/// loop point with no gap. The loop never finishes, unless the source can't
+/// be rewound or finishes again without playing anything, which would
+/// otherwise loop forever within a single read.
+///
+/// To loop only part of a source, use [`LoopRegion`](crate::effects::LoopRegion).
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Looping, generators::VecSource, AudioFormat, AudioSource, StreamState};
+/// let source = VecSource::new(vec![1.0, 2.0, 3.0], AudioFormat::MONO_CD);
+/// let mut looping = Looping::new(source);
+///
+/// let mut buffer = vec![0.0; 8];
+/// assert_eq!(looping.read(&mut buffer).state, StreamState::Good);
+/// assert_eq!(buffer, [1.0, 2.0, 3.0, 1.0, 2.0, 3.0, 1.0, 2.0]);
+/// assert_eq!(looping.loops(), 2);
+/// ```
+pub struct Looping<S: AudioSource + Rewind> {
+    source: S,
+    loops: u64,
+}
+
+impl<S: AudioSource + Rewind> Looping<S> {
+    /// Construct a looping adapter, which plays `source` from its current position.
+    pub fn new(source: S) -> Self {
+        Looping { source, loops: 0 }
+    }
+
+    /// Returns the number of times the source has been rewound.
+    pub fn loops(&self) -> u64 {
+        self.loops
+    }
+
+    /// Returns the wrapped source, ending the loop.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: AudioSource + Rewind> AudioSource for Looping<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Looping::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let mut written = 0;
+        let mut rewound = false;
+
+        while written < buffer.len() {
+            let result = self.source.read(&mut buffer[written..]);
+            written += result.read;
+
+            match result.state {
+                StreamState::Good => {}
+                StreamState::Underrun => return ReadResult::underrun(written),
+                StreamState::Finished => {
+                    // A source that finishes straight after rewinding is empty.
+                    if (rewound && result.read == 0) || !self.source.rewind() {
+                        return ReadResult::finished(written);
+                    }
+                    self.loops += 1;
+                    rewound = true;
+                }
+            }
+        }
+
+        ReadResult::good(written)
+    }
+}
+
+impl<S: AudioSource + Rewind + Latency> Latency for Looping<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind + Tail> Tail for Looping<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Looping<S> {
+    fn rewind(&mut self) -> bool {
+        let rewound = self.source.rewind();
+        if rewound {
+            self.loops = 0;
+        }
+        rewound
+    }
+}
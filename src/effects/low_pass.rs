@@ -1,4 +1,8 @@
-use crate::{core::AudioSource, ReadResult, Sample};
+use crate::{
+    core::{normalized_frequency, AudioSource},
+    effects::smoothed::{ramp, Smoothed},
+    flush_denormal, midi_to_frequency, Latency, Param, ReadResult, Rewind, Sample, Seek, Tail,
+};
 
 use tracing::instrument;
 
@@ -7,6 +11,11 @@ use tracing::instrument;
 /// `LowPass` reduces the volume of frequencies above the given cutoff.
 /// This can create the impression of sound coming from far away or in another room.
 ///
+/// Changes to the cutoff ramp over a few milliseconds rather than taking effect
+/// immediately, so the cutoff can be swept in real time without clicks. The
+/// cutoff can also be changed from another thread through
+/// [`cutoff_param`](LowPass::cutoff_param).
+///
 /// # Examples
 /// ```
 /// # use timbre::{generators::SineWave, effects::LowPass, IntoShared};
@@ -14,9 +23,46 @@ use tracing::instrument;
 /// let sin = SineWave::new(1.0, 440.0);
 /// let low_pass = LowPass::new(sin, 200.0);
 /// ```
+///
+/// Any number of channels is supported, and each is filtered independently:
+/// ```
+/// # use timbre::{generators::SineWave, effects::LowPass, AudioFormat, AudioSource, ReadResult, Sample};
+/// // Six channels, each carrying a different tone.
+/// struct Surround(Vec<SineWave>);
+///
+/// impl AudioSource for Surround {
+///     fn format(&self) -> AudioFormat {
+///         AudioFormat { channels: 6, sample_rate: 44100 }
+///     }
+///     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+///         let mut channel = vec![0.0; buffer.len() / 6];
+///         for (i, sin) in self.0.iter_mut().enumerate() {
+///             sin.read(&mut channel);
+///             buffer.iter_mut().skip(i).step_by(6).zip(channel.iter()).for_each(|(b, c)| *b = *c);
+///         }
+///         ReadResult::good(buffer.len())
+///     }
+/// }
+///
+/// let tone = |i| SineWave::with_format(AudioFormat::MONO_CD, 1.0, 200.0 * (i + 1) as f32);
+/// let mut surround = LowPass::new(Surround((0..6).map(tone).collect()), 500.0);
+/// let mut samples = vec![0.0; 6 * 1024];
+/// surround.read(&mut samples);
+///
+/// for i in 0..6 {
+///     let mut mono = LowPass::new(tone(i), 500.0);
+///     let mut expected = vec![0.0; 1024];
+///     mono.read(&mut expected);
+///
+///     let channel: Vec<f32> = samples.iter().skip(i).step_by(6).copied().collect();
+///     assert_eq!(channel, expected);
+/// }
+/// ```
 pub struct LowPass<S: AudioSource> {
-    buffer: Vec<f32>,
-    rc: f32,
+    state: Vec<f32>,
+    cutoff: Param,
+    applied: f32,
+    rc: Smoothed,
     source: S,
 }
 
@@ -28,17 +74,113 @@ impl<S: AudioSource> LowPass<S> {
     /// * `source` -- The source of audio for this effect.
     /// * `cutoff` -- The frequency above which volume will be reduced.
     pub fn new(source: S, cutoff: f32) -> Self {
-        let buffer = Vec::new();
-        let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff);
-        LowPass { buffer, rc, source }
+        let state = vec![0.0; source.format().channels as usize];
+        let rc = Smoothed::new(time_constant(cutoff));
+        LowPass {
+            state,
+            cutoff: Param::new(cutoff),
+            applied: cutoff,
+            rc,
+            source,
+        }
     }
 
+    /// Set the frequency above which volume will be reduced.
+    ///
+    /// The filter ramps to the new cutoff over a few milliseconds.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::LowPass, AudioSource};
+    /// let sin = SineWave::new(1.0, 440.0);
+    /// let mut low_pass = LowPass::new(sin, 50.0);
+    ///
+    /// let mut samples = vec![0.0; 8192];
+    /// low_pass.read(&mut samples);
+    /// let mut previous = samples[samples.len() - 2];
+    ///
+    /// // Jump from heavily filtered to nearly unfiltered.
+    /// low_pass.set_cutoff(15000.0);
+    /// low_pass.read(&mut samples);
+    ///
+    /// // A 440 Hz sine never changes by more than ~0.063 between samples,
+    /// // so any larger step would be a click.
+    /// for frame in samples.chunks_exact(2) {
+    ///     assert!((frame[0] - previous).abs() < 0.1);
+    ///     previous = frame[0];
+    /// }
+    /// ```
     pub fn set_cutoff(&mut self, cutoff: f32) {
-        self.rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff);
+        self.cutoff.set(cutoff);
     }
 
+    /// Set the cutoff from a value between 0.0 and 1.0, e.g. from a slider.
+    ///
+    /// The value is mapped logarithmically, from 20 Hz at 0.0 to 20 kHz at
+    /// 1.0, so that equal steps are equal musical intervals. Values outside
+    /// that range are clamped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::LowPass};
+    /// let mut filter = LowPass::new(SineWave::new(1.0, 440.0), 1000.0);
+    ///
+    /// // Halfway is the geometric mean of 20 Hz and 20 kHz.
+    /// filter.set_cutoff_normalized(0.5);
+    /// assert!((filter.cutoff() - (20.0f32 * 20000.0).sqrt()).abs() < 0.01);
+    ///
+    /// filter.set_cutoff_normalized(0.0);
+    /// assert!((filter.cutoff() - 20.0).abs() < 1e-3);
+    /// filter.set_cutoff_normalized(1.0);
+    /// assert!((filter.cutoff() - 20000.0).abs() < 0.1);
+    /// ```
+    pub fn set_cutoff_normalized(&mut self, normalized: f32) {
+        self.set_cutoff(normalized_frequency(normalized));
+    }
+
+    /// Set the cutoff to the frequency of a MIDI note, with A4 (note 69) at 440 Hz.
+    ///
+    /// This lets the cutoff track the notes being played, e.g. in a synthesizer.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::LowPass};
+    /// let mut filter = LowPass::new(SineWave::new(1.0, 440.0), 1000.0);
+    /// filter.set_cutoff_note(69.0);
+    /// assert_eq!(filter.cutoff(), 440.0);
+    /// ```
+    pub fn set_cutoff_note(&mut self, note: f32) {
+        self.set_cutoff(midi_to_frequency(note));
+    }
+
+    /// Returns the cutoff frequency, or the frequency being ramped toward.
     pub fn cutoff(&self) -> f32 {
-        1.0 / (2.0 * std::f32::consts::PI * self.rc)
+        self.cutoff.get()
+    }
+
+    /// Returns a handle to the cutoff frequency, which can be changed from any thread.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, effects::LowPass};
+    /// let sin = SineWave::new(1.0, 440.0);
+    /// let filter = LowPass::new(sin, 1000.0);
+    ///
+    /// let cutoff = filter.cutoff_param();
+    /// std::thread::spawn(move || cutoff.set(500.0)).join().unwrap();
+    /// assert_eq!(filter.cutoff(), 500.0);
+    /// ```
+    pub fn cutoff_param(&self) -> Param {
+        self.cutoff.clone()
+    }
+
+    /// Start ramping toward the cutoff if it has changed since the last read.
+    fn update_cutoff(&mut self) {
+        let cutoff = self.cutoff.get();
+        if cutoff != self.applied {
+            self.applied = cutoff;
+            self.rc.set(time_constant(cutoff));
+        }
     }
 }
 
@@ -49,64 +191,76 @@ impl<S: AudioSource> AudioSource for LowPass<S> {
 
     #[instrument(name = "LowPass::read", skip(self, buffer))]
     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        // Adapters sometimes ask for nothing; don't pass that on to the source.
+        if buffer.is_empty() {
+            return ReadResult::good(0);
+        }
+
         let format = self.source.format();
         let result = self.source.read(buffer);
         let written = result.read;
         if written == 0 {
             return result;
         }
-        self.buffer.resize(buffer.len(), 0.0);
-
-        match format.channels {
-            1 => {
-                let dt = 1.0 / format.sample_rate as f32;
-                filter_mono(
-                    &mut buffer[..written],
-                    &mut self.buffer[..written],
-                    dt,
-                    self.rc,
-                );
-            }
-            2 => {
-                let dt = 1.0 / format.sample_rate as f32;
-                filter_stereo(
-                    &mut buffer[..written],
-                    &mut self.buffer[..written],
-                    dt,
-                    self.rc,
-                );
-            }
-            _ => panic!("Unsupported channel count."),
-        }
+        self.state.resize(format.channels as usize, 0.0);
+
+        let frames = written / format.channels as usize;
+        self.update_cutoff();
+        let rc = self.rc.advance(frames as f32 / format.sample_rate as f32);
+        let dt = 1.0 / format.sample_rate as f32;
+        filter(&mut buffer[..written], &mut self.state, dt, rc);
 
         result
     }
 }
 
-fn filter_mono(samples: &mut [f32], buffer: &mut [f32], dt: f32, rc: f32) {
-    assert!(!samples.is_empty() && !buffer.is_empty());
-    assert!(buffer.len() >= samples.len());
+impl<S: AudioSource + Latency> Latency for LowPass<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
 
-    let a = rc / (rc + dt);
+impl<S: AudioSource + Tail> Tail for LowPass<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
 
-    buffer[0] = buffer[buffer.len() - 1] + a * (samples[0] - buffer[buffer.len() - 1]);
-    for i in 1..buffer.len() {
-        buffer[i] = buffer[i - 1] + a * (samples[i] - buffer[i - 1]);
+impl<S: AudioSource + Rewind> Rewind for LowPass<S> {
+    fn rewind(&mut self) -> bool {
+        self.state.iter_mut().for_each(|s| *s = 0.0);
+        self.update_cutoff();
+        self.rc.finish();
+        self.source.rewind()
     }
-    samples.copy_from_slice(&buffer[..samples.len()]);
 }
 
-fn filter_stereo(samples: &mut [f32], buffer: &mut [f32], dt: f32, rc: f32) {
-    assert!(!samples.is_empty() && !buffer.is_empty());
-    assert!(samples.len() % 2 == 0 && buffer.len() % 2 == 0);
-    assert!(buffer.len() >= samples.len());
+impl<S: AudioSource + Seek> Seek for LowPass<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.state.iter_mut().for_each(|s| *s = 0.0);
+        self.update_cutoff();
+        self.rc.finish();
+        self.source.seek_frame(frame)
+    }
+}
+
+/// Filter interleaved `samples`, where `state` holds the previous output for each channel.
+fn filter(samples: &mut [f32], state: &mut [f32], dt: f32, rc: (f32, f32)) {
+    let channels = state.len();
+    assert!(samples.len().is_multiple_of(channels));
 
-    let a = dt / (rc + dt);
+    let frames = samples.len() / channels;
+    for (i, frame) in samples.chunks_exact_mut(channels).enumerate() {
+        let rc = ramp(rc, i + 1, frames);
+        let a = dt / (rc + dt);
 
-    buffer[0] = buffer[buffer.len() - 2] + a * (samples[0] - buffer[buffer.len() - 2]);
-    buffer[1] = buffer[buffer.len() - 1] + a * (samples[1] - buffer[buffer.len() - 1]);
-    for i in 2..buffer.len() {
-        buffer[i] = buffer[i - 2] + a * (samples[i] - buffer[i - 2]);
+        for (sample, previous) in frame.iter_mut().zip(state.iter_mut()) {
+            *previous = flush_denormal(*previous + a * (*sample - *previous));
+            *sample = *previous;
+        }
     }
-    samples.copy_from_slice(&buffer[..samples.len()]);
+}
+
+fn time_constant(cutoff: f32) -> f32 {
+    1.0 / (2.0 * std::f32::consts::PI * cutoff)
 }
@@ -0,0 +1,287 @@
+use crate::{
+    core::AudioSource, db_to_linear, linear_to_db, AudioFormat, Latency, ReadResult, Rewind,
+    Sample, Seek, Tail,
+};
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// An effect that measures the level of audio passing through it.
+///
+/// Audio passes through `Meter` unchanged. After each read, the peak and RMS
+/// of the samples read are available from [`peak`](Meter::peak) and
+/// [`rms`](Meter::rms). Like a physical peak programme meter,
+/// [`peak_hold`](Meter::peak_hold) holds the highest recent peak for a while
+/// and then lets it fall at a steady rate, which is much easier to read than
+/// the raw peak of each block.
+///
+/// The levels can be read from another thread, e.g. a UI, through a
+/// [`MeterHandle`].
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Meter, generators::SineWave, AudioSource};
+/// let sin = SineWave::new(0.5, 440.0);
+/// let mut meter = Meter::new(sin);
+///
+/// let mut samples = vec![0.0; 4096];
+/// meter.read(&mut samples);
+///
+/// assert!((meter.peak() - 0.5).abs() < 0.01);
+/// assert!((meter.rms() - 0.5 / 2.0f32.sqrt()).abs() < 0.01);
+/// ```
+pub struct Meter<S: AudioSource> {
+    source: S,
+    levels: Arc<Levels>,
+    hold: Duration,
+    decay: f32,
+    held: f32,
+    hold_remaining: f64,
+}
+
+/// A handle for reading the levels of a [`Meter`] from another thread.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Meter, generators::SineWave, AudioSource};
+/// let sin = SineWave::new(0.5, 440.0);
+/// let mut meter = Meter::new(sin);
+/// let handle = meter.handle();
+///
+/// let ui = std::thread::spawn(move || {
+///     while handle.peak() == 0.0 {
+///         std::thread::yield_now();
+///     }
+///     handle.peak()
+/// });
+///
+/// let mut samples = vec![0.0; 4096];
+/// meter.read(&mut samples);
+/// assert!((ui.join().unwrap() - 0.5).abs() < 0.01);
+/// ```
+#[derive(Clone)]
+pub struct MeterHandle {
+    levels: Arc<Levels>,
+}
+
+#[derive(Default)]
+struct Levels {
+    peak: AtomicU32,
+    rms: AtomicU32,
+    peak_hold: AtomicU32,
+}
+
+fn load(value: &AtomicU32) -> f32 {
+    f32::from_bits(value.load(Ordering::Relaxed))
+}
+
+fn store(value: &AtomicU32, level: f32) {
+    value.store(level.to_bits(), Ordering::Relaxed);
+}
+
+impl<S: AudioSource> Meter<S> {
+    /// Construct a `Meter` that holds peaks for 1 second, then lets them fall at 12 dB per second.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to measure.
+    pub fn new(source: S) -> Self {
+        Meter::with_peak_hold(source, Duration::from_secs(1), 12.0)
+    }
+
+    /// Construct a `Meter` with the given peak-hold behavior.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to measure.
+    /// * `hold` -- How long to hold a peak before it starts to fall.
+    /// * `decay` -- How quickly a held peak falls once the hold expires, in dB per second.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::Meter, linear_to_db, AudioFormat, AudioSource, ReadResult, Sample};
+    /// # use std::time::Duration;
+    /// // A single full-scale sample, followed by silence.
+    /// struct Impulse(bool);
+    ///
+    /// impl AudioSource for Impulse {
+    ///     fn format(&self) -> AudioFormat {
+    ///         AudioFormat::MONO_CD
+    ///     }
+    ///     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+    ///         buffer.iter_mut().for_each(|s| *s = 0.0);
+    ///         if !self.0 {
+    ///             buffer[0] = 1.0;
+    ///             self.0 = true;
+    ///         }
+    ///         ReadResult::good(buffer.len())
+    ///     }
+    /// }
+    ///
+    /// let mut meter = Meter::with_peak_hold(Impulse(false), Duration::from_millis(500), 20.0);
+    /// let mut samples = vec![0.0; 4410]; // 100ms
+    ///
+    /// // The peak is held for 500ms...
+    /// for _ in 0..6 {
+    ///     meter.read(&mut samples);
+    ///     assert_eq!(meter.peak_hold(), 1.0);
+    /// }
+    /// assert_eq!(meter.peak(), 0.0);
+    ///
+    /// // ...then falls by 2dB every 100ms.
+    /// for i in 1..10 {
+    ///     meter.read(&mut samples);
+    ///     assert!((linear_to_db(meter.peak_hold()) + 2.0 * i as f32).abs() < 0.01);
+    /// }
+    /// ```
+    pub fn with_peak_hold(source: S, hold: Duration, decay: f32) -> Self {
+        Meter {
+            source,
+            levels: Arc::new(Levels::default()),
+            hold,
+            decay,
+            held: 0.0,
+            hold_remaining: 0.0,
+        }
+    }
+
+    /// Returns a handle for reading the levels from another thread.
+    pub fn handle(&self) -> MeterHandle {
+        MeterHandle {
+            levels: Arc::clone(&self.levels),
+        }
+    }
+
+    /// Returns the largest absolute sample value in the most recent read.
+    pub fn peak(&self) -> f32 {
+        load(&self.levels.peak)
+    }
+
+    /// Returns the RMS of the samples in the most recent read.
+    pub fn rms(&self) -> f32 {
+        load(&self.levels.rms)
+    }
+
+    /// Returns the held peak, which falls after the hold time expires.
+    pub fn peak_hold(&self) -> f32 {
+        load(&self.levels.peak_hold)
+    }
+
+    /// Set how long to hold a peak before it starts to fall.
+    pub fn set_hold(&mut self, hold: Duration) {
+        self.hold = hold;
+    }
+
+    pub fn hold(&self) -> Duration {
+        self.hold
+    }
+
+    /// Set how quickly a held peak falls once the hold expires, in dB per second.
+    pub fn set_decay(&mut self, decay: f32) {
+        self.decay = decay;
+    }
+
+    pub fn decay(&self) -> f32 {
+        self.decay
+    }
+
+    fn reset(&mut self) {
+        self.held = 0.0;
+        self.hold_remaining = 0.0;
+        store(&self.levels.peak, 0.0);
+        store(&self.levels.rms, 0.0);
+        store(&self.levels.peak_hold, 0.0);
+    }
+
+    /// Update the held peak given the peak of a block lasting `elapsed` seconds.
+    fn update_hold(&mut self, peak: f32, elapsed: f64) {
+        if peak >= self.held {
+            self.held = peak;
+            self.hold_remaining = self.hold.as_secs_f64();
+            return;
+        }
+
+        let falling = elapsed - self.hold_remaining;
+        self.hold_remaining = f64::max(0.0, self.hold_remaining - elapsed);
+        if falling > 0.0 {
+            let held = linear_to_db(self.held) - self.decay * falling as f32;
+            self.held = f32::max(peak, db_to_linear(held));
+        }
+    }
+}
+
+impl MeterHandle {
+    /// Returns the largest absolute sample value in the most recent read.
+    pub fn peak(&self) -> f32 {
+        load(&self.levels.peak)
+    }
+
+    /// Returns the RMS of the samples in the most recent read.
+    pub fn rms(&self) -> f32 {
+        load(&self.levels.rms)
+    }
+
+    /// Returns the held peak, which falls after the hold time expires.
+    pub fn peak_hold(&self) -> f32 {
+        load(&self.levels.peak_hold)
+    }
+}
+
+impl<S: AudioSource> AudioSource for Meter<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Meter::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.source.read(buffer);
+        let samples = &buffer[..result.read];
+        if samples.is_empty() {
+            return result;
+        }
+
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+        let format = self.source.format();
+        let frames = samples.len() / format.channels as usize;
+        self.update_hold(peak, frames as f64 / format.sample_rate as f64);
+
+        store(&self.levels.peak, peak);
+        store(&self.levels.rms, rms);
+        store(&self.levels.peak_hold, self.held);
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Meter<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Meter<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Meter<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Meter<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset();
+        self.source.seek_frame(frame)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{core::AudioSource, effects::LowPass, Latency, ReadResult, Sample, Tail};
+
+use tracing::instrument;
+
+/// A low-pass filter whose cutoff is driven by a control signal.
+///
+/// The control source is read alongside the audio, one control frame per audio
+/// frame, and the cutoff is updated once per block from the first control
+/// sample of the block:
+///
+/// `cutoff = base_cutoff + depth * control`
+///
+/// Driving the cutoff with a [`SineWave`](crate::generators::SineWave) gives
+/// an LFO-swept filter; a [`Ramp`](crate::generators::Ramp) gives a filter sweep.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::ModulatedLowPass, generators::{Ramp, SineWave}, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// let sin = SineWave::new(1.0, 440.0);
+/// let ramp = Ramp::new(0.0, 1.0, Duration::from_secs(1), AudioFormat::MONO_CD);
+/// let mut filter = ModulatedLowPass::new(sin, 200.0, ramp, 1000.0);
+///
+/// let mut samples = vec![0.0; 4410 * 2];
+/// for _ in 0..5 {
+///     filter.read(&mut samples);
+/// }
+/// // The last block started 0.4 s into the ramp.
+/// assert!((filter.cutoff() - 600.0).abs() < 1.0);
+/// ```
+pub struct ModulatedLowPass<S: AudioSource, C: AudioSource> {
+    filter: LowPass<S>,
+    control: C,
+    base_cutoff: f32,
+    depth: f32,
+    buffer: Vec<f32>,
+}
+
+impl<S: AudioSource, C: AudioSource> ModulatedLowPass<S, C> {
+    /// Construct a modulated low-pass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `base_cutoff` -- The cutoff frequency when the control signal is zero.
+    /// * `control` -- The source of the control signal. Should have the same sample rate as `source`.
+    /// * `depth` -- The change in cutoff frequency, in Hz, per unit of control signal.
+    pub fn new(source: S, base_cutoff: f32, control: C, depth: f32) -> Self {
+        ModulatedLowPass {
+            filter: LowPass::new(source, base_cutoff),
+            control,
+            base_cutoff,
+            depth,
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn set_base_cutoff(&mut self, base_cutoff: f32) {
+        self.base_cutoff = base_cutoff;
+    }
+
+    pub fn base_cutoff(&self) -> f32 {
+        self.base_cutoff
+    }
+
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth;
+    }
+
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// Returns the cutoff frequency currently applied to the filter.
+    pub fn cutoff(&self) -> f32 {
+        self.filter.cutoff()
+    }
+}
+
+impl<S: AudioSource, C: AudioSource> AudioSource for ModulatedLowPass<S, C> {
+    fn format(&self) -> crate::AudioFormat {
+        self.filter.format()
+    }
+
+    #[instrument(name = "ModulatedLowPass::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let frames = buffer.len() / self.filter.format().channels as usize;
+        let control_channels = self.control.format().channels as usize;
+        self.buffer.resize(frames * control_channels, 0.0);
+
+        let control = self.control.read(&mut self.buffer);
+        if control.read > 0 {
+            // Keep the cutoff positive so the filter stays stable.
+            let cutoff = self.base_cutoff + self.depth * self.buffer[0];
+            self.filter.set_cutoff(cutoff.max(1.0));
+        }
+
+        self.filter.read(buffer)
+    }
+}
+
+impl<S: AudioSource + Latency, C: AudioSource> Latency for ModulatedLowPass<S, C> {
+    fn latency_frames(&self) -> u64 {
+        self.filter.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail, C: AudioSource> Tail for ModulatedLowPass<S, C> {
+    fn tail_frames(&self) -> u64 {
+        self.filter.tail_frames()
+    }
+}
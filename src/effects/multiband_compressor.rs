@@ -0,0 +1,351 @@
+use crate::{
+    core::AudioSource,
+    db_to_linear,
+    effects::{
+        biquad::{filter, Coefficients},
+        crossover::{self, LinkwitzRiley},
+        envelope::EnvelopeFollower,
+    },
+    linear_to_db, Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// Settings for compressing one band of a [`MultibandCompressor`](crate::effects::MultibandCompressor).
+///
+/// The default settings leave the band unchanged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CompressorSettings {
+    /// The level, in dB, above which the band is compressed.
+    pub threshold_db: f32,
+    /// How much the level above the threshold is reduced, e.g. 4.0 for 4:1. 1.0 disables compression.
+    pub ratio: f32,
+    /// How quickly compression responds to a rising level.
+    pub attack: Duration,
+    /// How quickly compression recovers once the level falls.
+    pub release: Duration,
+    /// Gain, in dB, applied to the band after compression.
+    pub makeup_db: f32,
+}
+
+impl Default for CompressorSettings {
+    fn default() -> Self {
+        CompressorSettings {
+            threshold_db: 0.0,
+            ratio: 1.0,
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(100),
+            makeup_db: 0.0,
+        }
+    }
+}
+
+impl CompressorSettings {
+    fn is_bypassed(&self) -> bool {
+        self.ratio == 1.0 && self.makeup_db == 0.0
+    }
+}
+
+/// An effect that splits a source into frequency bands and compresses each separately.
+///
+/// Compressing bands separately keeps, e.g., a loud bass note from pulling
+/// down the level of the vocals, which is why multiband compression is a
+/// staple of mastering.
+///
+/// The bands are split by 4th-order Linkwitz-Riley filters at each crossover
+/// frequency, and lower bands are passed through matching all-pass filters so
+/// every band ends up with the same phase response. As a result, when no band
+/// is compressed the bands sum to the input with a perfectly flat frequency
+/// response; only the phase is shifted, which is inaudible.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{CompressorSettings, MultibandCompressor}, generators::{SineWave, VecSource}, AudioFormat, AudioSource};
+/// // Broadband noise.
+/// let mut seed = 1u32;
+/// let noise: Vec<f32> = (0..44100)
+///     .map(|_| {
+///         seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+///         (seed >> 8) as f32 / (1 << 23) as f32 - 1.0
+///     })
+///     .collect();
+///
+/// // With every band bypassed, splitting and recombining preserves the
+/// // level of a broadband signal...
+/// fn bypassed<S: AudioSource>(source: S) -> MultibandCompressor<S> {
+///     let bands = vec![CompressorSettings::default(); 4];
+///     MultibandCompressor::new(source, vec![200.0, 2000.0, 8000.0], bands)
+/// }
+/// let mut compressor = bypassed(VecSource::new(noise.clone(), AudioFormat::MONO_CD));
+/// let mut samples = vec![0.0; 44100];
+/// compressor.read(&mut samples);
+///
+/// let power = |s: &[f32]| s.iter().map(|s| s * s).sum::<f32>() / s.len() as f32;
+/// let error = (power(&samples[4410..]) / power(&noise[4410..]) - 1.0).abs();
+/// assert!(error < 0.02);
+///
+/// // ...and of every frequency, including those at the crossovers.
+/// for &frequency in &[50.0, 200.0, 1000.0, 2000.0, 5000.0, 8000.0, 15000.0] {
+///     let sin = SineWave::with_format(AudioFormat::MONO_CD, 0.5, frequency);
+///     let mut compressor = bypassed(sin);
+///     compressor.read(&mut samples);
+///     let peak = samples[22050..].iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+///     assert!((peak - 0.5).abs() < 0.005);
+/// }
+/// ```
+///
+/// Compressing one band leaves the others alone:
+/// ```
+/// # use timbre::{effects::{CompressorSettings, MultibandCompressor}, generators::SineWave, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// let squash_bass = || {
+///     let bass = CompressorSettings {
+///         threshold_db: -20.0,
+///         ratio: 10.0,
+///         attack: Duration::from_millis(1),
+///         ..CompressorSettings::default()
+///     };
+///     vec![bass, CompressorSettings::default()]
+/// };
+/// let peak = |source: &mut dyn AudioSource| {
+///     let mut samples = vec![0.0; 44100];
+///     source.read(&mut samples);
+///     samples[22050..].iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+/// };
+///
+/// let bass = SineWave::with_format(AudioFormat::MONO_CD, 0.8, 100.0);
+/// let mut compressor = MultibandCompressor::new(bass, vec![1000.0], squash_bass());
+/// assert!(peak(&mut compressor) < 0.2);
+///
+/// let treble = SineWave::with_format(AudioFormat::MONO_CD, 0.8, 8000.0);
+/// let mut compressor = MultibandCompressor::new(treble, vec![1000.0], squash_bass());
+/// assert!((peak(&mut compressor) - 0.8).abs() < 0.05);
+/// ```
+pub struct MultibandCompressor<S: AudioSource> {
+    source: S,
+    crossovers: Vec<Crossover>,
+    bands: Vec<Band>,
+    rest: Vec<f32>,
+}
+
+/// A pair of Linkwitz-Riley filters, splitting off the band below the frequency.
+struct Crossover {
+    low: LinkwitzRiley,
+    high: LinkwitzRiley,
+}
+
+struct Band {
+    settings: CompressorSettings,
+    follower: EnvelopeFollower,
+    /// All-pass state for each crossover above the one that split off this band.
+    all_pass: Vec<Vec<[f32; 2]>>,
+    samples: Vec<f32>,
+}
+
+impl Band {
+    fn new(settings: CompressorSettings) -> Self {
+        Band {
+            settings,
+            follower: EnvelopeFollower::new(
+                settings.attack.as_secs_f32(),
+                settings.release.as_secs_f32(),
+            ),
+            all_pass: Vec::new(),
+            samples: Vec::new(),
+        }
+    }
+
+    /// Compress the band's samples, linking the channels so the stereo image doesn't shift.
+    fn compress(&mut self, channels: usize, sample_rate: u32) {
+        let settings = self.settings;
+        let slope = 1.0 - 1.0 / settings.ratio;
+
+        for frame in self.samples.chunks_exact_mut(channels) {
+            let level = frame.iter().fold(0.0f32, |level, s| level.max(s.abs()));
+            let envelope = self.follower.follow(level, sample_rate);
+
+            let over = linear_to_db(envelope) - settings.threshold_db;
+            let reduction = if over > 0.0 { over * slope } else { 0.0 };
+            let gain = db_to_linear(settings.makeup_db - reduction);
+            frame.iter_mut().for_each(|s| *s *= gain);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.follower.reset();
+        for state in self.all_pass.iter_mut() {
+            state.iter_mut().for_each(|z| *z = [0.0; 2]);
+        }
+    }
+}
+
+impl Crossover {
+    fn reset(&mut self) {
+        self.low.reset();
+        self.high.reset();
+    }
+}
+
+impl<S: AudioSource> MultibandCompressor<S> {
+    /// Construct a multiband compressor.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `crossovers` -- The frequencies, in Hz and increasing order, at which to split the bands.
+    /// * `bands` -- Settings for each band, from lowest to highest. There must be one more band than crossovers.
+    ///
+    /// # Panics
+    ///
+    /// If `crossovers` are not in increasing order, or there isn't exactly one more band than crossovers.
+    pub fn new(source: S, crossovers: Vec<f32>, bands: Vec<CompressorSettings>) -> Self {
+        assert!(
+            crossovers.windows(2).all(|pair| pair[0] < pair[1]),
+            "Crossover frequencies must be increasing."
+        );
+        assert_eq!(
+            bands.len(),
+            crossovers.len() + 1,
+            "There must be one more band than crossovers."
+        );
+
+        let channels = source.format().channels as usize;
+        let state = || vec![[0.0; 2]; channels];
+        let count = crossovers.len();
+        let crossovers = crossovers
+            .into_iter()
+            .map(|frequency| Crossover {
+                low: LinkwitzRiley::new(crossover::Band::Low, frequency, channels),
+                high: LinkwitzRiley::new(crossover::Band::High, frequency, channels),
+            })
+            .collect();
+        let bands = bands
+            .into_iter()
+            .enumerate()
+            .map(|(i, settings)| Band {
+                all_pass: (i + 1..count).map(|_| state()).collect(),
+                ..Band::new(settings)
+            })
+            .collect();
+
+        MultibandCompressor {
+            source,
+            crossovers,
+            bands,
+            rest: Vec::new(),
+        }
+    }
+
+    /// Returns the crossover frequencies.
+    pub fn crossovers(&self) -> Vec<f32> {
+        self.crossovers.iter().map(|c| c.low.frequency()).collect()
+    }
+
+    /// Replace the settings of one band.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than the number of bands.
+    pub fn set_band(&mut self, index: usize, settings: CompressorSettings) {
+        let band = &mut self.bands[index];
+        band.settings = settings;
+        band.follower.set_attack(settings.attack.as_secs_f32());
+        band.follower.set_release(settings.release.as_secs_f32());
+    }
+
+    /// Returns the settings of one band.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than the number of bands.
+    pub fn band(&self, index: usize) -> CompressorSettings {
+        self.bands[index].settings
+    }
+
+    fn reset(&mut self) {
+        self.crossovers.iter_mut().for_each(Crossover::reset);
+        self.bands.iter_mut().for_each(Band::reset);
+    }
+}
+
+impl<S: AudioSource> AudioSource for MultibandCompressor<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "MultibandCompressor::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let result = self.source.read(buffer);
+        let written = result.read;
+        if written == 0 {
+            return result;
+        }
+        let channels = format.channels as usize;
+        let sample_rate = format.sample_rate;
+
+        // Split off each band in turn, from the lowest up.
+        self.rest.clear();
+        self.rest.extend_from_slice(&buffer[..written]);
+        for (crossover, band) in self.crossovers.iter_mut().zip(self.bands.iter_mut()) {
+            band.samples.clear();
+            band.samples.extend_from_slice(&self.rest);
+            crossover.low.process(&mut band.samples, format);
+            crossover.high.process(&mut self.rest, format);
+        }
+        if let Some(top) = self.bands.last_mut() {
+            std::mem::swap(&mut top.samples, &mut self.rest);
+        }
+
+        buffer[..written].iter_mut().for_each(|s| *s = 0.0);
+        for (i, band) in self.bands.iter_mut().enumerate() {
+            // The bands above were shifted in phase by the crossovers between
+            // them, so apply the same shift here to keep the bands aligned.
+            let above = self.crossovers.iter().skip(i + 1);
+            for (crossover, state) in above.zip(band.all_pass.iter_mut()) {
+                let frequency = crossover.low.frequency();
+                let all_pass = Coefficients::all_pass(sample_rate, frequency, LinkwitzRiley::Q);
+                state.resize(channels, [0.0; 2]);
+                filter(&mut band.samples, state, &all_pass);
+            }
+
+            if !band.settings.is_bypassed() {
+                band.compress(channels, sample_rate);
+            }
+            buffer[..written]
+                .iter_mut()
+                .zip(band.samples.iter())
+                .for_each(|(o, b)| *o += b);
+        }
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for MultibandCompressor<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for MultibandCompressor<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for MultibandCompressor<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for MultibandCompressor<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset();
+        self.source.seek_frame(frame)
+    }
+}
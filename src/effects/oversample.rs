@@ -0,0 +1,277 @@
+use crate::{core::AudioSource, AudioFormat, Latency, ReadResult, Sample, Tail};
+
+use tracing::instrument;
+
+/// An adapter that runs an effect at a multiple of the source's sample rate.
+///
+/// Nonlinear effects such as distortion generate harmonics above the Nyquist
+/// frequency, which fold back into the audible range as aliasing. `Oversample`
+/// upsamples the source, runs the inner effect at the higher rate, then filters
+/// and downsamples the result so those harmonics are removed instead of aliased.
+///
+/// The inner effect is constructed by a closure that receives the upsampled
+/// source, since the effect has to read from it.
+///
+/// # Performance
+///
+/// The inner effect processes `factor` times as many samples, and both the
+/// upsampling and downsampling filters run at the higher rate with
+/// `32 * factor + 1` taps each. Expect this to cost substantially more than the
+/// inner effect alone; 2x is often enough.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Oversample, Upsample}, generators::SineWave, AudioFormat, AudioSource, ReadResult, Sample};
+/// # fn power(samples: &[f32], frequency: f32) -> f32 {
+/// #     let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / 44100.0).cos();
+/// #     let (mut s1, mut s2) = (0.0, 0.0);
+/// #     for sample in samples {
+/// #         let s0 = sample + coefficient * s1 - s2;
+/// #         s2 = s1;
+/// #         s1 = s0;
+/// #     }
+/// #     s1 * s1 + s2 * s2 - coefficient * s1 * s2
+/// # }
+/// // A harsh nonlinear effect.
+/// struct HardClip<S: AudioSource>(S);
+///
+/// impl<S: AudioSource> AudioSource for HardClip<S> {
+///     fn format(&self) -> AudioFormat { self.0.format() }
+///     fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+///         let result = self.0.read(buffer);
+///         buffer.iter_mut().for_each(|s| *s = s.max(-0.3).min(0.3));
+///         result
+///     }
+/// }
+///
+/// let format = AudioFormat::MONO_CD;
+/// let mut naive = HardClip(SineWave::with_format(format, 1.0, 5000.0));
+/// let mut oversampled = Oversample::new(SineWave::with_format(format, 1.0, 5000.0), 4, HardClip);
+///
+/// let mut a = vec![0.0; 8192];
+/// let mut b = vec![0.0; 8192];
+/// naive.read(&mut a);
+/// oversampled.read(&mut b);
+///
+/// // The 5th and 7th harmonics (25 kHz and 35 kHz) alias to 19.1 kHz and 9.1 kHz.
+/// let aliased = |s: &[f32]| power(&s[1024..], 9100.0) + power(&s[1024..], 19100.0);
+/// assert!(aliased(&b) < 0.1 * aliased(&a));
+/// ```
+pub struct Oversample<S: AudioSource, E: AudioSource> {
+    inner: E,
+    factor: usize,
+    filter: Fir,
+    buffer: Vec<f32>,
+    _source: std::marker::PhantomData<S>,
+}
+
+/// An [`AudioSource`](crate::AudioSource) that presents its source at a higher sample rate.
+///
+/// Used by [`Oversample`](crate::effects::Oversample) as the source for the
+/// inner effect. Samples are interpolated with a windowed-sinc filter.
+pub struct Upsample<S: AudioSource> {
+    source: S,
+    factor: usize,
+    filter: Fir,
+    buffer: Vec<f32>,
+}
+
+impl<S: AudioSource, E: AudioSource> Oversample<S, E> {
+    /// Construct an oversampling adapter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `factor` -- The multiple of the source's sample rate to run `inner` at, usually 2 or 4.
+    /// * `inner` -- Constructs the effect to oversample from the upsampled source.
+    ///
+    /// # Panics
+    ///
+    /// If `factor` is zero.
+    pub fn new<F: FnOnce(Upsample<S>) -> E>(source: S, factor: usize, inner: F) -> Self {
+        assert!(factor > 0, "Oversampling factor must be at least 1.");
+        let channels = source.format().channels as usize;
+        let inner = inner(Upsample {
+            source,
+            factor,
+            filter: Fir::new(factor, channels),
+            buffer: Vec::new(),
+        });
+
+        Oversample {
+            inner,
+            factor,
+            filter: Fir::new(factor, channels),
+            buffer: Vec::new(),
+            _source: std::marker::PhantomData,
+        }
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+}
+
+impl<S: AudioSource, E: AudioSource> AudioSource for Oversample<S, E> {
+    fn format(&self) -> AudioFormat {
+        let format = self.inner.format();
+        AudioFormat {
+            sample_rate: format.sample_rate / self.factor as u32,
+            ..format
+        }
+    }
+
+    #[instrument(name = "Oversample::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.inner.format().channels as usize;
+        self.buffer.resize(buffer.len() * self.factor, 0.0);
+
+        let result = self.inner.read(&mut self.buffer);
+        let frames = result.read / channels;
+
+        for (i, frame) in self.buffer[..result.read]
+            .chunks_exact(channels)
+            .enumerate()
+        {
+            // Every frame must pass through the filter to keep its history
+            // intact, but only every `factor`th output is kept.
+            for (channel, &sample) in frame.iter().enumerate() {
+                let filtered = self.filter.process(channel, sample);
+                if i % self.factor == 0 {
+                    buffer[i / self.factor * channels + channel] = filtered;
+                }
+            }
+        }
+
+        let read = frames.div_ceil(self.factor) * channels;
+        ReadResult {
+            state: result.state,
+            read,
+        }
+    }
+}
+
+impl<S: AudioSource, E: AudioSource + Latency> Latency for Oversample<S, E> {
+    fn latency_frames(&self) -> u64 {
+        // Each linear-phase filter delays by half its length at the high rate.
+        let filters = (self.filter.taps.len() - 1) as u64;
+        (self.inner.latency_frames() + filters) / self.factor as u64
+    }
+}
+
+impl<S: AudioSource, E: AudioSource + Tail> Tail for Oversample<S, E> {
+    fn tail_frames(&self) -> u64 {
+        match self.inner.tail_frames() {
+            u64::MAX => u64::MAX,
+            tail => tail / self.factor as u64,
+        }
+    }
+}
+
+impl<S: AudioSource> AudioSource for Upsample<S> {
+    fn format(&self) -> AudioFormat {
+        let format = self.source.format();
+        AudioFormat {
+            sample_rate: format.sample_rate * self.factor as u32,
+            ..format
+        }
+    }
+
+    #[instrument(name = "Upsample::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.source.format().channels as usize;
+        let frames = buffer.len() / channels / self.factor;
+        self.buffer.resize(frames * channels, 0.0);
+
+        let result = self.source.read(&mut self.buffer);
+
+        // Insert zeros between samples, then filter out the resulting images.
+        // Scaling by `factor` restores the original level.
+        let gain = self.factor as f32;
+        for (i, frame) in self.buffer[..result.read]
+            .chunks_exact(channels)
+            .enumerate()
+        {
+            for k in 0..self.factor {
+                let out = (i * self.factor + k) * channels;
+                for (channel, &sample) in frame.iter().enumerate() {
+                    let input = if k == 0 { sample * gain } else { 0.0 };
+                    buffer[out + channel] = self.filter.process(channel, input);
+                }
+            }
+        }
+
+        ReadResult {
+            state: result.state,
+            read: result.read * self.factor,
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Upsample<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames() * self.factor as u64
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Upsample<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames().saturating_mul(self.factor as u64)
+    }
+}
+
+/// A windowed-sinc low-pass filter at the original Nyquist frequency.
+struct Fir {
+    taps: Vec<f32>,
+    history: Vec<Vec<f32>>,
+    position: usize,
+}
+
+impl Fir {
+    fn new(factor: usize, channels: usize) -> Self {
+        let length = 32 * factor + 1;
+        let middle = (length / 2) as f32;
+        // Cut off slightly below the original Nyquist frequency to leave room
+        // for the transition band.
+        let cutoff = 0.45 / factor as f32;
+
+        let mut taps: Vec<f32> = (0..length)
+            .map(|i| {
+                let x = i as f32 - middle;
+                let sinc = if x == 0.0 {
+                    2.0 * cutoff
+                } else {
+                    (2.0 * std::f32::consts::PI * cutoff * x).sin() / (std::f32::consts::PI * x)
+                };
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / (length - 1) as f32;
+                let blackman = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+                sinc * blackman
+            })
+            .collect();
+
+        let sum: f32 = taps.iter().sum();
+        taps.iter_mut().for_each(|t| *t /= sum);
+
+        Fir {
+            history: vec![vec![0.0; length]; channels],
+            taps,
+            position: 0,
+        }
+    }
+
+    fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        let length = self.taps.len();
+        let history = &mut self.history[channel];
+        history[self.position] = sample;
+
+        let mut sum = 0.0;
+        for (i, tap) in self.taps.iter().enumerate() {
+            sum += tap * history[(self.position + length - i) % length];
+        }
+
+        if channel + 1 == self.history.len() {
+            self.position = (self.position + 1) % length;
+        }
+        sum
+    }
+}
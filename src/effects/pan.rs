@@ -0,0 +1,201 @@
+use crate::{
+    core::AudioSource, AudioFormat, Latency, Param, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use std::f32::consts::FRAC_PI_2;
+
+use tracing::instrument;
+
+/// How [`Pan`](crate::effects::Pan) divides a mono signal between the left
+/// and right channels, as the gain of each channel when panned to the center.
+///
+/// Mixing consoles and DAWs differ in the law they use, so matching another
+/// tool's mix means matching its law.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum PanLaw {
+    /// 0 dB at the center: each channel is at full volume until the signal is
+    /// panned away from it. Centered signals sound louder than panned ones.
+    Linear,
+    /// -3 dB at the center, following a sine/cosine curve so the total power
+    /// is the same at every position.
+    #[default]
+    EqualPower,
+    /// -4.5 dB at the center, a compromise between equal power and -6 dB.
+    Minus4_5Db,
+    /// -6 dB at the center, with gains that always sum to 1, so the signal
+    /// keeps the same level if the channels are summed to mono.
+    Minus6Db,
+}
+
+impl PanLaw {
+    /// Returns the `(left, right)` gains for `pan`, from -1.0 (left) to 1.0 (right).
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{effects::PanLaw, linear_to_db};
+    /// let center_db = |law: PanLaw| linear_to_db(law.gains(0.0).0);
+    /// assert_eq!(center_db(PanLaw::Linear), 0.0);
+    /// assert!((center_db(PanLaw::EqualPower) + 3.0).abs() < 0.02);
+    /// assert!((center_db(PanLaw::Minus4_5Db) + 4.5).abs() < 0.02);
+    /// assert!((center_db(PanLaw::Minus6Db) + 6.0).abs() < 0.03);
+    ///
+    /// // Every law puts a hard-panned signal at full volume on one side only.
+    /// for &law in [PanLaw::Linear, PanLaw::EqualPower, PanLaw::Minus4_5Db, PanLaw::Minus6Db].iter() {
+    ///     let (left, right) = law.gains(-1.0);
+    ///     assert!((left - 1.0).abs() < 1e-6 && right.abs() < 1e-6);
+    /// }
+    /// ```
+    pub fn gains(self, pan: f32) -> (f32, f32) {
+        let pan = pan.clamp(-1.0, 1.0);
+        // The fraction of the way from left to right.
+        let position = (pan + 1.0) / 2.0;
+        let angle = position * FRAC_PI_2;
+        match self {
+            PanLaw::Linear => (f32::min(1.0, 1.0 - pan), f32::min(1.0, 1.0 + pan)),
+            PanLaw::EqualPower => (angle.cos(), angle.sin()),
+            PanLaw::Minus4_5Db => (
+                (angle.cos() * (1.0 - position)).sqrt(),
+                (angle.sin() * position).sqrt(),
+            ),
+            PanLaw::Minus6Db => (1.0 - position, position),
+        }
+    }
+}
+
+/// An effect that places a mono source in a stereo field.
+///
+/// The output is stereo, with the left and right gains set by the pan
+/// position and the [`PanLaw`](crate::effects::PanLaw).
+///
+/// Because the output has two samples per frame, each read consumes half as
+/// many samples from the source as it produces.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Pan, PanLaw}, generators::VecSource, AudioFormat, AudioSource};
+/// let mono = VecSource::new(vec![1.0; 4], AudioFormat::MONO_CD);
+/// let mut pan = Pan::with_law(mono, 0.0, PanLaw::Minus6Db);
+/// assert_eq!(pan.format(), AudioFormat::STEREO_CD);
+///
+/// let mut samples = vec![0.0; 8];
+/// pan.read(&mut samples);
+/// assert!(samples.iter().all(|&sample| sample == 0.5));
+/// ```
+pub struct Pan<S: AudioSource> {
+    source: S,
+    pan: Param,
+    law: PanLaw,
+}
+
+impl<S: AudioSource> Pan<S> {
+    /// Construct a panner with the [`EqualPower`](PanLaw::EqualPower) law.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The mono source of audio to pan.
+    /// * `pan` -- From -1.0 (left only) to 1.0 (right only), with 0.0 in the center.
+    ///
+    /// # Panics
+    ///
+    /// If `source` is not mono.
+    pub fn new(source: S, pan: f32) -> Self {
+        Pan::with_law(source, pan, PanLaw::default())
+    }
+
+    /// Construct a panner with the given law.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The mono source of audio to pan.
+    /// * `pan` -- From -1.0 (left only) to 1.0 (right only), with 0.0 in the center.
+    /// * `law` -- How the signal is divided between the channels.
+    ///
+    /// # Panics
+    ///
+    /// If `source` is not mono.
+    pub fn with_law(source: S, pan: f32, law: PanLaw) -> Self {
+        assert_eq!(source.format().channels, 1, "Pan requires a mono source.");
+        Pan {
+            source,
+            pan: Param::new(pan.clamp(-1.0, 1.0)),
+            law,
+        }
+    }
+
+    /// Set the pan position, clamped to the range [-1.0, 1.0].
+    pub fn set_pan(&mut self, pan: f32) {
+        self.pan.set(pan.clamp(-1.0, 1.0));
+    }
+
+    pub fn pan(&self) -> f32 {
+        self.pan.get()
+    }
+
+    /// Returns a handle to the pan position, which can be changed from any thread.
+    ///
+    /// Values set through the handle are clamped to the range [-1.0, 1.0] when read.
+    pub fn pan_param(&self) -> Param {
+        self.pan.clone()
+    }
+
+    pub fn set_law(&mut self, law: PanLaw) {
+        self.law = law;
+    }
+
+    pub fn law(&self) -> PanLaw {
+        self.law
+    }
+}
+
+impl<S: AudioSource> AudioSource for Pan<S> {
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            channels: 2,
+            ..self.source.format()
+        }
+    }
+
+    #[instrument(name = "Pan::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let frames = buffer.len() / 2;
+        let result = self.source.read(&mut buffer[..frames]);
+
+        // Spread the mono samples out in place, working backward so that
+        // nothing is overwritten before it's been copied.
+        let (left, right) = self.law.gains(self.pan.get());
+        for i in (0..result.read).rev() {
+            let sample = buffer[i];
+            buffer[2 * i] = sample * left;
+            buffer[2 * i + 1] = sample * right;
+        }
+
+        ReadResult {
+            state: result.state,
+            read: result.read * 2,
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Pan<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Pan<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Pan<S> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Pan<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{core::AudioSource, AudioFormat, Latency, ReadResult, Sample, StreamState, Tail};
+
+use tracing::instrument;
+
+/// An adapter that always reads from its source in multiples of a fixed block size.
+///
+/// Some processors, such as those built on an FFT, need exactly `block` frames
+/// at a time, but drivers read with whatever size suits them. `Rechunk` reads
+/// whole blocks from its source and buffers the leftovers for the next read.
+///
+/// When the source finishes with a partial final block, the remainder is
+/// passed through as-is rather than padded.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Inspect, Rechunk}, generators::Ramp, render, AudioFormat, AudioSource, StreamState};
+/// # use std::time::Duration;
+/// let ramp = || Ramp::finite(0.0, 1.0, Duration::from_millis(100), AudioFormat::STEREO_CD);
+///
+/// // Every read from the source is a whole number of 64-frame blocks,
+/// // except the final partial block.
+/// let source = Inspect::new(ramp(), |buffer, result| {
+///     assert!(buffer.len() % (64 * 2) == 0 || result.state == StreamState::Finished);
+/// });
+/// let mut rechunk = Rechunk::new(source, 64);
+///
+/// let mut samples = Vec::new();
+/// for &size in [100, 6, 256, 130].iter().cycle() {
+///     let mut buffer = vec![0.0; size];
+///     let result = rechunk.read(&mut buffer);
+///     samples.extend_from_slice(&buffer[..result.read]);
+///     if result.state == StreamState::Finished {
+///         break;
+///     }
+/// }
+///
+/// assert_eq!(samples, render(ramp()).0);
+/// ```
+pub struct Rechunk<S: AudioSource> {
+    source: S,
+    block: usize,
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl<S: AudioSource> Rechunk<S> {
+    /// Construct a `Rechunk` adapter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source to read from in whole blocks.
+    /// * `block` -- The number of frames in each block.
+    ///
+    /// # Panics
+    ///
+    /// If `block` is zero.
+    pub fn new(source: S, block: usize) -> Self {
+        assert!(block > 0, "Block size must be at least one frame.");
+        Rechunk {
+            source,
+            block,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    pub fn block(&self) -> usize {
+        self.block
+    }
+
+    /// Copy buffered samples into `buffer`, returning the number copied.
+    fn drain(&mut self, buffer: &mut [Sample]) -> usize {
+        let count = std::cmp::min(buffer.len(), self.buffer.len() - self.position);
+        buffer[..count].copy_from_slice(&self.buffer[self.position..self.position + count]);
+        self.position += count;
+        count
+    }
+}
+
+impl<S: AudioSource> AudioSource for Rechunk<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Rechunk::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let mut written = self.drain(buffer);
+        if written == buffer.len() {
+            return ReadResult::good(written);
+        }
+
+        // Read enough whole blocks to cover the rest of the request.
+        let block = self.source.format().frames_to_samples(self.block);
+        let needed = buffer.len() - written;
+        self.buffer.resize(needed.div_ceil(block) * block, 0.0);
+        self.position = 0;
+
+        let result = self.source.read(&mut self.buffer);
+        self.buffer.truncate(result.read);
+        written += self.drain(&mut buffer[written..]);
+
+        match result.state {
+            StreamState::Finished => {
+                if self.position == self.buffer.len() {
+                    ReadResult::finished(written)
+                } else {
+                    ReadResult::good(written)
+                }
+            }
+            StreamState::Underrun if written < buffer.len() => ReadResult::underrun(written),
+            _ => ReadResult::good(written),
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Rechunk<S> {
+    /// Rechunking buffers data that has already been read, so it adds no latency.
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Rechunk<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
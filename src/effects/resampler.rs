@@ -0,0 +1,142 @@
+use crate::{
+    core::AudioSource,
+    effects::{interpolator::Interpolator, Quality},
+    AudioFormat, Latency, ReadResult, Rewind, Sample, Tail,
+};
+
+use tracing::instrument;
+
+/// An effect that converts a source to a different sample rate.
+///
+/// The position between input frames is tracked across reads, so there are no
+/// clicks at buffer boundaries. The interpolation [`Quality`](crate::effects::Quality)
+/// defaults to [`Linear`](crate::effects::Quality::Linear).
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Quality, Resampler}, generators::SineWave, AudioFormat, AudioSource};
+/// # fn power(samples: &[f32], frequency: f32) -> f32 {
+/// #     let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / 96000.0).cos();
+/// #     let (mut s1, mut s2) = (0.0, 0.0);
+/// #     for sample in samples {
+/// #         let s0 = sample + coefficient * s1 - s2;
+/// #         s2 = s1;
+/// #         s1 = s0;
+/// #     }
+/// #     s1 * s1 + s2 * s2 - coefficient * s1 * s2
+/// # }
+/// let target = AudioFormat { channels: 1, sample_rate: 96000 };
+///
+/// // Upsampling leaves images of each tone reflected around 44.1 kHz. Sum the
+/// // image energy of a sweep of tones relative to the tones themselves.
+/// let images = |quality| {
+///     let (mut tones, mut images) = (0.0, 0.0);
+///     for &frequency in [2000.0, 6000.0, 10000.0, 14000.0, 18000.0].iter() {
+///         let sin = SineWave::with_format(AudioFormat::MONO_CD, 1.0, frequency);
+///         let mut resampler = Resampler::with_quality(sin, target, quality);
+///
+///         let mut samples = vec![0.0; 9600];
+///         resampler.read(&mut samples);
+///         tones += power(&samples[100..], frequency);
+///         images += power(&samples[100..], 44100.0 - frequency);
+///     }
+///     images / tones
+/// };
+///
+/// let linear = images(Quality::Linear);
+/// let cubic = images(Quality::Cubic);
+/// let sinc = images(Quality::Sinc);
+/// assert!(cubic < linear && sinc < 0.01 * cubic);
+/// ```
+pub struct Resampler<S: AudioSource> {
+    source: S,
+    sample_rate: u32,
+    interpolator: Interpolator,
+}
+
+impl<S: AudioSource> Resampler<S> {
+    /// Construct a resampler with [`Linear`](crate::effects::Quality::Linear) interpolation.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `target` -- The format to convert to.
+    ///
+    /// # Panics
+    ///
+    /// If `target` has a different number of channels than `source`.
+    pub fn new(source: S, target: AudioFormat) -> Self {
+        Resampler::with_quality(source, target, Quality::default())
+    }
+
+    /// Construct a resampler with the given interpolation quality.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `target` -- The format to convert to.
+    /// * `quality` -- The interpolation method to use.
+    ///
+    /// # Panics
+    ///
+    /// If `target` has a different number of channels than `source`.
+    pub fn with_quality(source: S, target: AudioFormat, quality: Quality) -> Self {
+        let channels = source.format().channels;
+        assert_eq!(
+            channels, target.channels,
+            "Resampler can't change the number of channels."
+        );
+        Resampler {
+            source,
+            sample_rate: target.sample_rate,
+            interpolator: Interpolator::new(quality, channels as usize),
+        }
+    }
+
+    pub fn set_quality(&mut self, quality: Quality) {
+        self.interpolator.set_quality(quality);
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.interpolator.quality()
+    }
+}
+
+impl<S: AudioSource> AudioSource for Resampler<S> {
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            sample_rate: self.sample_rate,
+            ..self.source.format()
+        }
+    }
+
+    #[instrument(name = "Resampler::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let step = self.source.format().sample_rate as f64 / self.sample_rate as f64;
+        self.interpolator.read(&mut self.source, buffer, step)
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Resampler<S> {
+    fn latency_frames(&self) -> u64 {
+        let ratio = self.sample_rate as f64 / self.source.format().sample_rate as f64;
+        (self.source.latency_frames() as f64 * ratio).round() as u64
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Resampler<S> {
+    fn tail_frames(&self) -> u64 {
+        let ratio = self.sample_rate as f64 / self.source.format().sample_rate as f64;
+        match self.source.tail_frames() {
+            u64::MAX => u64::MAX,
+            tail => (tail as f64 * ratio).round() as u64,
+        }
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Resampler<S> {
+    fn rewind(&mut self) -> bool {
+        self.interpolator.reset();
+        self.source.rewind()
+    }
+}
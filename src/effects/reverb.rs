@@ -0,0 +1,299 @@
+use crate::{
+    core::AudioSource, flush_denormal, AudioFormat, Latency, ReadResult, Rewind, Sample, Seek,
+    StreamState, Tail,
+};
+
+use tracing::instrument;
+
+/// Comb filter lengths in frames at 44.1 kHz, from Freeverb.
+const COMB_TUNING: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+/// Allpass filter lengths in frames at 44.1 kHz, from Freeverb.
+const ALLPASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+/// How many frames longer each channel's delay lines are than the last, to
+/// decorrelate the channels for a wide stereo image.
+const STEREO_SPREAD: usize = 23;
+
+const ALLPASS_FEEDBACK: f32 = 0.5;
+/// Scales the input so the eight combs summed together don't clip.
+const INPUT_GAIN: f32 = 0.015;
+const WET_GAIN: f32 = 3.0;
+
+/// An effect that simulates the reverberation of a room.
+///
+/// This is the Freeverb variant of a Schroeder reverb: each channel feeds
+/// eight parallel [comb filters](crate::effects::Comb), with a low-pass filter
+/// in each feedback path, into four [allpass filters](crate::effects::Allpass)
+/// in series. The delay lines are sized from the source's sample rate, and are
+/// slightly longer on each channel so stereo sounds wide.
+///
+/// The output is `dry * (1.0 - wet) + reverberated * wet`. Once the source
+/// finishes, the reverb keeps ringing out as if the source were followed by
+/// silence, for [`tail_frames`](crate::Tail::tail_frames).
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Reverb, generators::VecSource, AudioFormat, AudioSource};
+/// let mut impulse = vec![0.0; 44100];
+/// impulse[0] = 1.0;
+/// let source = VecSource::new(impulse, AudioFormat::MONO_CD);
+/// let mut hall = Reverb::new(source, 0.9, 0.2, 1.0);
+///
+/// let mut samples = vec![0.0; 44100];
+/// hall.read(&mut samples);
+///
+/// // Nothing comes out until the shortest comb and allpasses have delayed the impulse...
+/// assert!(samples[..1116].iter().all(|&sample| sample == 0.0));
+/// // ...then echoes build up densely, and gradually die away.
+/// let energy = |s: &[f32]| s.iter().map(|s| s * s).sum::<f32>();
+/// assert!(samples[4410..8820].iter().filter(|&&sample| sample != 0.0).count() > 4000);
+/// assert!(energy(&samples[35280..]) < 0.5 * energy(&samples[4410..8820]));
+/// ```
+///
+/// With no wet signal, the source passes through untouched:
+/// ```
+/// # use timbre::{effects::Reverb, generators::SineWave, AudioSource};
+/// let mut dry = SineWave::new(1.0, 440.0);
+/// let mut reverb = Reverb::new(SineWave::new(1.0, 440.0), 0.5, 0.5, 0.0);
+///
+/// let mut expected = vec![0.0; 4096];
+/// let mut samples = vec![0.0; 4096];
+/// dry.read(&mut expected);
+/// reverb.read(&mut samples);
+/// assert_eq!(samples, expected);
+/// ```
+pub struct Reverb<S: AudioSource> {
+    source: S,
+    room_size: f32,
+    damping: f32,
+    wet: f32,
+    format: AudioFormat,
+    channels: Vec<Tank>,
+    finished: bool,
+}
+
+impl<S: AudioSource> Reverb<S> {
+    /// Construct a new `Reverb` effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `room_size` -- How long the reverb rings, from 0.0 (a small room) to 1.0 (a huge hall).
+    /// * `damping` -- How quickly high frequencies die away, from 0.0 (bright) to 1.0 (dull).
+    /// * `wet` -- How much of the reverberated signal to output, from 0.0 (dry only) to 1.0.
+    pub fn new(source: S, room_size: f32, damping: f32, wet: f32) -> Self {
+        let format = source.format();
+        Reverb {
+            source,
+            room_size: room_size.clamp(0.0, 1.0),
+            damping: damping.clamp(0.0, 1.0),
+            wet: wet.clamp(0.0, 1.0),
+            format,
+            channels: tanks(format),
+            finished: false,
+        }
+    }
+
+    /// Set how long the reverb rings, clamped to the range [0.0, 1.0].
+    pub fn set_room_size(&mut self, room_size: f32) {
+        self.room_size = room_size.clamp(0.0, 1.0);
+    }
+
+    pub fn room_size(&self) -> f32 {
+        self.room_size
+    }
+
+    /// Set how quickly high frequencies die away, clamped to the range [0.0, 1.0].
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.clamp(0.0, 1.0);
+    }
+
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Set how much of the reverberated signal to output, clamped to the range [0.0, 1.0].
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    /// The gain applied on each pass through a comb filter.
+    fn feedback(&self) -> f32 {
+        0.7 + 0.28 * self.room_size
+    }
+
+    fn clear(&mut self) {
+        self.channels = tanks(self.format);
+        self.finished = false;
+    }
+}
+
+impl<S: AudioSource> AudioSource for Reverb<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Reverb::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        // The delay lines depend on the format, so start afresh if it changes.
+        let format = self.source.format();
+        if format != self.format {
+            self.format = format;
+            self.clear();
+        }
+
+        let status = if self.finished {
+            let written = buffer.len() - buffer.len() % format.channels as usize;
+            buffer[..written].iter_mut().for_each(|s| *s = 0.0);
+            ReadResult::finished(written)
+        } else {
+            self.source.read(buffer)
+        };
+        self.finished = status.state == StreamState::Finished;
+
+        let feedback = self.feedback();
+        let damping = 0.4 * self.damping;
+        let (dry_gain, wet_gain) = (1.0 - self.wet, WET_GAIN * self.wet);
+        let channels = self.channels.len();
+        for frame in buffer[..status.read].chunks_exact_mut(channels) {
+            for (sample, tank) in frame.iter_mut().zip(self.channels.iter_mut()) {
+                let reverberated = tank.process(*sample, feedback, damping);
+                *sample = *sample * dry_gain + reverberated * wet_gain;
+            }
+        }
+
+        status
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Reverb<S> {
+    /// `Reverb` doesn't buffer its input, so it reports only the source's latency.
+    ///
+    /// With `wet` at 1.0 there's no direct signal, so nothing comes out until
+    /// the shortest comb filter has delayed the input, about 25 ms. That gap is
+    /// the sound of the room, not a lag to compensate for.
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Reverb<S> {
+    /// The reverb rings until the longest comb filter has decayed below -60 dB.
+    fn tail_frames(&self) -> u64 {
+        if self.wet == 0.0 {
+            return self.source.tail_frames();
+        }
+
+        let longest = self.channels.iter().flat_map(|tank| &tank.combs);
+        let longest = longest.map(|comb| comb.line.len()).max().unwrap_or(0) as u64;
+        let repetitions = (0.001f32.ln() / self.feedback().ln()).ceil() as u64;
+        (repetitions * longest).saturating_add(self.source.tail_frames())
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Reverb<S> {
+    fn rewind(&mut self) -> bool {
+        self.clear();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Reverb<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.clear();
+        self.source.seek_frame(frame)
+    }
+}
+
+/// The filters that reverberate one channel.
+struct Tank {
+    combs: Vec<DampedComb>,
+    allpasses: Vec<DelayLine>,
+}
+
+impl Tank {
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let input = input * INPUT_GAIN;
+        let mut output = self
+            .combs
+            .iter_mut()
+            .map(|comb| comb.process(input, feedback, damping))
+            .sum::<f32>();
+        for allpass in self.allpasses.iter_mut() {
+            let delayed = allpass.get();
+            allpass.push(flush_denormal(output + delayed * ALLPASS_FEEDBACK));
+            output = delayed - output;
+        }
+        output
+    }
+}
+
+/// A feedback comb filter with a one-pole low-pass filter in its feedback path.
+struct DampedComb {
+    line: DelayLine,
+    filtered: f32,
+}
+
+impl DampedComb {
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.line.get();
+        self.filtered = flush_denormal(output * (1.0 - damping) + self.filtered * damping);
+        self.line.push(input + self.filtered * feedback);
+        output
+    }
+}
+
+struct DelayLine {
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl DelayLine {
+    fn new(length: usize) -> Self {
+        DelayLine {
+            buffer: vec![0.0; length.max(1)],
+            position: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the sample pushed `len()` samples ago.
+    fn get(&self) -> f32 {
+        self.buffer[self.position]
+    }
+
+    fn push(&mut self, sample: f32) {
+        self.buffer[self.position] = sample;
+        self.position = (self.position + 1) % self.buffer.len();
+    }
+}
+
+/// Build a tank for each channel of `format`, scaling the delay lines to its sample rate.
+fn tanks(format: AudioFormat) -> Vec<Tank> {
+    let scale =
+        |frames: usize| (frames as f64 * format.sample_rate as f64 / 44100.0).round() as usize;
+    (0..format.channels as usize)
+        .map(|channel| {
+            let spread = channel * STEREO_SPREAD;
+            Tank {
+                combs: COMB_TUNING
+                    .iter()
+                    .map(|&frames| DampedComb {
+                        line: DelayLine::new(scale(frames + spread)),
+                        filtered: 0.0,
+                    })
+                    .collect(),
+                allpasses: ALLPASS_TUNING
+                    .iter()
+                    .map(|&frames| DelayLine::new(scale(frames + spread)))
+                    .collect(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,94 @@
+use crate::{
+    core::AudioSource,
+    effects::biquad::{filter, Coefficients},
+    AudioFormat, Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+/// Which end of the spectrum a [`Shelf`] changes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ShelfKind {
+    Low,
+    High,
+}
+
+/// A shelving filter at a fixed frequency, shared by
+/// [`BassControl`](crate::effects::BassControl) and
+/// [`TrebleControl`](crate::effects::TrebleControl).
+pub(crate) struct Shelf<S: AudioSource> {
+    source: S,
+    kind: ShelfKind,
+    frequency: f32,
+    pub gain_db: f32,
+    state: Vec<[f32; 2]>,
+}
+
+impl<S: AudioSource> Shelf<S> {
+    pub fn new(source: S, kind: ShelfKind, frequency: f32, gain_db: f32) -> Self {
+        let channels = source.format().channels as usize;
+        Shelf {
+            source,
+            kind,
+            frequency,
+            gain_db,
+            state: vec![[0.0; 2]; channels],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state.iter_mut().for_each(|z| *z = [0.0; 2]);
+    }
+}
+
+impl<S: AudioSource> AudioSource for Shelf<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.source.format();
+        let result = self.source.read(buffer);
+        let written = result.read;
+        if written == 0 {
+            return result;
+        }
+        self.state.resize(format.channels as usize, [0.0; 2]);
+
+        let coefficients = match self.kind {
+            ShelfKind::Low => {
+                Coefficients::low_shelf(format.sample_rate, self.frequency, self.gain_db)
+            }
+            ShelfKind::High => {
+                Coefficients::high_shelf(format.sample_rate, self.frequency, self.gain_db)
+            }
+        };
+        filter(&mut buffer[..written], &mut self.state, &coefficients);
+
+        result
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Shelf<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Shelf<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Shelf<S> {
+    fn rewind(&mut self) -> bool {
+        self.reset();
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for Shelf<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.reset();
+        self.source.seek_frame(frame)
+    }
+}
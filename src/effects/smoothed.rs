@@ -0,0 +1,68 @@
+/// A parameter that ramps linearly to new values instead of jumping.
+///
+/// Abruptly changing a filter coefficient makes its output jump, which is
+/// heard as a click. Effects advance the ramp once per block and interpolate
+/// between the returned values for each frame in the block.
+pub(crate) struct Smoothed {
+    current: f32,
+    target: f32,
+    rate: f32,
+}
+
+impl Smoothed {
+    /// The time taken to ramp to a new value, in seconds.
+    const RAMP_TIME: f32 = 0.005;
+
+    pub fn new(value: f32) -> Self {
+        Smoothed {
+            current: value,
+            target: value,
+            rate: 0.0,
+        }
+    }
+
+    /// Start ramping toward `target`.
+    pub fn set(&mut self, target: f32) {
+        self.target = target;
+        self.rate = (target - self.current).abs() / Smoothed::RAMP_TIME;
+    }
+
+    /// Jump to the target immediately, e.g. when the effect's history is cleared.
+    pub fn finish(&mut self) {
+        self.current = self.target;
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// Advance the ramp by `seconds`.
+    ///
+    /// # Returns
+    ///
+    /// The values at the start and end of the elapsed time.
+    pub fn advance(&mut self, seconds: f32) -> (f32, f32) {
+        let start = self.current;
+        let remaining = self.target - self.current;
+        let step = self.rate * seconds;
+        if remaining.abs() <= step {
+            self.current = self.target;
+        } else {
+            self.current += step.copysign(remaining);
+        }
+        (start, self.current)
+    }
+}
+
+/// Interpolate between the values returned by [`Smoothed::advance`] for `frame` of `frames`.
+pub(crate) fn ramp((start, end): (f32, f32), frame: usize, frames: usize) -> f32 {
+    if start == end {
+        start
+    } else {
+        start + (end - start) * frame as f32 / frames as f32
+    }
+}
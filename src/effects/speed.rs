@@ -0,0 +1,124 @@
+use crate::{
+    core::AudioSource,
+    effects::{interpolator::Interpolator, Quality},
+    AudioFormat, Latency, ReadResult, Rewind, Sample, Tail,
+};
+
+use tracing::instrument;
+
+/// An effect that plays a source faster or slower, like changing tape speed.
+///
+/// Both the tempo and the pitch change. The speed may be changed while
+/// playing without clicks. The interpolation [`Quality`](crate::effects::Quality)
+/// defaults to [`Linear`](crate::effects::Quality::Linear); when speeding up,
+/// [`Sinc`](crate::effects::Quality::Sinc) also filters out frequencies that
+/// would alias.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{Quality, Speed}, generators::Ramp, render, AudioFormat};
+/// # use std::time::Duration;
+/// let ramp = Ramp::finite(0.0, 1.0, Duration::from_secs(1), AudioFormat::MONO_CD);
+/// let speed = Speed::with_quality(ramp, 2.0, Quality::Cubic);
+///
+/// // Twice as fast, so half as long.
+/// let (samples, _) = render(speed);
+/// assert_eq!(samples.len(), 22050);
+/// ```
+pub struct Speed<S: AudioSource> {
+    source: S,
+    speed: f64,
+    interpolator: Interpolator,
+}
+
+impl<S: AudioSource> Speed<S> {
+    /// Construct a speed effect with [`Linear`](crate::effects::Quality::Linear) interpolation.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `speed` -- The playback rate; 2.0 is twice as fast, 0.5 is half as fast.
+    ///
+    /// # Panics
+    ///
+    /// If `speed` is not positive.
+    pub fn new(source: S, speed: f32) -> Self {
+        Speed::with_quality(source, speed, Quality::default())
+    }
+
+    /// Construct a speed effect with the given interpolation quality.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `speed` -- The playback rate; 2.0 is twice as fast, 0.5 is half as fast.
+    /// * `quality` -- The interpolation method to use.
+    ///
+    /// # Panics
+    ///
+    /// If `speed` is not positive.
+    pub fn with_quality(source: S, speed: f32, quality: Quality) -> Self {
+        assert!(speed > 0.0, "Speed must be positive.");
+        let channels = source.format().channels as usize;
+        Speed {
+            source,
+            speed: speed as f64,
+            interpolator: Interpolator::new(quality, channels),
+        }
+    }
+
+    /// Set the playback rate.
+    ///
+    /// # Panics
+    ///
+    /// If `speed` is not positive.
+    pub fn set_speed(&mut self, speed: f32) {
+        assert!(speed > 0.0, "Speed must be positive.");
+        self.speed = speed as f64;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed as f32
+    }
+
+    pub fn set_quality(&mut self, quality: Quality) {
+        self.interpolator.set_quality(quality);
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.interpolator.quality()
+    }
+}
+
+impl<S: AudioSource> AudioSource for Speed<S> {
+    fn format(&self) -> AudioFormat {
+        self.source.format()
+    }
+
+    #[instrument(name = "Speed::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.interpolator.read(&mut self.source, buffer, self.speed)
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for Speed<S> {
+    fn latency_frames(&self) -> u64 {
+        (self.source.latency_frames() as f64 / self.speed).round() as u64
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for Speed<S> {
+    fn tail_frames(&self) -> u64 {
+        match self.source.tail_frames() {
+            u64::MAX => u64::MAX,
+            tail => (tail as f64 / self.speed).round() as u64,
+        }
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for Speed<S> {
+    fn rewind(&mut self) -> bool {
+        self.interpolator.reset();
+        self.source.rewind()
+    }
+}
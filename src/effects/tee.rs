@@ -0,0 +1,169 @@
+use crate::{
+    core::{AudioSource, SharedAudioSource},
+    AudioFormat, ReadResult, Sample, StreamState,
+};
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use slotmap::{DefaultKey, DenseSlotMap};
+use tracing::instrument;
+
+/// An adapter that splits one [`AudioSource`](crate::AudioSource) into several identical ones.
+///
+/// Each call to [`branch`](Tee::branch) creates a new consumer of the upstream
+/// source. The upstream source is read once, and every branch sees the same
+/// samples regardless of how its reads are sized or interleaved with the others.
+///
+/// Samples are buffered until every branch has read them, so a branch that
+/// stops being read will cause the buffer to grow. Drop branches that are no
+/// longer needed.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::Tee, generators::SineWave, AudioFormat, AudioSource, ReadResult};
+/// let tee = Tee::new(SineWave::new(1.0, 440.0));
+/// let mut a = tee.branch();
+/// let mut b = tee.branch();
+///
+/// let mut samples_a = vec![0.0; 256];
+/// let mut samples_b = vec![0.0; 256];
+/// a.read(&mut samples_a[..100]);
+/// a.read(&mut samples_a[100..]);
+/// b.read(&mut samples_b);
+///
+/// assert_eq!(samples_a, samples_b);
+///
+/// // A read that ends partway through a frame gets the whole frames that fit.
+/// let stereo = Tee::new(SineWave::with_format(AudioFormat::STEREO_CD, 1.0, 440.0));
+/// let result = stereo.branch().lock().unwrap().read(&mut samples_a[..3]);
+/// assert_eq!(result, ReadResult::good(2));
+/// ```
+pub struct Tee<S: AudioSource + Send + 'static> {
+    state: Arc<Mutex<TeeState<S>>>,
+}
+
+struct TeeState<S: AudioSource> {
+    source: S,
+    buffer: VecDeque<f32>,
+    scratch: Vec<f32>,
+    // Absolute index of the first sample in `buffer`.
+    offset: u64,
+    cursors: DenseSlotMap<DefaultKey, u64>,
+    finished: bool,
+}
+
+struct Branch<S: AudioSource> {
+    format: AudioFormat,
+    key: DefaultKey,
+    state: Arc<Mutex<TeeState<S>>>,
+}
+
+impl<S: AudioSource + Send + 'static> Tee<S> {
+    /// Construct a `Tee` that shares the given source between branches.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The upstream source to share.
+    pub fn new(source: S) -> Self {
+        Tee {
+            state: Arc::new(Mutex::new(TeeState {
+                source,
+                buffer: VecDeque::new(),
+                scratch: Vec::new(),
+                offset: 0,
+                cursors: DenseSlotMap::new(),
+                finished: false,
+            })),
+        }
+    }
+
+    /// Create a new branch that reads from the upstream source.
+    ///
+    /// A branch starts at the oldest sample that another branch hasn't read
+    /// yet, or at the current position of the upstream source if there is none.
+    pub fn branch(&self) -> SharedAudioSource {
+        let mut state = self.state.lock().unwrap();
+        let start = state.offset;
+        let key = state.cursors.insert(start);
+        let format = state.source.format();
+
+        Arc::new(Mutex::new(Branch {
+            format,
+            key,
+            state: Arc::clone(&self.state),
+        }))
+    }
+}
+
+impl<S: AudioSource> TeeState<S> {
+    fn fill(&mut self, end: u64) {
+        let available = self.offset + self.buffer.len() as u64;
+        if self.finished || end <= available {
+            return;
+        }
+
+        let needed = (end - available) as usize;
+        self.scratch.resize(needed, 0.0);
+        let result = self.source.read(&mut self.scratch);
+        self.buffer
+            .extend(self.scratch[..result.read].iter().cloned());
+        self.finished = result.state == StreamState::Finished;
+    }
+
+    fn trim(&mut self) {
+        let oldest = self
+            .cursors
+            .values()
+            .cloned()
+            .min()
+            .unwrap_or(self.offset + self.buffer.len() as u64);
+        let count = (oldest - self.offset) as usize;
+        self.buffer.drain(..count);
+        self.offset = oldest;
+    }
+}
+
+impl<S: AudioSource> AudioSource for Branch<S> {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "Tee::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let mut state = self.state.lock().unwrap();
+        let cursor = state.cursors[self.key];
+        let channels = self.format.channels as usize;
+        let whole = buffer.len() - buffer.len() % channels;
+        state.fill(cursor + whole as u64);
+
+        let start = (cursor - state.offset) as usize;
+        let available = std::cmp::min(whole, state.buffer.len() - start);
+        let read = available - available % channels;
+        state
+            .buffer
+            .range(start..start + read)
+            .zip(buffer.iter_mut())
+            .for_each(|(s, b)| *b = *s);
+
+        state.cursors[self.key] = cursor + read as u64;
+        state.trim();
+
+        if read == whole {
+            ReadResult::good(read)
+        } else if state.finished {
+            ReadResult::finished(read)
+        } else {
+            ReadResult::underrun(read)
+        }
+    }
+}
+
+impl<S: AudioSource> Drop for Branch<S> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.cursors.remove(self.key);
+            state.trim();
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{
+    core::AudioSource, effects::downmix::stereo_gains, AudioFormat, ChannelLayout, Latency,
+    ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// An adapter that downmixes a source with any number of channels to mono.
+///
+/// Each output sample is a weighted sum of the channels in one input frame,
+/// using half the gains of a stereo [`Downmix`](crate::effects::Downmix), so
+/// for stereo it is `(left + right) / 2`. Averaging rather than summing keeps
+/// full-scale stereo from clipping. For surround layouts, each front channel
+/// is weighted 0.5, the center channel 0.707 (+3 dB relative to a front,
+/// since the stereo downmix feeds it to both sides), and the surround
+/// channels 0.354 (-3 dB), while the LFE channel is dropped. If any
+/// channel's position is unknown, the channels are averaged.
+///
+/// Because the output has one sample per frame, each read consumes
+/// `channels` times as many samples from the source as it produces: reading
+/// 1024 samples from a `ToMono` wrapping a stereo source reads 2048 samples
+/// from the source. [`ReadResult::read`](crate::ReadResult) counts the mono
+/// samples produced.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::ToMono, generators::VecSource, AudioFormat, AudioSource};
+/// let stereo = VecSource::new(vec![1.0, 0.0, 0.5, 0.25, -1.0, 1.0], AudioFormat::STEREO_CD);
+/// let mut mono = ToMono::new(stereo);
+/// assert_eq!(mono.format(), AudioFormat::MONO_CD);
+///
+/// let mut samples = vec![0.0; 3];
+/// let result = mono.read(&mut samples);
+/// assert_eq!(result.read, 3);
+/// assert_eq!(samples, [0.5, 0.375, 0.0]);
+/// ```
+///
+/// Reading 1024 mono samples consumes 2048 stereo samples:
+/// ```
+/// # use timbre::{effects::{Inspect, ToMono}, generators::SineWave, AudioSource};
+/// let mut consumed = 0;
+/// let mut samples = vec![0.0; 1024];
+/// {
+///     let stereo = Inspect::new(SineWave::new(1.0, 440.0), |buffer, _| consumed += buffer.len());
+///     let mut mono = ToMono::new(stereo);
+///     assert_eq!(mono.read(&mut samples).read, 1024);
+/// }
+/// assert_eq!(consumed, 2048);
+/// ```
+///
+/// 5.1 surround uses standard downmix coefficients:
+/// ```
+/// # use timbre::{effects::ToMono, generators::VecSource, AudioFormat, AudioSource};
+/// # use std::f32::consts::FRAC_1_SQRT_2;
+/// // Left, right, center, LFE, back left, back right.
+/// let format = AudioFormat { channels: 6, sample_rate: 48000 };
+/// let surround = VecSource::new(vec![0.2, 0.2, 0.2, 1.0, 0.2, 0.2], format);
+/// let mut mono = ToMono::new(surround);
+///
+/// let mut samples = vec![0.0; 1];
+/// mono.read(&mut samples);
+/// let expected = 0.5 * (0.2 + 0.2) + FRAC_1_SQRT_2 * 0.2 + 0.5 * FRAC_1_SQRT_2 * (0.2 + 0.2);
+/// assert!((samples[0] - expected).abs() < 1e-6);
+/// ```
+pub struct ToMono<S: AudioSource> {
+    source: S,
+    gains: Vec<f32>,
+    buffer: Vec<Sample>,
+}
+
+impl<S: AudioSource> ToMono<S> {
+    /// Construct a mono downmix.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to downmix.
+    pub fn new(source: S) -> Self {
+        let layout = ChannelLayout::from_channels(source.format().channels);
+        ToMono::with_layout(source, layout)
+    }
+
+    /// Construct a mono downmix of a source with the given channel layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio to downmix.
+    /// * `layout` -- The speaker each of the source's channels is meant for.
+    ///
+    /// # Panics
+    ///
+    /// If `layout` doesn't have the same number of channels as `source`.
+    pub fn with_layout(source: S, layout: ChannelLayout) -> Self {
+        assert_eq!(
+            layout.channels(),
+            source.format().channels as usize,
+            "The channel layout must match the source's channels."
+        );
+        let gains = if layout.is_positioned() {
+            let gains = layout.speakers().iter().map(|&s| stereo_gains(s));
+            gains.map(|(left, right)| (left + right) / 2.0).collect()
+        } else {
+            vec![1.0 / layout.channels() as f32; layout.channels()]
+        };
+        ToMono {
+            source,
+            gains,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<S: AudioSource> AudioSource for ToMono<S> {
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            channels: 1,
+            ..self.source.format()
+        }
+    }
+
+    #[instrument(name = "ToMono::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.source.format().channels as usize;
+        if channels == 1 {
+            return self.source.read(buffer);
+        }
+
+        self.buffer.resize(buffer.len() * channels, 0.0);
+        let result = self.source.read(&mut self.buffer);
+
+        let frames = self.buffer[..result.read].chunks_exact(channels);
+        for (sample, frame) in buffer.iter_mut().zip(frames) {
+            *sample = frame
+                .iter()
+                .zip(self.gains.iter())
+                .map(|(s, g)| s * g)
+                .sum();
+        }
+
+        ReadResult {
+            state: result.state,
+            read: result.read / channels,
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for ToMono<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for ToMono<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for ToMono<S> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for ToMono<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{core::AudioSource, AudioFormat, Latency, ReadResult, Rewind, Sample, Seek, Tail};
+
+use tracing::instrument;
+
+/// An adapter that plays a mono source on both channels of a stereo stream.
+///
+/// Each input sample is duplicated, unchanged, to the left and right channels.
+///
+/// Because the output has two samples per frame, each read consumes half as
+/// many samples from the source as it produces: reading 2048 samples from a
+/// `ToStereo` reads 1024 samples from the source.
+/// [`ReadResult::read`](crate::ReadResult) counts the stereo samples produced.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::ToStereo, generators::{SineWave, VecSource}, AudioFormat, AudioSource};
+/// let mono = VecSource::new(vec![0.1, 0.2, 0.3], AudioFormat::MONO_CD);
+/// let mut stereo = ToStereo::new(mono);
+/// assert_eq!(stereo.format(), AudioFormat::STEREO_CD);
+///
+/// let mut samples = vec![0.0; 6];
+/// assert_eq!(stereo.read(&mut samples).read, 6);
+/// assert_eq!(samples, [0.1, 0.1, 0.2, 0.2, 0.3, 0.3]);
+///
+/// // 1024 frames of mono become 2048 samples of stereo.
+/// let mut expected = vec![0.0; 1024];
+/// SineWave::with_format(AudioFormat::MONO_CD, 1.0, 440.0).read(&mut expected);
+///
+/// let mut stereo = ToStereo::new(SineWave::with_format(AudioFormat::MONO_CD, 1.0, 440.0));
+/// let mut samples = vec![0.0; 2048];
+/// assert_eq!(stereo.read(&mut samples).read, 2048);
+/// for (frame, sample) in samples.chunks_exact(2).zip(expected.iter()) {
+///     assert_eq!(frame, [*sample, *sample]);
+/// }
+/// ```
+pub struct ToStereo<S: AudioSource> {
+    source: S,
+}
+
+impl<S: AudioSource> ToStereo<S> {
+    /// Construct a stereo upmix.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The mono source of audio to upmix.
+    ///
+    /// # Panics
+    ///
+    /// If `source` is not mono.
+    pub fn new(source: S) -> Self {
+        assert_eq!(
+            source.format().channels,
+            1,
+            "ToStereo requires a mono source."
+        );
+        ToStereo { source }
+    }
+}
+
+impl<S: AudioSource> AudioSource for ToStereo<S> {
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            channels: 2,
+            ..self.source.format()
+        }
+    }
+
+    #[instrument(name = "ToStereo::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let frames = buffer.len() / 2;
+        let result = self.source.read(&mut buffer[..frames]);
+
+        // Spread the mono samples out in place, working backward so that
+        // nothing is overwritten before it's been copied.
+        for i in (0..result.read).rev() {
+            let sample = buffer[i];
+            buffer[2 * i] = sample;
+            buffer[2 * i + 1] = sample;
+        }
+
+        ReadResult {
+            state: result.state,
+            read: result.read * 2,
+        }
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for ToStereo<S> {
+    fn latency_frames(&self) -> u64 {
+        self.source.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for ToStereo<S> {
+    fn tail_frames(&self) -> u64 {
+        self.source.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for ToStereo<S> {
+    fn rewind(&mut self) -> bool {
+        self.source.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for ToStereo<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.source.seek_frame(frame)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    core::AudioSource,
+    effects::shelf::{Shelf, ShelfKind},
+    Latency, ReadResult, Rewind, Sample, Seek, Tail,
+};
+
+use tracing::instrument;
+
+/// An effect that boosts or cuts high frequencies, like the treble knob on a stereo.
+///
+/// `TrebleControl` is a high-shelf filter at a fixed 10 kHz. Frequencies above the
+/// shelf have their volume changed by the given gain, while lower frequencies
+/// pass through unchanged.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SineWave, effects::TrebleControl, AudioFormat, AudioSource};
+/// let peak = |source: &mut dyn AudioSource| {
+///     let mut samples = vec![0.0; 2 * 44100];
+///     source.read(&mut samples);
+///     samples[44100..].iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+/// };
+///
+/// // 0 dB leaves everything alone, in mono as well as stereo.
+/// let mono = SineWave::with_format(AudioFormat::MONO_CD, 1.0, 15000.0);
+/// let mut flat = TrebleControl::new(mono, 0.0);
+/// assert!((peak(&mut flat) - 1.0).abs() < 0.01);
+///
+/// // +6 dB roughly doubles the treble...
+/// let mut high = TrebleControl::new(SineWave::new(1.0, 15000.0), 6.0);
+/// assert!(peak(&mut high) > 1.8);
+///
+/// // ...without affecting the bass.
+/// let mut low = TrebleControl::new(SineWave::new(1.0, 200.0), 6.0);
+/// assert!((peak(&mut low) - 1.0).abs() < 0.05);
+/// ```
+pub struct TrebleControl<S: AudioSource> {
+    shelf: Shelf<S>,
+}
+
+impl<S: AudioSource> TrebleControl<S> {
+    /// The frequency of the shelf, in Hz.
+    const FREQUENCY: f32 = 10000.0;
+
+    /// Construct a treble control.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` -- The source of audio for this effect.
+    /// * `gain_db` -- The gain to apply to high frequencies, in decibels.
+    pub fn new(source: S, gain_db: f32) -> Self {
+        TrebleControl {
+            shelf: Shelf::new(source, ShelfKind::High, Self::FREQUENCY, gain_db),
+        }
+    }
+
+    /// Set the gain to apply to high frequencies, in decibels.
+    pub fn set_gain_db(&mut self, gain_db: f32) {
+        self.shelf.gain_db = gain_db;
+    }
+
+    pub fn gain_db(&self) -> f32 {
+        self.shelf.gain_db
+    }
+}
+
+impl<S: AudioSource> AudioSource for TrebleControl<S> {
+    fn format(&self) -> crate::AudioFormat {
+        self.shelf.format()
+    }
+
+    #[instrument(name = "TrebleControl::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.shelf.read(buffer)
+    }
+}
+
+impl<S: AudioSource + Latency> Latency for TrebleControl<S> {
+    fn latency_frames(&self) -> u64 {
+        self.shelf.latency_frames()
+    }
+}
+
+impl<S: AudioSource + Tail> Tail for TrebleControl<S> {
+    fn tail_frames(&self) -> u64 {
+        self.shelf.tail_frames()
+    }
+}
+
+impl<S: AudioSource + Rewind> Rewind for TrebleControl<S> {
+    fn rewind(&mut self) -> bool {
+        self.shelf.rewind()
+    }
+}
+
+impl<S: AudioSource + Seek> Seek for TrebleControl<S> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        self.shelf.seek_frame(frame)
+    }
+}
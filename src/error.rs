@@ -1,25 +1,79 @@
 /// Unified error type.
+///
+/// # Examples
+/// ```
+/// # use timbre::{decoders::WavDecoder, Error};
+/// let bytes = std::fs::read("./assets/tagged-mono-i16.wav").unwrap();
+///
+/// // Cut the file off partway through its audio.
+/// match WavDecoder::new(&bytes[..bytes.len() / 2]) {
+///     Err(Error::Truncated) => {}
+///     _ => panic!("Expected a truncated file."),
+/// }
+/// match WavDecoder::new(&b"Not a WAV file"[..]) {
+///     Err(Error::DecodeError(_)) => {}
+///     _ => panic!("Expected a decode error."),
+/// }
+/// ```
 #[derive(Debug)]
 pub enum Error {
     /// Wraps a [`std::io::Error`](std::io::Error), such as file not found, etc.
     IoError(std::io::Error),
     /// Wraps an error from SDL2.
     SdlError(String),
+    /// The data being decoded is corrupt or not in the expected container format.
+    DecodeError(String),
+    /// The data is valid, but uses a format that isn't supported, such as an
+    /// unusual bit depth.
+    UnsupportedFormat(String),
+    /// The data ends before its headers say it should, e.g. a partially
+    /// downloaded or copied file.
+    Truncated,
+    /// No device matched the request, e.g. there's no loopback capture device.
+    DeviceNotFound(String),
 }
 
 impl Error {
+    #[cfg(feature = "sdl2")]
     pub(crate) fn from_sdl(err: String) -> Error {
         Error::SdlError(err)
     }
+
+    #[cfg(feature = "symphonia")]
+    pub(crate) fn from_symphonia(error: symphonia::core::errors::Error) -> Error {
+        use symphonia::core::errors::Error as SymphoniaError;
+        match error {
+            SymphoniaError::IoError(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Error::Truncated
+            }
+            SymphoniaError::IoError(error) => Error::IoError(error),
+            SymphoniaError::Unsupported(feature) => Error::UnsupportedFormat(feature.to_string()),
+            error => Error::DecodeError(error.to_string()),
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{:?}", self))
+        match self {
+            Error::IoError(error) => write!(f, "I/O error: {}", error),
+            Error::SdlError(message) => write!(f, "SDL error: {}", message),
+            Error::DecodeError(message) => write!(f, "Decode error: {}", message),
+            Error::UnsupportedFormat(message) => write!(f, "Unsupported format: {}", message),
+            Error::Truncated => write!(f, "The data ends unexpectedly; it may be truncated."),
+            Error::DeviceNotFound(message) => write!(f, "Device not found: {}", message),
+        }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IoError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
 
 impl From<std::io::Error> for Error {
     fn from(error: std::io::Error) -> Self {
@@ -1,3 +1,31 @@
 //! [`AudioSource`](crate::AudioSource) implementations that generate their own sounds.
+mod blep;
+mod channel_source;
+mod dtmf;
+mod fm;
+mod iter_source;
+mod metronome;
+mod noise;
+mod pluck;
+mod ramp;
+mod rng;
+mod sawtooth;
+mod square;
+mod synth_voice;
 mod tone;
+mod triangle;
+mod vec_source;
+pub use channel_source::ChannelSource;
+pub use dtmf::Dtmf;
+pub use fm::FmOsc;
+pub use iter_source::IterSource;
+pub use metronome::Metronome;
+pub use noise::{BrownNoise, PinkNoise, WhiteNoise};
+pub use pluck::PluckedString;
+pub use ramp::Ramp;
+pub use sawtooth::SawtoothWave;
+pub use square::SquareWave;
+pub use synth_voice::{Adsr, Oscillator, SynthVoice};
 pub use tone::SineWave;
+pub use triangle::TriangleWave;
+pub use vec_source::{SliceSource, VecSource};
@@ -0,0 +1,21 @@
+/// The PolyBLEP correction for a unit step at phase 0.
+///
+/// A naive oscillator jumps instantly at its discontinuities, which contains
+/// energy at every frequency and aliases. Adding this residual smooths the
+/// samples either side of the jump into a bandlimited step.
+///
+/// # Arguments
+///
+/// * `phase` -- The oscillator's phase, normalized to [0, 1).
+/// * `increment` -- The change in phase per sample, i.e. frequency / sample rate.
+pub(crate) fn poly_blep(phase: f32, increment: f32) -> f32 {
+    if phase < increment {
+        let x = phase / increment;
+        2.0 * x - x * x - 1.0
+    } else if phase > 1.0 - increment {
+        let x = (phase - 1.0) / increment;
+        x * x + 2.0 * x + 1.0
+    } else {
+        0.0
+    }
+}
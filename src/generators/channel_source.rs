@@ -0,0 +1,122 @@
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Sample, Tail};
+use std::collections::VecDeque;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that plays samples sent from another thread.
+///
+/// Another subsystem, such as a network stream or an emulator, sends chunks of
+/// interleaved samples through a [`std::sync::mpsc`] channel, and `ChannelSource`
+/// plays them in order. Chunks can be any size; whatever doesn't fit in one
+/// read is kept for the next. Reading never blocks: if not enough samples have
+/// been sent, the read is an [`Underrun`](crate::StreamState::Underrun), and
+/// once every sender has been dropped and everything sent has been played, it's
+/// [`Finished`](crate::StreamState::Finished).
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::ChannelSource, AudioFormat, AudioSource, StreamState};
+/// let (sender, receiver) = std::sync::mpsc::channel();
+/// let mut source = ChannelSource::new(receiver, AudioFormat::STEREO_CD);
+///
+/// // Nothing has been sent yet.
+/// let mut samples = vec![0.0; 4];
+/// assert_eq!(source.read(&mut samples).state, StreamState::Underrun);
+///
+/// // Chunks are split and joined to fill each read.
+/// sender.send(vec![0.1, 0.2, 0.3]).unwrap();
+/// sender.send(vec![0.4, 0.5, 0.6]).unwrap();
+/// assert_eq!(source.read(&mut samples).state, StreamState::Good);
+/// assert_eq!(samples, [0.1, 0.2, 0.3, 0.4]);
+///
+/// // Only whole frames are read, so the partial frame waits for more data.
+/// let result = source.read(&mut samples);
+/// assert_eq!((result.state, result.read), (StreamState::Underrun, 2));
+/// assert_eq!(samples[..2], [0.5, 0.6]);
+///
+/// // A buffer that ends partway through a frame gets the whole frames that fit.
+/// sender.send(vec![0.7, 0.8]).unwrap();
+/// let result = source.read(&mut samples[..3]);
+/// assert_eq!((result.state, result.read), (StreamState::Good, 2));
+///
+/// sender.send(vec![0.9]).unwrap();
+/// drop(sender);
+/// let result = source.read(&mut samples);
+/// assert_eq!((result.state, result.read), (StreamState::Finished, 0));
+/// ```
+pub struct ChannelSource {
+    receiver: Receiver<Vec<Sample>>,
+    format: AudioFormat,
+    pending: VecDeque<Sample>,
+    disconnected: bool,
+}
+
+impl ChannelSource {
+    /// Construct a source that plays the samples sent to `receiver`.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` -- The receiving end of a channel of interleaved samples.
+    /// * `format` -- The format of the samples sent.
+    pub fn new(receiver: Receiver<Vec<Sample>>, format: AudioFormat) -> Self {
+        ChannelSource {
+            receiver,
+            format,
+            pending: VecDeque::new(),
+            disconnected: false,
+        }
+    }
+
+    /// Returns the number of samples that have been received but not yet played.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Move chunks from the channel into `pending` until there are at least `samples`.
+    fn receive(&mut self, samples: usize) {
+        while self.pending.len() < samples && !self.disconnected {
+            match self.receiver.try_recv() {
+                Ok(chunk) => self.pending.extend(chunk),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.disconnected = true,
+            }
+        }
+    }
+}
+
+impl AudioSource for ChannelSource {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "ChannelSource::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.receive(buffer.len());
+
+        let channels = self.format.channels as usize;
+        let whole = buffer.len() - buffer.len() % channels;
+        let available = std::cmp::min(whole, self.pending.len());
+        let read = available - available % channels;
+        for (sample, value) in buffer[..read].iter_mut().zip(self.pending.drain(..read)) {
+            *sample = value;
+        }
+
+        if read == whole {
+            ReadResult::good(read)
+        } else if self.disconnected {
+            // A partial frame left over at the end can never be completed.
+            self.pending.clear();
+            ReadResult::finished(read)
+        } else {
+            ReadResult::underrun(read)
+        }
+    }
+}
+
+impl Latency for ChannelSource {
+    fn latency_frames(&self) -> u64 {
+        (self.pending.len() / self.format.channels as usize) as u64
+    }
+}
+
+impl Tail for ChannelSource {}
@@ -0,0 +1,177 @@
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample, Tail};
+use std::time::Duration;
+use tracing::instrument;
+
+const ROWS: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+const COLUMNS: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+const KEYS: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+/// An [`AudioSource`](crate::AudioSource) that generates a telephone keypad tone.
+///
+/// Each key produces the sum of two sine waves, one for its row and one for
+/// its column on the keypad. The tone plays for a fixed duration, after which
+/// the source is [`Finished`](crate::StreamState::Finished).
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::Dtmf, AudioSource};
+/// // Measures the power of `frequency` in `samples` (Goertzel algorithm).
+/// fn power(samples: &[f32], frequency: f32) -> f32 {
+///     let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / 44100.0).cos();
+///     let (mut s1, mut s2) = (0.0, 0.0);
+///     for sample in samples {
+///         let s0 = sample + coefficient * s1 - s2;
+///         s2 = s1;
+///         s1 = s0;
+///     }
+///     s1 * s1 + s2 * s2 - coefficient * s1 * s2
+/// }
+///
+/// let mut tone = Dtmf::new('5', 0.5);
+/// let mut samples = vec![0.0; 4410 * 2];
+/// tone.read(&mut samples);
+/// let left: Vec<f32> = samples.iter().step_by(2).cloned().collect();
+///
+/// // '5' is the second row (770 Hz) and second column (1336 Hz).
+/// assert!(power(&left, 770.0) > 100.0 * power(&left, 697.0));
+/// assert!(power(&left, 1336.0) > 100.0 * power(&left, 1209.0));
+/// ```
+#[derive(Clone)]
+pub struct Dtmf {
+    amplitude: f32,
+    format: AudioFormat,
+    frequencies: [f32; 2],
+    phases: [f32; 2],
+    position: usize,
+    length: usize,
+}
+
+impl Dtmf {
+    /// The default length of a tone.
+    pub const DEFAULT_DURATION: Duration = Duration::from_millis(200);
+
+    /// Construct a tone for the given key lasting [`DEFAULT_DURATION`](Self::DEFAULT_DURATION).
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` -- The keypad key to play: `'0'`-`'9'`, `'*'`, `'#'`, or `'A'`-`'D'`.
+    /// * `amplitude` -- The peak value of each of the two component tones.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is not a valid keypad key.
+    pub fn new(key: char, amplitude: f32) -> Self {
+        Dtmf::with_duration(key, amplitude, Dtmf::DEFAULT_DURATION)
+    }
+
+    /// Construct a tone for the given key lasting `duration`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` -- The keypad key to play: `'0'`-`'9'`, `'*'`, `'#'`, or `'A'`-`'D'`.
+    /// * `amplitude` -- The peak value of each of the two component tones.
+    /// * `duration` -- How long the tone plays before the source finishes.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is not a valid keypad key.
+    pub fn with_duration(key: char, amplitude: f32, duration: Duration) -> Self {
+        Dtmf::with_format(AudioFormat::default(), key, amplitude, duration)
+    }
+
+    /// Construct a tone for the given key with the given format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `key` -- The keypad key to play: `'0'`-`'9'`, `'*'`, `'#'`, or `'A'`-`'D'`.
+    /// * `amplitude` -- The peak value of each of the two component tones.
+    /// * `duration` -- How long the tone plays before the source finishes.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is not a valid keypad key.
+    pub fn with_format(format: AudioFormat, key: char, amplitude: f32, duration: Duration) -> Self {
+        let frequencies =
+            Dtmf::frequencies(key).unwrap_or_else(|| panic!("Invalid DTMF key: {:?}", key));
+
+        Dtmf {
+            amplitude,
+            format,
+            frequencies,
+            phases: [0.0, 0.0],
+            position: 0,
+            length: format.frames_to_samples(format.duration_to_frames(duration) as usize),
+        }
+    }
+
+    /// Look up the row and column frequencies for a key.
+    ///
+    /// # Returns
+    ///
+    /// The `[row, column]` frequencies in Hz, or `None` if `key` is not a valid keypad key.
+    pub fn frequencies(key: char) -> Option<[f32; 2]> {
+        let key = key.to_ascii_uppercase();
+        KEYS.iter().enumerate().find_map(|(row, keys)| {
+            keys.iter()
+                .position(|&k| k == key)
+                .map(|column| [ROWS[row], COLUMNS[column]])
+        })
+    }
+}
+
+impl AudioSource for Dtmf {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "Dtmf::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let tau = std::f32::consts::PI * 2.0;
+        let increments = [
+            tau * self.frequencies[0] / self.format.sample_rate as f32,
+            tau * self.frequencies[1] / self.format.sample_rate as f32,
+        ];
+
+        let channels = self.format.channels as usize;
+        let written = std::cmp::min(buffer.len(), self.length - self.position);
+
+        for frame in buffer[..written].chunks_exact_mut(channels) {
+            let value = self.amplitude * (self.phases[0].sin() + self.phases[1].sin());
+            frame.iter_mut().for_each(|sample| *sample = value);
+
+            self.phases[0] = (self.phases[0] + increments[0]) % tau;
+            self.phases[1] = (self.phases[1] + increments[1]) % tau;
+        }
+        self.position += written;
+
+        if self.position < self.length {
+            ReadResult::good(written)
+        } else {
+            ReadResult::finished(written)
+        }
+    }
+}
+
+impl Latency for Dtmf {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for Dtmf {}
+
+impl Rewind for Dtmf {
+    fn rewind(&mut self) -> bool {
+        self.phases = [0.0, 0.0];
+        self.position = 0;
+        true
+    }
+}
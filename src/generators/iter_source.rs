@@ -0,0 +1,82 @@
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Sample, Tail};
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that plays back samples from an iterator.
+///
+/// When the iterator runs out, `IterSource` reports
+/// [`Finished`](crate::StreamState::Finished). An infinite iterator makes
+/// an infinite source, so simple signals can be written as a closure.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::IterSource, AudioFormat, AudioSource, StreamState};
+/// // A mono square wave with a period of 4 samples, lasting 10 samples.
+/// let square = (0..10).map(|i| if i % 4 < 2 { 1.0 } else { -1.0 });
+/// let mut source = IterSource::new(square, AudioFormat::MONO_CD);
+///
+/// let mut samples = vec![0.0; 8];
+/// assert_eq!(source.read(&mut samples).state, StreamState::Good);
+/// assert_eq!(samples, [1.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0]);
+///
+/// let result = source.read(&mut samples);
+/// assert_eq!(result.state, StreamState::Finished);
+/// assert_eq!(result.read, 2);
+///
+/// // A buffer that ends partway through a frame gets the whole frames that fit.
+/// let mut stereo = IterSource::new((0..6).map(|i| i as f32), AudioFormat::STEREO_CD);
+/// let result = stereo.read(&mut samples[..3]);
+/// assert_eq!((result.state, result.read), (StreamState::Good, 2));
+/// assert_eq!(&samples[..2], [0.0, 1.0]);
+///
+/// let result = stereo.read(&mut samples[..5]);
+/// assert_eq!((result.state, result.read), (StreamState::Good, 4));
+/// assert_eq!(&samples[..4], [2.0, 3.0, 4.0, 5.0]);
+/// ```
+pub struct IterSource<I: Iterator<Item = Sample>> {
+    samples: I,
+    format: AudioFormat,
+}
+
+impl<I: Iterator<Item = Sample>> IterSource<I> {
+    /// Construct a source that plays back samples from `samples`.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` -- The interleaved samples to play. If the iterator ends
+    ///   partway through a frame, the partial frame is discarded.
+    /// * `format` -- The format of `samples`.
+    pub fn new(samples: I, format: AudioFormat) -> Self {
+        IterSource { samples, format }
+    }
+}
+
+impl<I: Iterator<Item = Sample>> AudioSource for IterSource<I> {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "IterSource::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.format.channels as usize;
+        let whole = buffer.len() - buffer.len() % channels;
+        let mut read = 0;
+        for (sample, value) in buffer[..whole].iter_mut().zip(&mut self.samples) {
+            *sample = value;
+            read += 1;
+        }
+
+        if read == whole {
+            ReadResult::good(read)
+        } else {
+            ReadResult::finished(read - read % channels)
+        }
+    }
+}
+
+impl<I: Iterator<Item = Sample>> Latency for IterSource<I> {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl<I: Iterator<Item = Sample>> Tail for IterSource<I> {}
@@ -0,0 +1,145 @@
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample, Tail};
+use tracing::instrument;
+
+/// How long each click lasts, in seconds.
+const CLICK_SECONDS: f64 = 0.03;
+/// The time constant of the click's exponential decay, in seconds.
+const DECAY_SECONDS: f64 = 0.005;
+/// The pitch and peak amplitude of the click on the first beat of each bar.
+const DOWNBEAT: (f64, f64) = (2000.0, 1.0);
+/// The pitch and peak amplitude of the click on the other beats.
+const BEAT: (f64, f64) = (1000.0, 0.6);
+
+/// An [`AudioSource`](crate::AudioSource) that clicks in time, like a metronome.
+///
+/// Each beat is a short, decaying sine burst, with silence in between. The
+/// first beat of each bar is accented with a higher and louder click. The
+/// first click plays at the very first frame.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::Metronome, render_frames, AudioFormat};
+/// // At 120 BPM, beats are half a second apart.
+/// let metronome = Metronome::new(120.0, 4, AudioFormat::MONO_CD);
+/// let (samples, _) = render_frames(metronome, 5 * 22050);
+///
+/// let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+/// let peaks: Vec<f32> = (0..5)
+///     .map(|beat| peak(&samples[beat * 22050..beat * 22050 + 1323]))
+///     .collect();
+///
+/// // It's silent from the end of each click up to the frame the next begins.
+/// for beat in 1..5 {
+///     assert!(samples[beat * 22050 - 20000..beat * 22050].iter().all(|&s| s == 0.0));
+///     assert_ne!(samples[beat * 22050 + 1], 0.0);
+/// }
+///
+/// // Every beat clicks, and the downbeats are louder.
+/// assert!(peaks.iter().all(|&peak| peak > 0.5));
+/// assert!(peaks[0] > peaks[1] && peaks[4] > peaks[3]);
+/// assert_eq!(peaks[0], peaks[4]);
+/// ```
+#[derive(Clone)]
+pub struct Metronome {
+    format: AudioFormat,
+    bpm: f32,
+    beats_per_bar: u32,
+    beat: u32,
+    elapsed: f64,
+}
+
+impl Metronome {
+    /// Construct a metronome.
+    ///
+    /// # Arguments
+    ///
+    /// * `bpm` -- The tempo, in beats per minute.
+    /// * `beats_per_bar` -- The number of beats in each bar, e.g. 3 for 3/4 time.
+    /// * `format` -- The format for the generated stream.
+    ///
+    /// # Panics
+    ///
+    /// If `bpm` is not positive or `beats_per_bar` is zero.
+    pub fn new(bpm: f32, beats_per_bar: u32, format: AudioFormat) -> Self {
+        assert!(bpm > 0.0, "The tempo must be positive.");
+        assert!(beats_per_bar > 0, "A bar must have at least one beat.");
+        Metronome {
+            format,
+            bpm,
+            beats_per_bar,
+            beat: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Set the tempo, in beats per minute.
+    ///
+    /// The next click plays one beat at the new tempo after the previous click.
+    ///
+    /// # Panics
+    ///
+    /// If `bpm` is not positive.
+    pub fn set_bpm(&mut self, bpm: f32) {
+        assert!(bpm > 0.0, "The tempo must be positive.");
+        self.bpm = bpm;
+    }
+
+    pub fn bpm(&self) -> f32 {
+        self.bpm
+    }
+
+    pub fn beats_per_bar(&self) -> u32 {
+        self.beats_per_bar
+    }
+}
+
+impl AudioSource for Metronome {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "Metronome::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let sample_rate = self.format.sample_rate as f64;
+        let interval = 60.0 / self.bpm as f64 * sample_rate;
+        let channels = self.format.channels as usize;
+        let written = buffer.len() / channels * channels;
+
+        for frame in buffer[..written].chunks_exact_mut(channels) {
+            if self.elapsed >= interval {
+                self.elapsed -= interval;
+                self.beat = (self.beat + 1) % self.beats_per_bar;
+            }
+
+            let time = self.elapsed / sample_rate;
+            let value = if time < CLICK_SECONDS {
+                let (frequency, amplitude) = if self.beat == 0 { DOWNBEAT } else { BEAT };
+                let phase = 2.0 * std::f64::consts::PI * frequency * time;
+                (amplitude * (-time / DECAY_SECONDS).exp() * phase.sin()) as f32
+            } else {
+                0.0
+            };
+            frame.iter_mut().for_each(|sample| *sample = value);
+
+            self.elapsed += 1.0;
+        }
+
+        ReadResult::good(written)
+    }
+}
+
+impl Latency for Metronome {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for Metronome {}
+
+impl Rewind for Metronome {
+    fn rewind(&mut self) -> bool {
+        self.beat = 0;
+        self.elapsed = 0.0;
+        true
+    }
+}
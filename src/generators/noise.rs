@@ -0,0 +1,409 @@
+use crate::{
+    generators::rng::XorShift, AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample, Tail,
+};
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that generates white noise.
+///
+/// Every sample, on every channel, is an independent uniformly distributed
+/// value in `[-amplitude, amplitude]`. The noise comes from a small
+/// deterministic generator, so two sources with the same seed produce
+/// identical output, and rewinding replays the same noise.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::WhiteNoise, AudioSource};
+/// let mut a = WhiteNoise::with_seed(0.5, 42);
+/// let mut b = WhiteNoise::with_seed(0.5, 42);
+///
+/// let mut first = vec![0.0; 1024];
+/// let mut second = vec![0.0; 1024];
+/// a.read(&mut first);
+/// b.read(&mut second);
+/// assert_eq!(first, second);
+/// assert!(first.iter().all(|sample| sample.abs() <= 0.5));
+///
+/// // The noise averages out to nothing.
+/// let mean = first.iter().sum::<f32>() / first.len() as f32;
+/// assert!(mean.abs() < 0.05);
+/// ```
+#[derive(Clone)]
+pub struct WhiteNoise {
+    amplitude: f32,
+    format: AudioFormat,
+    rng: XorShift,
+    seed: u64,
+}
+
+impl WhiteNoise {
+    /// Construct a new white noise generator with the given amplitude.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the
+    /// format, and a fixed seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    pub fn new(amplitude: f32) -> Self {
+        WhiteNoise::with_format(AudioFormat::default(), amplitude)
+    }
+
+    /// Construct a new white noise generator with the given amplitude and seed.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `seed` -- The seed of the noise; the same seed always gives the same noise.
+    pub fn with_seed(amplitude: f32, seed: u64) -> Self {
+        WhiteNoise::with_format_and_seed(AudioFormat::default(), amplitude, seed)
+    }
+
+    /// Construct a new white noise generator with the given format and amplitude.
+    ///
+    /// Uses a fixed seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    pub fn with_format(format: AudioFormat, amplitude: f32) -> Self {
+        WhiteNoise::with_format_and_seed(format, amplitude, XorShift::DEFAULT_SEED)
+    }
+
+    /// Construct a new white noise generator with the given format, amplitude and seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `seed` -- The seed of the noise; the same seed always gives the same noise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::WhiteNoise, AudioFormat, AudioSource};
+    /// let mut a = WhiteNoise::with_format_and_seed(AudioFormat::MONO_CD, 0.5, 42);
+    /// let mut b = WhiteNoise::with_format_and_seed(AudioFormat::MONO_CD, 0.5, 7);
+    /// let mut c = WhiteNoise::with_seed(0.5, 42);
+    /// assert_eq!(a.format(), AudioFormat::MONO_CD);
+    ///
+    /// let mut first = vec![0.0; 1024];
+    /// let mut second = vec![0.0; 1024];
+    /// let mut third = vec![0.0; 1024];
+    /// a.read(&mut first);
+    /// b.read(&mut second);
+    /// c.read(&mut third);
+    /// assert_ne!(first, second);
+    /// // The noise depends only on the seed, not the format.
+    /// assert_eq!(first, third);
+    /// ```
+    pub fn with_format_and_seed(format: AudioFormat, amplitude: f32, seed: u64) -> Self {
+        WhiteNoise {
+            amplitude,
+            format,
+            rng: XorShift::new(seed),
+            seed,
+        }
+    }
+}
+
+impl AudioSource for WhiteNoise {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "WhiteNoise::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        for sample in buffer.iter_mut() {
+            *sample = self.amplitude * self.rng.next_f32();
+        }
+
+        ReadResult::good(buffer.len())
+    }
+}
+
+impl Latency for WhiteNoise {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for WhiteNoise {}
+
+impl Rewind for WhiteNoise {
+    fn rewind(&mut self) -> bool {
+        self.rng = XorShift::new(self.seed);
+        true
+    }
+}
+
+/// An [`AudioSource`](crate::AudioSource) that generates pink noise.
+///
+/// Pink noise has equal energy in every octave, falling off at 3 dB per
+/// octave, which sounds far softer than white noise: more like rain or
+/// wind. It's made by filtering [`WhiteNoise`] with Paul Kellet's filter,
+/// which stays within 0.05 dB of the ideal slope across the audible range.
+/// Each channel is filtered separately, and the filter state carries across
+/// reads. The output is clamped to `[-amplitude, amplitude]`.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::{PinkNoise, WhiteNoise}, AudioFormat, AudioSource};
+/// // The proportion of the energy in the differences between samples, which
+/// // is the high frequency content.
+/// fn roughness(samples: &[f32]) -> f32 {
+///     let energy: f32 = samples.iter().map(|s| s * s).sum();
+///     let differences: f32 = samples.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum();
+///     differences / energy
+/// }
+///
+/// let mut pink = vec![0.0; 44100];
+/// let mut white = vec![0.0; 44100];
+/// PinkNoise::with_format(AudioFormat::MONO_CD, 0.5).read(&mut pink);
+/// WhiteNoise::with_format(AudioFormat::MONO_CD, 0.5).read(&mut white);
+///
+/// assert!(pink.iter().all(|sample| sample.abs() <= 0.5));
+/// assert!(roughness(&pink) < 0.5 * roughness(&white));
+/// ```
+#[derive(Clone)]
+pub struct PinkNoise {
+    amplitude: f32,
+    white: WhiteNoise,
+    filters: Vec<[f32; 7]>,
+}
+
+impl PinkNoise {
+    /// Construct a new pink noise generator with the given amplitude.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    pub fn new(amplitude: f32) -> Self {
+        PinkNoise::with_format(AudioFormat::default(), amplitude)
+    }
+
+    /// Construct a new pink noise generator with the given format and amplitude.
+    ///
+    /// Uses a fixed seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    pub fn with_format(format: AudioFormat, amplitude: f32) -> Self {
+        PinkNoise::with_format_and_seed(format, amplitude, XorShift::DEFAULT_SEED)
+    }
+
+    /// Construct a new pink noise generator with the given format, amplitude and seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `seed` -- The seed of the underlying white noise; the same seed always
+    ///   gives the same noise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::PinkNoise, AudioFormat, AudioSource};
+    /// let mut a = PinkNoise::with_format_and_seed(AudioFormat::STEREO_CD, 0.5, 42);
+    /// let mut b = PinkNoise::with_format_and_seed(AudioFormat::STEREO_CD, 0.5, 42);
+    /// let mut c = PinkNoise::with_format_and_seed(AudioFormat::STEREO_CD, 0.5, 7);
+    ///
+    /// let mut first = vec![0.0; 1024];
+    /// let mut second = vec![0.0; 1024];
+    /// let mut third = vec![0.0; 1024];
+    /// a.read(&mut first);
+    /// b.read(&mut second);
+    /// c.read(&mut third);
+    /// assert_eq!(first, second);
+    /// assert_ne!(first, third);
+    /// ```
+    pub fn with_format_and_seed(format: AudioFormat, amplitude: f32, seed: u64) -> Self {
+        PinkNoise {
+            amplitude,
+            white: WhiteNoise::with_format_and_seed(format, 1.0, seed),
+            filters: vec![[0.0; 7]; format.channels as usize],
+        }
+    }
+}
+
+impl AudioSource for PinkNoise {
+    fn format(&self) -> AudioFormat {
+        self.white.format()
+    }
+
+    #[instrument(name = "PinkNoise::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.white.read(buffer);
+
+        let channels = self.filters.len();
+        for frame in buffer[..result.read].chunks_exact_mut(channels) {
+            for (sample, b) in frame.iter_mut().zip(self.filters.iter_mut()) {
+                let white = *sample;
+                b[0] = 0.99886 * b[0] + white * 0.0555179;
+                b[1] = 0.99332 * b[1] + white * 0.0750759;
+                b[2] = 0.96900 * b[2] + white * 0.153852;
+                b[3] = 0.86650 * b[3] + white * 0.3104856;
+                b[4] = 0.55000 * b[4] + white * 0.5329522;
+                b[5] = -0.7616 * b[5] - white * 0.016898;
+                let pink = b.iter().sum::<f32>() + white * 0.5362;
+                b[6] = white * 0.115926;
+
+                // The filter has a gain of about 9 at low frequencies.
+                *sample = self.amplitude * (pink * 0.11).clamp(-1.0, 1.0);
+            }
+        }
+
+        result
+    }
+}
+
+impl Latency for PinkNoise {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for PinkNoise {}
+
+impl Rewind for PinkNoise {
+    fn rewind(&mut self) -> bool {
+        self.filters.iter_mut().for_each(|b| *b = [0.0; 7]);
+        self.white.rewind()
+    }
+}
+
+/// An [`AudioSource`](crate::AudioSource) that generates brown noise.
+///
+/// Brown noise falls off at 6 dB per octave, a deep rumble like a waterfall
+/// or distant thunder. It's made by integrating [`WhiteNoise`]; the
+/// integrator leaks a little on every sample, so it can't wander off into a
+/// DC offset. Each channel is integrated separately, and the state carries
+/// across reads. The output is clamped to `[-amplitude, amplitude]`.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::{BrownNoise, PinkNoise}, AudioFormat, AudioSource};
+/// fn roughness(samples: &[f32]) -> f32 {
+///     let energy: f32 = samples.iter().map(|s| s * s).sum();
+///     let differences: f32 = samples.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum();
+///     differences / energy
+/// }
+///
+/// let mut brown = vec![0.0; 441000];
+/// let mut pink = vec![0.0; 441000];
+/// BrownNoise::with_format(AudioFormat::MONO_CD, 0.5).read(&mut brown);
+/// PinkNoise::with_format(AudioFormat::MONO_CD, 0.5).read(&mut pink);
+///
+/// assert!(brown.iter().all(|sample| sample.abs() <= 0.5));
+/// assert!(roughness(&brown) < 0.5 * roughness(&pink));
+///
+/// // The leak keeps it centered.
+/// let mean = brown.iter().sum::<f32>() / brown.len() as f32;
+/// assert!(mean.abs() < 0.05);
+/// ```
+#[derive(Clone)]
+pub struct BrownNoise {
+    amplitude: f32,
+    white: WhiteNoise,
+    levels: Vec<f32>,
+}
+
+impl BrownNoise {
+    /// Construct a new brown noise generator with the given amplitude.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    pub fn new(amplitude: f32) -> Self {
+        BrownNoise::with_format(AudioFormat::default(), amplitude)
+    }
+
+    /// Construct a new brown noise generator with the given format and amplitude.
+    ///
+    /// Uses a fixed seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    pub fn with_format(format: AudioFormat, amplitude: f32) -> Self {
+        BrownNoise::with_format_and_seed(format, amplitude, XorShift::DEFAULT_SEED)
+    }
+
+    /// Construct a new brown noise generator with the given format, amplitude and seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `seed` -- The seed of the underlying white noise; the same seed always
+    ///   gives the same noise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::BrownNoise, AudioFormat, AudioSource};
+    /// let mut a = BrownNoise::with_format_and_seed(AudioFormat::STEREO_CD, 0.5, 42);
+    /// let mut b = BrownNoise::with_format_and_seed(AudioFormat::STEREO_CD, 0.5, 42);
+    /// let mut c = BrownNoise::with_format_and_seed(AudioFormat::STEREO_CD, 0.5, 7);
+    ///
+    /// let mut first = vec![0.0; 1024];
+    /// let mut second = vec![0.0; 1024];
+    /// let mut third = vec![0.0; 1024];
+    /// a.read(&mut first);
+    /// b.read(&mut second);
+    /// c.read(&mut third);
+    /// assert_eq!(first, second);
+    /// assert_ne!(first, third);
+    /// ```
+    pub fn with_format_and_seed(format: AudioFormat, amplitude: f32, seed: u64) -> Self {
+        BrownNoise {
+            amplitude,
+            white: WhiteNoise::with_format_and_seed(format, 1.0, seed),
+            levels: vec![0.0; format.channels as usize],
+        }
+    }
+}
+
+impl AudioSource for BrownNoise {
+    fn format(&self) -> AudioFormat {
+        self.white.format()
+    }
+
+    #[instrument(name = "BrownNoise::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let result = self.white.read(buffer);
+
+        let channels = self.levels.len();
+        for frame in buffer[..result.read].chunks_exact_mut(channels) {
+            for (sample, level) in frame.iter_mut().zip(self.levels.iter_mut()) {
+                *level = (*level + 0.02 * *sample) / 1.02;
+                // Scale the integrated level, which rarely passes 0.3, to full range.
+                *sample = self.amplitude * (*level * 3.5).clamp(-1.0, 1.0);
+            }
+        }
+
+        result
+    }
+}
+
+impl Latency for BrownNoise {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for BrownNoise {}
+
+impl Rewind for BrownNoise {
+    fn rewind(&mut self) -> bool {
+        self.levels.iter_mut().for_each(|level| *level = 0.0);
+        self.white.rewind()
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    generators::rng::XorShift, AudioFormat, AudioSource, Latency, ReadResult, Sample, Tail,
+};
+use tracing::instrument;
+
+/// A plucked string, synthesized with the Karplus-Strong algorithm.
+///
+/// A delay line one period long is filled with noise, then repeatedly played
+/// back while being averaged and decayed, which quickly settles into a
+/// string-like tone. The source is [`Finished`](crate::StreamState::Finished)
+/// once the string has decayed to silence.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::PluckedString, AudioSource, StreamState};
+/// # fn power(samples: &[f32], frequency: f32) -> f32 {
+/// #     let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / 44100.0).cos();
+/// #     let (mut s1, mut s2) = (0.0, 0.0);
+/// #     for sample in samples {
+/// #         let s0 = sample + coefficient * s1 - s2;
+/// #         s2 = s1;
+/// #         s1 = s0;
+/// #     }
+/// #     s1 * s1 + s2 * s2 - coefficient * s1 * s2
+/// # }
+/// let mut string = PluckedString::new(220.0, 0.99, 0.5);
+///
+/// let mut samples = vec![0.0; 4410 * 2];
+/// string.read(&mut samples);
+/// let left: Vec<f32> = samples.iter().step_by(2).cloned().collect();
+///
+/// // The fundamental dominates frequencies between the harmonics.
+/// assert!(power(&left, 220.0) > 10.0 * power(&left, 330.0));
+///
+/// // Eventually the string decays to silence.
+/// let mut blocks = 0;
+/// while string.read(&mut samples).state != StreamState::Finished {
+///     blocks += 1;
+///     assert!(blocks < 1000);
+/// }
+/// ```
+pub struct PluckedString {
+    decay: f32,
+    format: AudioFormat,
+    line: Vec<f32>,
+    position: usize,
+    threshold: f32,
+    finished: bool,
+}
+
+impl PluckedString {
+    /// Construct a new plucked string.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` -- The fundamental frequency of the string, in Hz.
+    /// * `decay` -- The energy retained on each pass through the delay line, just under 1.0.
+    /// * `amplitude` -- The peak value of the initial pluck.
+    pub fn new(frequency: f32, decay: f32, amplitude: f32) -> Self {
+        PluckedString::with_format(AudioFormat::default(), frequency, decay, amplitude)
+    }
+
+    /// Construct a new plucked string with the given format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `frequency` -- The fundamental frequency of the string, in Hz.
+    /// * `decay` -- The amount of energy retained on each step through the delay line.
+    /// * `amplitude` -- The peak value of the initial pluck.
+    pub fn with_format(format: AudioFormat, frequency: f32, decay: f32, amplitude: f32) -> Self {
+        let length = std::cmp::max(1, (format.sample_rate as f32 / frequency).round() as usize);
+        let mut rng = XorShift::new(XorShift::DEFAULT_SEED);
+        let line = (0..length).map(|_| amplitude * rng.next_f32()).collect();
+
+        PluckedString {
+            decay,
+            format,
+            line,
+            position: 0,
+            // Roughly -80 dB relative to the pluck.
+            threshold: amplitude.abs() * 1e-4,
+            finished: false,
+        }
+    }
+}
+
+impl AudioSource for PluckedString {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "PluckedString::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.format.channels as usize;
+        let length = self.line.len();
+
+        let mut written = 0;
+        for frame in buffer.chunks_exact_mut(channels) {
+            if self.finished {
+                break;
+            }
+
+            let value = self.line[self.position];
+            let next = self.line[(self.position + 1) % length];
+            self.line[self.position] = self.decay * 0.5 * (value + next);
+            frame.iter_mut().for_each(|sample| *sample = value);
+            written += channels;
+
+            self.position = (self.position + 1) % length;
+            if self.position == 0 {
+                let peak = self.line.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+                self.finished = peak < self.threshold;
+            }
+        }
+
+        if self.finished {
+            ReadResult::finished(written)
+        } else {
+            ReadResult::good(written)
+        }
+    }
+}
+
+impl Latency for PluckedString {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for PluckedString {}
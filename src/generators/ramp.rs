@@ -0,0 +1,122 @@
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample, Tail};
+use std::time::Duration;
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that moves linearly from one value to another.
+///
+/// `Ramp` is mostly useful as a control signal, e.g. for automating effect
+/// parameters, or for testing how effects respond to a changing input.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::Ramp, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// let mut ramp = Ramp::new(0.0, 1.0, Duration::from_secs(1), AudioFormat::MONO_CD);
+///
+/// let mut samples = vec![0.0; 44100];
+/// ramp.read(&mut samples);
+/// assert!((samples[22050] - 0.5).abs() < 1e-6);
+///
+/// // After the ramp completes, the end value is held.
+/// ramp.read(&mut samples);
+/// assert!(samples.iter().all(|&s| s == 1.0));
+/// ```
+#[derive(Clone)]
+pub struct Ramp {
+    start: f32,
+    end: f32,
+    format: AudioFormat,
+    frames: u64,
+    position: u64,
+    finite: bool,
+}
+
+impl Ramp {
+    /// Construct a ramp that holds its end value once it completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` -- The value of the first frame.
+    /// * `end` -- The value reached after `duration`.
+    /// * `duration` -- The length of time taken to move from `start` to `end`.
+    /// * `format` -- The format for the generated stream.
+    pub fn new(start: f32, end: f32, duration: Duration, format: AudioFormat) -> Self {
+        let frames = format.duration_to_frames(duration);
+        Ramp {
+            start,
+            end,
+            format,
+            frames,
+            position: 0,
+            finite: false,
+        }
+    }
+
+    /// Construct a ramp that is [`Finished`](crate::StreamState::Finished) once it completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` -- The value of the first frame.
+    /// * `end` -- The value reached after `duration`.
+    /// * `duration` -- The length of time taken to move from `start` to `end`.
+    /// * `format` -- The format for the generated stream.
+    pub fn finite(start: f32, end: f32, duration: Duration, format: AudioFormat) -> Self {
+        Ramp {
+            finite: true,
+            ..Ramp::new(start, end, duration, format)
+        }
+    }
+
+    fn value(&self) -> f32 {
+        if self.position >= self.frames {
+            self.end
+        } else {
+            let t = self.position as f64 / self.frames as f64;
+            (self.start as f64 + (self.end - self.start) as f64 * t) as f32
+        }
+    }
+}
+
+impl AudioSource for Ramp {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "Ramp::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let channels = self.format.channels as usize;
+
+        let mut written = 0;
+        for frame in buffer.chunks_exact_mut(channels) {
+            if self.finite && self.position >= self.frames {
+                return ReadResult::finished(written);
+            }
+
+            let value = self.value();
+            frame.iter_mut().for_each(|sample| *sample = value);
+            self.position += 1;
+            written += channels;
+        }
+
+        if self.finite && self.position >= self.frames {
+            ReadResult::finished(written)
+        } else {
+            ReadResult::good(written)
+        }
+    }
+}
+
+impl Latency for Ramp {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for Ramp {}
+
+impl Rewind for Ramp {
+    fn rewind(&mut self) -> bool {
+        self.position = 0;
+        true
+    }
+}
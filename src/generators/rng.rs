@@ -0,0 +1,37 @@
+/// A small, fast pseudo-random number generator (xorshift64).
+///
+/// Deterministic for a given seed, which keeps generated noise reproducible.
+#[derive(Clone)]
+pub(crate) struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub const DEFAULT_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero, so substitute any non-zero seed.
+        let state = if seed == 0 {
+            XorShift::DEFAULT_SEED
+        } else {
+            seed
+        };
+        XorShift { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// Returns a uniformly distributed value in `[-1.0, 1.0]`.
+    pub fn next_f32(&mut self) -> f32 {
+        // Use the top 24 bits, which is all the precision an f32 mantissa holds.
+        let value = (self.next_u64() >> 40) as f32 / (1u32 << 24) as f32;
+        value * 2.0 - 1.0
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    generators::blep::poly_blep, AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample,
+    Tail,
+};
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that generates a sawtooth wave.
+///
+/// The wave ramps linearly from `-amplitude` to `amplitude` over each period.
+/// The naive wave aliases harshly at high frequencies; use
+/// [`bandlimited`](SawtoothWave::bandlimited) to smooth each reset with PolyBLEP.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SawtoothWave, AudioFormat, AudioSource};
+/// # fn power(samples: &[f32], frequency: f32) -> f32 {
+/// #     let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / 44100.0).cos();
+/// #     let (mut s1, mut s2) = (0.0, 0.0);
+/// #     for sample in samples {
+/// #         let s0 = sample + coefficient * s1 - s2;
+/// #         s2 = s1;
+/// #         s1 = s0;
+/// #     }
+/// #     s1 * s1 + s2 * s2 - coefficient * s1 * s2
+/// # }
+/// let format = AudioFormat::MONO_CD;
+/// let mut naive = SawtoothWave::with_format(format, 1.0, 5000.0);
+/// let mut bandlimited = SawtoothWave::with_format(format, 1.0, 5000.0);
+/// bandlimited.set_bandlimited(true);
+///
+/// let mut a = vec![0.0; 4410];
+/// let mut b = vec![0.0; 4410];
+/// naive.read(&mut a);
+/// bandlimited.read(&mut b);
+///
+/// // Sum the energy above a quarter of the sample rate that isn't a real
+/// // harmonic (15 kHz or 20 kHz); it's all aliased from above Nyquist.
+/// let aliased = |s: &[f32]| {
+///     (1103..2205)
+///         .map(|bin| bin as f32 * 10.0)
+///         .filter(|&f| f != 15000.0 && f != 20000.0)
+///         .map(|f| power(s, f))
+///         .sum::<f32>()
+/// };
+/// assert!(aliased(&b) < 0.1 * aliased(&a));
+/// ```
+#[derive(Clone)]
+pub struct SawtoothWave {
+    amplitude: f32,
+    bandlimited: bool,
+    format: AudioFormat,
+    frequency: f32,
+    phase: f32,
+}
+
+impl SawtoothWave {
+    /// Construct a new sawtooth wave generator with the given amplitude and frequency.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn new(amplitude: f32, frequency: f32) -> Self {
+        SawtoothWave::with_format(AudioFormat::default(), amplitude, frequency)
+    }
+
+    /// Construct a new sawtooth wave generator with the given format, amplitude, and frequency.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn with_format(format: AudioFormat, amplitude: f32, frequency: f32) -> Self {
+        SawtoothWave {
+            amplitude,
+            bandlimited: false,
+            format,
+            frequency,
+            phase: 0.0,
+        }
+    }
+
+    /// Construct a new sawtooth wave generator that reduces aliasing with PolyBLEP.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn bandlimited(amplitude: f32, frequency: f32) -> Self {
+        SawtoothWave {
+            bandlimited: true,
+            ..SawtoothWave::new(amplitude, frequency)
+        }
+    }
+
+    /// Set whether to reduce aliasing with PolyBLEP.
+    pub fn set_bandlimited(&mut self, bandlimited: bool) {
+        self.bandlimited = bandlimited;
+    }
+
+    pub fn is_bandlimited(&self) -> bool {
+        self.bandlimited
+    }
+
+    /// Set the frequency of the wave, in Hz.
+    ///
+    /// The wave continues from its current phase, so retuning doesn't click.
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+}
+
+impl AudioSource for SawtoothWave {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "SawtoothWave::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let increment = self.frequency / self.format.sample_rate as f32;
+
+        let channels = self.format.channels as usize;
+        for frame in buffer.chunks_exact_mut(channels) {
+            let mut value = 2.0 * self.phase - 1.0;
+            if self.bandlimited {
+                value -= poly_blep(self.phase, increment);
+            }
+            let value = self.amplitude * value;
+            frame.iter_mut().for_each(|sample| *sample = value);
+
+            self.phase = (self.phase + increment).fract();
+        }
+
+        ReadResult::good(buffer.len())
+    }
+}
+
+impl Latency for SawtoothWave {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for SawtoothWave {}
+
+impl Rewind for SawtoothWave {
+    fn rewind(&mut self) -> bool {
+        self.phase = 0.0;
+        true
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    generators::blep::poly_blep, AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample,
+    Tail,
+};
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that generates a square wave.
+///
+/// The wave is `amplitude` for the first part of each period and `-amplitude`
+/// for the rest. By default the parts are equal halves; a different
+/// [duty cycle](SquareWave::set_duty_cycle) gives the thinner pulse waves
+/// heard in chiptunes. The naive wave aliases harshly at high frequencies; use
+/// [`bandlimited`](SquareWave::bandlimited) to smooth each edge with PolyBLEP.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SquareWave, AudioSource};
+/// let mut square = SquareWave::bandlimited(0.5, 440.0);
+///
+/// let mut samples = vec![0.0; 512];
+/// square.read(&mut samples);
+/// assert!(samples.iter().all(|sample| sample.abs() <= 0.5));
+/// ```
+#[derive(Clone)]
+pub struct SquareWave {
+    amplitude: f32,
+    bandlimited: bool,
+    duty_cycle: f32,
+    format: AudioFormat,
+    frequency: f32,
+    phase: f32,
+}
+
+impl SquareWave {
+    /// Construct a new square wave generator with the given amplitude and frequency.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn new(amplitude: f32, frequency: f32) -> Self {
+        SquareWave::with_format(AudioFormat::default(), amplitude, frequency)
+    }
+
+    /// Construct a new square wave generator with the given format, amplitude, and frequency.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn with_format(format: AudioFormat, amplitude: f32, frequency: f32) -> Self {
+        SquareWave {
+            amplitude,
+            bandlimited: false,
+            duty_cycle: 0.5,
+            format,
+            frequency,
+            phase: 0.0,
+        }
+    }
+
+    /// Construct a new square wave generator that reduces aliasing with PolyBLEP.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn bandlimited(amplitude: f32, frequency: f32) -> Self {
+        SquareWave {
+            bandlimited: true,
+            ..SquareWave::new(amplitude, frequency)
+        }
+    }
+
+    /// Set whether to reduce aliasing with PolyBLEP.
+    pub fn set_bandlimited(&mut self, bandlimited: bool) {
+        self.bandlimited = bandlimited;
+    }
+
+    pub fn is_bandlimited(&self) -> bool {
+        self.bandlimited
+    }
+
+    /// Set the frequency of the wave, in Hz.
+    ///
+    /// The wave continues from its current phase, so retuning doesn't click.
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Set the fraction of each period that the wave is positive.
+    ///
+    /// # Arguments
+    ///
+    /// * `duty_cycle` -- The fraction of the period, between 0.0 and 1.0
+    ///   exclusive. The default is 0.5, a symmetric square wave; NES-style
+    ///   pulse waves use 0.125, 0.25, and 0.75.
+    ///
+    /// # Panics
+    ///
+    /// If `duty_cycle` is not between 0.0 and 1.0 exclusive.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SquareWave, AudioSource};
+    /// let mut pulse = SquareWave::new(1.0, 441.0);
+    /// pulse.set_duty_cycle(0.25);
+    ///
+    /// let mut samples = vec![0.0; 44100 * 2];
+    /// pulse.read(&mut samples);
+    /// let positive = samples.iter().filter(|&&s| s > 0.0).count();
+    /// assert_eq!(positive, samples.len() / 4);
+    /// ```
+    pub fn set_duty_cycle(&mut self, duty_cycle: f32) {
+        assert!(
+            duty_cycle > 0.0 && duty_cycle < 1.0,
+            "The duty cycle must be between 0.0 and 1.0 exclusive."
+        );
+        self.duty_cycle = duty_cycle;
+    }
+
+    pub fn duty_cycle(&self) -> f32 {
+        self.duty_cycle
+    }
+}
+
+impl AudioSource for SquareWave {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "SquareWave::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let increment = self.frequency / self.format.sample_rate as f32;
+
+        let channels = self.format.channels as usize;
+        for frame in buffer.chunks_exact_mut(channels) {
+            let mut value = if self.phase < self.duty_cycle {
+                1.0
+            } else {
+                -1.0
+            };
+            if self.bandlimited {
+                // A rising edge at the start of the period, and a falling edge
+                // at the end of the duty cycle.
+                value += poly_blep(self.phase, increment);
+                value -= poly_blep((self.phase + 1.0 - self.duty_cycle).fract(), increment);
+            }
+            let value = self.amplitude * value;
+            frame.iter_mut().for_each(|sample| *sample = value);
+
+            self.phase = (self.phase + increment).fract();
+        }
+
+        ReadResult::good(buffer.len())
+    }
+}
+
+impl Latency for SquareWave {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for SquareWave {}
+
+impl Rewind for SquareWave {
+    fn rewind(&mut self) -> bool {
+        self.phase = 0.0;
+        true
+    }
+}
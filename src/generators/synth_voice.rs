@@ -0,0 +1,312 @@
+use crate::{
+    effects::{
+        biquad::{filter, Coefficients},
+        smoothed::Smoothed,
+    },
+    generators::{SawtoothWave, SineWave, SquareWave, TriangleWave},
+    midi_to_frequency, AudioFormat, AudioSource, ReadResult, Sample,
+};
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+/// A generator whose pitch can be changed while it plays, so that a
+/// [`SynthVoice`](crate::generators::SynthVoice) can play it at any note.
+pub trait Oscillator: AudioSource {
+    /// Set the frequency, in Hz, continuing from the current phase.
+    fn set_frequency(&mut self, frequency: f32);
+}
+
+impl Oscillator for SineWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        SineWave::set_frequency(self, frequency);
+    }
+}
+
+impl Oscillator for SquareWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        SquareWave::set_frequency(self, frequency);
+    }
+}
+
+impl Oscillator for SawtoothWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        SawtoothWave::set_frequency(self, frequency);
+    }
+}
+
+impl Oscillator for TriangleWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        TriangleWave::set_frequency(self, frequency);
+    }
+}
+
+/// The shape of the volume of a note over time: attack, decay, sustain and release.
+///
+/// When a note starts, the volume rises linearly to full over `attack`, then
+/// falls linearly to `sustain` over `decay`, and stays there while the note is
+/// held. When the note is released, the volume falls linearly to silence over
+/// `release`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Adsr {
+    /// How long the volume takes to rise to full at the start of a note.
+    pub attack: Duration,
+    /// How long the volume takes to fall from full to the sustain level.
+    pub decay: Duration,
+    /// The volume while the note is held, from 0.0 to 1.0.
+    pub sustain: f32,
+    /// How long the volume takes to fall to silence after the note is released.
+    pub release: Duration,
+}
+
+impl Default for Adsr {
+    fn default() -> Self {
+        Adsr {
+            attack: Duration::from_millis(10),
+            decay: Duration::from_millis(100),
+            sustain: 0.7,
+            release: Duration::from_millis(200),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release { step: f32, remaining: u64 },
+}
+
+/// The Butterworth quality factor, for a filter without resonance.
+const FILTER_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// An [`AudioSource`](crate::AudioSource) that plays notes on an oscillator,
+/// like one voice of a synthesizer.
+///
+/// [`note_on`](SynthVoice::note_on) tunes the oscillator to a MIDI note and
+/// starts its [`Adsr`](crate::generators::Adsr) envelope, with the volume
+/// scaled by the velocity, and [`note_off`](SynthVoice::note_off) releases it.
+/// An optional low-pass filter shapes the tone. Between notes, the voice
+/// plays silence. The oscillator should have an amplitude of 1.0, so that the
+/// envelope and velocity set the volume.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::{Adsr, SawtoothWave, SynthVoice}, AudioFormat, AudioSource};
+/// # use std::time::Duration;
+/// let saw = SawtoothWave::with_format(AudioFormat::MONO_CD, 1.0, 440.0);
+/// let mut voice = SynthVoice::new(saw, Adsr::default());
+/// voice.set_filter_cutoff(Some(2000.0));
+///
+/// let mut samples = vec![0.0; 4410];
+/// voice.read(&mut samples);
+/// assert!(samples.iter().all(|&s| s == 0.0));
+///
+/// voice.note_on(60, 100);
+/// voice.read(&mut samples);
+/// assert!(samples.iter().any(|&s| s != 0.0));
+///
+/// voice.note_off();
+/// voice.read(&mut vec![0.0; 44100]);
+/// assert!(!voice.is_active());
+/// ```
+pub struct SynthVoice<O: Oscillator> {
+    oscillator: O,
+    adsr: Adsr,
+    stage: Stage,
+    level: f32,
+    velocity: Smoothed,
+    note: Option<u8>,
+    cutoff: Option<f32>,
+    coefficients: Option<Coefficients>,
+    filter_state: Vec<[f32; 2]>,
+}
+
+impl<O: Oscillator> SynthVoice<O> {
+    /// Construct a voice.
+    ///
+    /// # Arguments
+    ///
+    /// * `oscillator` -- The generator to play, with an amplitude of 1.0.
+    /// * `adsr` -- The envelope applied to each note.
+    pub fn new(oscillator: O, adsr: Adsr) -> Self {
+        let channels = oscillator.format().channels as usize;
+        SynthVoice {
+            oscillator,
+            adsr,
+            stage: Stage::Idle,
+            level: 0.0,
+            velocity: Smoothed::new(0.0),
+            note: None,
+            cutoff: None,
+            coefficients: None,
+            filter_state: vec![[0.0; 2]; channels],
+        }
+    }
+
+    /// Start playing a note.
+    ///
+    /// If a note is already playing or releasing, the voice retriggers: the
+    /// oscillator is retuned and the attack starts again from the current
+    /// volume, while the volume glides to the new velocity over a few
+    /// milliseconds, so there's no click.
+    ///
+    /// # Arguments
+    ///
+    /// * `note` -- The MIDI note number, with A4 (440 Hz) at 69.
+    /// * `velocity` -- How hard the note was struck, from 0 to 127. As in
+    ///   MIDI, a velocity of 0 releases the note instead.
+    pub fn note_on(&mut self, note: u8, velocity: u8) {
+        if velocity == 0 {
+            self.note_off();
+            return;
+        }
+
+        self.oscillator
+            .set_frequency(midi_to_frequency(note as f32));
+        self.velocity.set(velocity.min(127) as f32 / 127.0);
+        if self.stage == Stage::Idle {
+            self.velocity.finish();
+        }
+        self.note = Some(note);
+        self.stage = Stage::Attack;
+    }
+
+    /// Release the note, so that it fades out over the envelope's release time.
+    pub fn note_off(&mut self) {
+        match self.stage {
+            Stage::Idle | Stage::Release { .. } => {}
+            _ => {
+                let remaining = std::cmp::max(
+                    self.oscillator
+                        .format()
+                        .duration_to_frames(self.adsr.release),
+                    1,
+                );
+                self.stage = Stage::Release {
+                    step: self.level / remaining as f32,
+                    remaining,
+                };
+            }
+        }
+    }
+
+    /// Returns the note playing or releasing, or `None` once the voice is silent.
+    pub fn note(&self) -> Option<u8> {
+        self.note
+    }
+
+    /// Returns true while a note is playing or releasing.
+    pub fn is_active(&self) -> bool {
+        self.stage != Stage::Idle
+    }
+
+    /// Set the envelope, starting with the next note.
+    pub fn set_adsr(&mut self, adsr: Adsr) {
+        self.adsr = adsr;
+    }
+
+    pub fn adsr(&self) -> Adsr {
+        self.adsr
+    }
+
+    /// Set the cutoff frequency of the low-pass filter, or `None` for no filter.
+    pub fn set_filter_cutoff(&mut self, cutoff: Option<f32>) {
+        self.cutoff = cutoff;
+        self.coefficients = None;
+        if cutoff.is_none() {
+            self.filter_state.iter_mut().for_each(|z| *z = [0.0; 2]);
+        }
+    }
+
+    pub fn filter_cutoff(&self) -> Option<f32> {
+        self.cutoff
+    }
+
+    /// Returns a reference to the oscillator, e.g. to change its settings.
+    pub fn oscillator_mut(&mut self) -> &mut O {
+        &mut self.oscillator
+    }
+
+    fn frames_in(&self, duration: Duration) -> f32 {
+        self.oscillator.format().duration_to_frames(duration) as f32
+    }
+
+    /// Advance the envelope by one frame, returning its new level.
+    fn envelope(&mut self) -> f32 {
+        match self.stage {
+            Stage::Idle => {}
+            Stage::Attack => {
+                self.level += 1.0 / self.frames_in(self.adsr.attack).max(1.0);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                let sustain = self.adsr.sustain.clamp(0.0, 1.0);
+                self.level -= (1.0 - sustain) / self.frames_in(self.adsr.decay).max(1.0);
+                if self.level <= sustain {
+                    self.level = sustain;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => self.level = self.adsr.sustain.clamp(0.0, 1.0),
+            Stage::Release { step, remaining } => {
+                self.level -= step;
+                self.stage = Stage::Release {
+                    step,
+                    remaining: remaining - 1,
+                };
+                // Counting frames rather than comparing the level makes sure
+                // rounding can't leave the voice sounding forever.
+                if remaining == 1 || self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Idle;
+                    self.note = None;
+                }
+            }
+        }
+        self.level
+    }
+}
+
+impl<O: Oscillator> AudioSource for SynthVoice<O> {
+    fn format(&self) -> AudioFormat {
+        self.oscillator.format()
+    }
+
+    #[instrument(name = "SynthVoice::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let format = self.oscillator.format();
+        let channels = format.channels as usize;
+        let written = buffer.len() - buffer.len() % channels;
+        let buffer = &mut buffer[..written];
+
+        if self.stage == Stage::Idle {
+            buffer.iter_mut().for_each(|s| *s = 0.0);
+            return ReadResult::good(written);
+        }
+
+        let read = self.oscillator.read(buffer).read;
+        buffer[read..].iter_mut().for_each(|s| *s = 0.0);
+        let frame_time = 1.0 / format.sample_rate as f32;
+        for frame in buffer.chunks_exact_mut(channels) {
+            let (_, velocity) = self.velocity.advance(frame_time);
+            let gain = self.envelope() * velocity;
+            frame.iter_mut().for_each(|s| *s *= gain);
+        }
+
+        if let Some(cutoff) = self.cutoff {
+            let coefficients = *self.coefficients.get_or_insert_with(|| {
+                Coefficients::low_pass(format.sample_rate, cutoff, FILTER_Q)
+            });
+            filter(buffer, &mut self.filter_state, &coefficients);
+        }
+
+        ReadResult::good(written)
+    }
+}
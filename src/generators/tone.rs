@@ -1,4 +1,4 @@
-use crate::{AudioFormat, AudioSource, ReadResult, Sample};
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample, Seek, Tail};
 use tracing::instrument;
 
 /// An [`AudioSource`](crate::AudioSource) that generates a sine wave.
@@ -13,6 +13,7 @@ pub struct SineWave {
     amplitude: f32,
     format: AudioFormat,
     phase: f32,
+    phases: Option<Vec<f32>>,
     frequency: f32,
 }
 
@@ -30,6 +31,7 @@ impl SineWave {
             amplitude,
             format: AudioFormat::default(),
             phase: 0.0,
+            phases: None,
             frequency,
         }
     }
@@ -46,9 +48,107 @@ impl SineWave {
             amplitude,
             format,
             phase: 0.0,
+            phases: None,
             frequency,
         }
     }
+
+    /// Construct a new sine wave generator with a phase offset for each channel.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    /// This is useful for generating test signals with a known relationship
+    /// between channels.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    /// * `phases` -- The phase offset of each channel, in radians.
+    ///
+    /// # Panics
+    ///
+    /// [`read`](crate::AudioSource::read) panics if `phases.len()` doesn't match
+    /// the number of channels in the format.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, AudioSource};
+    /// let quarter = std::f32::consts::FRAC_PI_2;
+    /// let mut sin = SineWave::with_phases(1.0, 440.0, vec![0.0, quarter]);
+    ///
+    /// let mut samples = vec![0.0; 512];
+    /// sin.read(&mut samples);
+    ///
+    /// // The channels are in quadrature: sin² + cos² = 1.
+    /// for frame in samples.chunks_exact(2) {
+    ///     assert!((frame[0] * frame[0] + frame[1] * frame[1] - 1.0).abs() < 1e-4);
+    /// }
+    /// ```
+    pub fn with_phases(amplitude: f32, frequency: f32, phases: Vec<f32>) -> Self {
+        SineWave {
+            amplitude,
+            format: AudioFormat::default(),
+            phase: 0.0,
+            phases: Some(phases),
+            frequency,
+        }
+    }
+
+    /// Set the frequency of the wave, in Hz.
+    ///
+    /// The wave continues from its current phase, so retuning doesn't click.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, AudioFormat, AudioSource};
+    /// # use std::f32::consts::PI;
+    /// let mut sin = SineWave::with_format(AudioFormat::MONO_CD, 1.0, 440.0);
+    /// let mut samples = vec![0.0; 100];
+    /// sin.read(&mut samples);
+    ///
+    /// sin.set_frequency(880.0);
+    /// assert_eq!(sin.frequency(), 880.0);
+    /// sin.read(&mut samples);
+    ///
+    /// // The next sample continues from where 440 Hz left off, then advances at 880 Hz.
+    /// let phase = 100.0 * 2.0 * PI * 440.0 / 44100.0;
+    /// assert!((samples[0] - phase.sin()).abs() < 1e-4);
+    /// assert!((samples[1] - (phase + 2.0 * PI * 880.0 / 44100.0).sin()).abs() < 1e-4);
+    /// ```
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Set the peak value of the samples generated, starting with the next read.
+    ///
+    /// # Examples
+    /// ```
+    /// # use timbre::{generators::SineWave, AudioSource};
+    /// let mut sin = SineWave::new(1.0, 440.0);
+    /// let mut reference = sin.clone();
+    ///
+    /// sin.set_amplitude(0.25);
+    /// assert_eq!(sin.amplitude(), 0.25);
+    ///
+    /// let mut samples = vec![0.0; 256];
+    /// let mut expected = vec![0.0; 256];
+    /// sin.read(&mut samples);
+    /// reference.read(&mut expected);
+    /// for (sample, expected) in samples.iter().zip(expected.iter()) {
+    ///     assert_eq!(*sample, 0.25 * expected);
+    /// }
+    /// ```
+    pub fn set_amplitude(&mut self, amplitude: f32) {
+        self.amplitude = amplitude;
+    }
+
+    pub fn amplitude(&self) -> f32 {
+        self.amplitude
+    }
 }
 
 impl AudioSource for SineWave {
@@ -64,6 +164,21 @@ impl AudioSource for SineWave {
         let channels = self.format.channels as usize;
         let frames = buffer.len() / channels;
 
+        if let Some(phases) = &self.phases {
+            assert_eq!(
+                phases.len(),
+                channels,
+                "SineWave needs one phase per channel."
+            );
+            for frame in buffer.chunks_exact_mut(channels) {
+                for (sample, offset) in frame.iter_mut().zip(phases.iter()) {
+                    *sample = self.amplitude * (self.phase + offset).sin();
+                }
+                self.phase += increment;
+            }
+            return ReadResult::good(buffer.len());
+        }
+
         for i in 0..frames {
             let amplitude = self.amplitude * self.phase.sin();
             for channel in 0..channels as usize {
@@ -75,3 +190,27 @@ impl AudioSource for SineWave {
         ReadResult::good(buffer.len())
     }
 }
+
+impl Latency for SineWave {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for SineWave {}
+
+impl Rewind for SineWave {
+    fn rewind(&mut self) -> bool {
+        self.phase = 0.0;
+        true
+    }
+}
+
+impl Seek for SineWave {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        let period = self.format.sample_rate as f64 / self.frequency as f64;
+        let offset = (frame as f64 % period) / period;
+        self.phase = (std::f64::consts::PI * 2.0 * offset) as f32;
+        frame
+    }
+}
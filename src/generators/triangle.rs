@@ -0,0 +1,110 @@
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample, Tail};
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that generates a triangle wave.
+///
+/// Each period starts at `-amplitude`, ramps linearly up to `amplitude` at
+/// its midpoint, and back down again. The wave has no jumps, so its
+/// harmonics fall off quickly and it aliases far less than a square or
+/// sawtooth wave.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::TriangleWave, AudioFormat, AudioSource};
+/// let format = AudioFormat { channels: 2, sample_rate: 8 };
+/// let mut wave = TriangleWave::with_format(format, 0.5, 1.0);
+///
+/// let mut buffer = vec![0.0; 16];
+/// wave.read(&mut buffer);
+/// assert_eq!(
+///     buffer,
+///     [
+///         -0.5, -0.5, -0.25, -0.25, 0.0, 0.0, 0.25, 0.25,
+///         0.5, 0.5, 0.25, 0.25, 0.0, 0.0, -0.25, -0.25,
+///     ]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct TriangleWave {
+    amplitude: f32,
+    format: AudioFormat,
+    frequency: f32,
+    phase: f32,
+}
+
+impl TriangleWave {
+    /// Construct a new triangle wave generator with the given amplitude and frequency.
+    ///
+    /// Uses [`AudioFormat::default()`](crate::AudioFormat::default) as the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn new(amplitude: f32, frequency: f32) -> Self {
+        TriangleWave::with_format(AudioFormat::default(), amplitude, frequency)
+    }
+
+    /// Construct a new triangle wave generator with the given format, amplitude, and frequency.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` -- The format for the generated stream.
+    /// * `amplitude` -- The peak value of samples generated by the generator.
+    /// * `frequency` -- The frequency of the wave generated, in Hz.
+    pub fn with_format(format: AudioFormat, amplitude: f32, frequency: f32) -> Self {
+        TriangleWave {
+            amplitude,
+            format,
+            frequency,
+            phase: 0.0,
+        }
+    }
+
+    /// Set the frequency of the wave, in Hz.
+    ///
+    /// The wave continues from its current phase, so retuning doesn't click.
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+}
+
+impl AudioSource for TriangleWave {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "TriangleWave::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let increment = self.frequency / self.format.sample_rate as f32;
+
+        let channels = self.format.channels as usize;
+        for frame in buffer.chunks_exact_mut(channels) {
+            let value = self.amplitude * (1.0 - 4.0 * (self.phase - 0.5).abs());
+            frame.iter_mut().for_each(|sample| *sample = value);
+
+            self.phase = (self.phase + increment).fract();
+        }
+
+        ReadResult::good(buffer.len())
+    }
+}
+
+impl Latency for TriangleWave {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for TriangleWave {}
+
+impl Rewind for TriangleWave {
+    fn rewind(&mut self) -> bool {
+        self.phase = 0.0;
+        true
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{AudioFormat, AudioSource, Latency, ReadResult, Rewind, Sample, Seek, Tail};
+use tracing::instrument;
+
+/// An [`AudioSource`](crate::AudioSource) that plays back a `Vec` of samples.
+///
+/// Once every sample has been read, `VecSource` reports
+/// [`Finished`](crate::StreamState::Finished). This is handy for tests and
+/// prototyping, where defining a new `AudioSource` for some known samples
+/// would be boilerplate.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::VecSource, AudioFormat, AudioSource, StreamState};
+/// let mut source = VecSource::new(vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6], AudioFormat::STEREO_CD);
+///
+/// let mut samples = vec![0.0; 4];
+/// let result = source.read(&mut samples);
+/// assert_eq!(result.state, StreamState::Good);
+/// assert_eq!(samples, [0.1, 0.2, 0.3, 0.4]);
+///
+/// let result = source.read(&mut samples);
+/// assert_eq!(result.state, StreamState::Finished);
+/// assert_eq!(result.read, 2);
+/// assert_eq!(samples[..2], [0.5, 0.6]);
+/// ```
+#[derive(Clone)]
+pub struct VecSource {
+    samples: Vec<Sample>,
+    format: AudioFormat,
+    position: usize,
+}
+
+/// An [`AudioSource`](crate::AudioSource) that plays back a borrowed slice of samples.
+///
+/// Like [`VecSource`], but without taking ownership of the samples.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SliceSource, AudioFormat, AudioSource, StreamState};
+/// let data = [0.25; 100];
+/// let mut source = SliceSource::new(&data, AudioFormat::MONO_CD);
+///
+/// let mut samples = vec![0.0; 64];
+/// assert_eq!(source.read(&mut samples).state, StreamState::Good);
+///
+/// let result = source.read(&mut samples);
+/// assert_eq!(result.state, StreamState::Finished);
+/// assert_eq!(result.read, 36);
+/// ```
+#[derive(Clone)]
+pub struct SliceSource<'a> {
+    samples: &'a [Sample],
+    format: AudioFormat,
+    position: usize,
+}
+
+impl VecSource {
+    /// Construct a source that plays back `samples`.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` -- The interleaved samples to play.
+    /// * `format` -- The format of `samples`.
+    ///
+    /// # Panics
+    ///
+    /// If `samples.len()` is not a multiple of `format.channels`.
+    pub fn new(samples: Vec<Sample>, format: AudioFormat) -> Self {
+        assert!(
+            samples.len().is_multiple_of(format.channels as usize),
+            "Samples must contain whole frames."
+        );
+        VecSource {
+            samples,
+            format,
+            position: 0,
+        }
+    }
+
+    /// Consume the source, returning its samples.
+    pub fn into_inner(self) -> Vec<Sample> {
+        self.samples
+    }
+}
+
+impl<'a> SliceSource<'a> {
+    /// Construct a source that plays back `samples`.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` -- The interleaved samples to play.
+    /// * `format` -- The format of `samples`.
+    ///
+    /// # Panics
+    ///
+    /// If `samples.len()` is not a multiple of `format.channels`.
+    pub fn new(samples: &'a [Sample], format: AudioFormat) -> Self {
+        assert!(
+            samples.len().is_multiple_of(format.channels as usize),
+            "Samples must contain whole frames."
+        );
+        SliceSource {
+            samples,
+            format,
+            position: 0,
+        }
+    }
+}
+
+/// Copy from `samples` at `position` into `buffer`, advancing `position`.
+fn read_samples(samples: &[Sample], position: &mut usize, buffer: &mut [Sample]) -> ReadResult {
+    let remaining = samples.len() - *position;
+
+    if buffer.len() <= remaining {
+        buffer.copy_from_slice(&samples[*position..*position + buffer.len()]);
+        *position += buffer.len();
+        ReadResult::good(buffer.len())
+    } else {
+        buffer[..remaining].copy_from_slice(&samples[*position..]);
+        *position = samples.len();
+        ReadResult::finished(remaining)
+    }
+}
+
+/// Move `position` to `frame`, returning the frame actually seeked to.
+fn seek_samples(samples: &[Sample], format: AudioFormat, position: &mut usize, frame: u64) -> u64 {
+    let channels = format.channels as usize;
+    let frame = std::cmp::min(frame, (samples.len() / channels) as u64);
+    *position = frame as usize * channels;
+    frame
+}
+
+impl AudioSource for VecSource {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "VecSource::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        read_samples(&self.samples, &mut self.position, buffer)
+    }
+}
+
+impl<'a> AudioSource for SliceSource<'a> {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "SliceSource::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        read_samples(self.samples, &mut self.position, buffer)
+    }
+}
+
+impl Latency for VecSource {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl Tail for VecSource {}
+
+impl<'a> Latency for SliceSource<'a> {
+    fn latency_frames(&self) -> u64 {
+        0
+    }
+}
+
+impl<'a> Tail for SliceSource<'a> {}
+
+impl Rewind for VecSource {
+    fn rewind(&mut self) -> bool {
+        self.position = 0;
+        true
+    }
+}
+
+impl<'a> Rewind for SliceSource<'a> {
+    fn rewind(&mut self) -> bool {
+        self.position = 0;
+        true
+    }
+}
+
+impl Seek for VecSource {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        seek_samples(&self.samples, self.format, &mut self.position, frame)
+    }
+}
+
+impl<'a> Seek for SliceSource<'a> {
+    fn seek_frame(&mut self, frame: u64) -> u64 {
+        seek_samples(self.samples, self.format, &mut self.position, frame)
+    }
+}
@@ -4,12 +4,20 @@ mod core;
 pub use crate::core::*;
 mod error;
 pub use crate::error::*;
+mod loudness;
+pub use crate::loudness::*;
+mod polarity;
+pub use crate::polarity::*;
+mod ring_buffer;
+pub use crate::ring_buffer::*;
 
 pub mod decoders;
 pub mod drivers;
 pub mod effects;
 pub mod generators;
+pub mod sinks;
 
 pub mod prelude;
 
+#[cfg(feature = "sdl2")]
 mod sdl_util;
@@ -0,0 +1,282 @@
+use crate::{
+    db_to_linear,
+    effects::{
+        biquad::{filter, Coefficients},
+        Gain,
+    },
+    render, render_frames, AudioFormat, AudioSource, Rewind, Sample,
+};
+
+use std::time::Duration;
+
+/// The length of the blocks loudness is measured over, in seconds.
+const BLOCK_SECONDS: f64 = 0.4;
+/// How far each block starts after the previous one, in seconds, for 75% overlap.
+const HOP_SECONDS: f64 = 0.1;
+/// Blocks quieter than this, in LUFS, are ignored as silence.
+const ABSOLUTE_GATE: f32 = -70.0;
+/// Blocks quieter than this, relative to the ungated loudness, are ignored.
+const RELATIVE_GATE: f32 = -10.0;
+
+/// Measure the integrated loudness of a finite source, in LUFS.
+///
+/// Loudness is measured as described by ITU-R BS.1770: the signal is
+/// K-weighted to approximate how loud each frequency sounds, then the mean
+/// square level of overlapping 400ms blocks is averaged, ignoring silent and
+/// comparatively quiet blocks. All channels are weighted equally.
+///
+/// The source must be finite; if it never finishes, this never returns.
+///
+/// # Returns
+///
+/// The loudness in LUFS, or negative infinity if the source is silent.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::SineWave, integrated_loudness, render_frames, generators::VecSource, AudioFormat};
+/// // A full scale 1 kHz sine measures -3 LUFS.
+/// let (samples, format) = render_frames(SineWave::with_format(AudioFormat::MONO_DVD, 1.0, 1000.0), 48000);
+/// let loudness = integrated_loudness(VecSource::new(samples, format));
+/// assert!((loudness + 3.01).abs() < 0.1);
+/// ```
+pub fn integrated_loudness<S: AudioSource>(source: S) -> f32 {
+    let (samples, format) = render(source);
+    measure(&samples, format)
+}
+
+/// Render two finite sources, with the louder one turned down so that both
+/// have the same integrated loudness.
+///
+/// This is meant for fair A/B comparisons, e.g. when tuning the parameters
+/// of an effect: a louder signal tends to sound better, whatever else is
+/// different. Only the louder source is changed, so neither is pushed
+/// past full scale. If either source is silent, both are returned unchanged.
+///
+/// Both sources must be finite; if either never finishes, this never returns.
+///
+/// # Returns
+///
+/// The rendered samples of `a` and `b`, in their own formats.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::{SineWave, VecSource}, loudness_match, render_frames, AudioFormat};
+/// let finite = |amplitude| {
+///     let sine = SineWave::with_format(AudioFormat::MONO_CD, amplitude, 1000.0);
+///     let (samples, format) = render_frames(sine, 44100);
+///     VecSource::new(samples, format)
+/// };
+/// let (a, b) = loudness_match(finite(0.8), finite(0.2));
+///
+/// let rms = |samples: &[f32]| {
+///     (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+/// };
+/// assert!((rms(&a) / rms(&b) - 1.0).abs() < 0.01);
+/// assert!((rms(&b) - 0.2 / 2.0f32.sqrt()).abs() < 1e-4);
+/// ```
+pub fn loudness_match<A: AudioSource, B: AudioSource>(a: A, b: B) -> (Vec<Sample>, Vec<Sample>) {
+    let (mut a, a_format) = render(a);
+    let (mut b, b_format) = render(b);
+    let a_loudness = measure(&a, a_format);
+    let b_loudness = measure(&b, b_format);
+    if !a_loudness.is_finite() || !b_loudness.is_finite() {
+        return (a, b);
+    }
+
+    let (louder, difference) = if a_loudness > b_loudness {
+        (&mut a, b_loudness - a_loudness)
+    } else {
+        (&mut b, a_loudness - b_loudness)
+    };
+    let gain = db_to_linear(difference);
+    louder.iter_mut().for_each(|sample| *sample *= gain);
+
+    (a, b)
+}
+
+/// Per-track gains that bring a set of tracks to the same loudness, in the
+/// style of ReplayGain.
+///
+/// Each track is measured once, when the `ReplayGain` is constructed, and
+/// rewound so it can be played from the start. The gains are kept, so the
+/// tracks can then be played any number of times without measuring them again.
+///
+/// Gains are chosen to bring each track to a reference loudness, -18 LUFS by
+/// default as in ReplayGain 2.0. Quiet tracks are turned up, and nothing
+/// prevents their peaks from going past full scale. Silent tracks are left
+/// unchanged.
+///
+/// Tracks must be finite: measuring stops with a panic at
+/// [`MAX_TRACK_DURATION`](ReplayGain::MAX_TRACK_DURATION), rather than
+/// rendering an endless source until memory runs out.
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::{SineWave, VecSource}, integrated_loudness, render_frames, AudioFormat, ReplayGain};
+/// let track = |amplitude| {
+///     let sine = SineWave::with_format(AudioFormat::MONO_CD, amplitude, 1000.0);
+///     let (samples, format) = render_frames(sine, 44100);
+///     VecSource::new(samples, format)
+/// };
+///
+/// let replay_gain = ReplayGain::new(vec![track(0.8), track(0.1)]);
+/// assert!(replay_gain.gains_db()[0] < replay_gain.gains_db()[1]);
+///
+/// // After the gains are applied, both tracks are equally loud.
+/// let loudness: Vec<f32> = replay_gain
+///     .into_sources()
+///     .into_iter()
+///     .map(integrated_loudness)
+///     .collect();
+/// assert!((loudness[0] - loudness[1]).abs() < 0.1);
+/// assert!((loudness[0] - ReplayGain::<VecSource>::REFERENCE_LUFS).abs() < 0.1);
+/// ```
+pub struct ReplayGain<S: AudioSource + Rewind> {
+    tracks: Vec<S>,
+    gains: Vec<f32>,
+}
+
+impl<S: AudioSource + Rewind> ReplayGain<S> {
+    /// The loudness that tracks are brought to by default, in LUFS.
+    pub const REFERENCE_LUFS: f32 = -18.0;
+
+    /// The longest track that can be measured.
+    pub const MAX_TRACK_DURATION: Duration = Duration::from_secs(2 * 60 * 60);
+
+    /// Measure a set of tracks to bring them to the default reference loudness.
+    ///
+    /// # Arguments
+    ///
+    /// * `tracks` -- The tracks to measure.
+    ///
+    /// # Panics
+    ///
+    /// If a track plays for longer than [`MAX_TRACK_DURATION`](ReplayGain::MAX_TRACK_DURATION),
+    /// or can't be rewound after it's measured.
+    pub fn new(tracks: Vec<S>) -> Self {
+        ReplayGain::with_reference(tracks, Self::REFERENCE_LUFS)
+    }
+
+    /// Measure a set of tracks to bring them to the given loudness.
+    ///
+    /// # Arguments
+    ///
+    /// * `tracks` -- The tracks to measure.
+    /// * `reference` -- The loudness to bring each track to, in LUFS.
+    ///
+    /// # Panics
+    ///
+    /// If a track plays for longer than [`MAX_TRACK_DURATION`](ReplayGain::MAX_TRACK_DURATION),
+    /// or can't be rewound after it's measured.
+    pub fn with_reference(mut tracks: Vec<S>, reference: f32) -> Self {
+        let gains = tracks
+            .iter_mut()
+            .map(|track| {
+                // Render one frame past the limit, to tell a track that ends
+                // there from one that goes on.
+                let max_frames = track.format().duration_to_frames(Self::MAX_TRACK_DURATION);
+                let (samples, format) = render_frames(&mut *track, max_frames + 1);
+                assert!(
+                    samples.len() <= format.frames_to_samples(max_frames as usize),
+                    "ReplayGain requires finite tracks, at most {:?} long.",
+                    Self::MAX_TRACK_DURATION
+                );
+                let loudness = measure(&samples, format);
+                assert!(
+                    track.rewind(),
+                    "ReplayGain requires tracks that can rewind."
+                );
+                if loudness.is_finite() {
+                    reference - loudness
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        ReplayGain { tracks, gains }
+    }
+
+    /// Returns the gain for each track, in decibels.
+    pub fn gains_db(&self) -> &[f32] {
+        &self.gains
+    }
+
+    /// Returns the tracks, each wrapped in a [`Gain`](crate::effects::Gain) that applies its gain.
+    pub fn into_sources(self) -> Vec<Gain<S>> {
+        self.tracks
+            .into_iter()
+            .zip(self.gains)
+            .map(|(track, gain)| Gain::with_db(track, gain))
+            .collect()
+    }
+}
+
+/// Measure the integrated loudness of `samples`, in LUFS.
+fn measure(samples: &[Sample], format: AudioFormat) -> f32 {
+    let channels = format.channels as usize;
+    let mut weighted = samples.to_vec();
+    k_weight(&mut weighted, format);
+
+    let frames = weighted.len() / channels;
+    let block = std::cmp::min(
+        (BLOCK_SECONDS * format.sample_rate as f64).round() as usize,
+        frames,
+    );
+    let hop = std::cmp::max(
+        1,
+        (HOP_SECONDS * format.sample_rate as f64).round() as usize,
+    );
+    if block == 0 {
+        return f32::NEG_INFINITY;
+    }
+
+    // The mean square of each block, summed across channels.
+    let powers: Vec<f64> = (0..=(frames - block) / hop)
+        .map(|i| {
+            let start = i * hop * channels;
+            let block = &weighted[start..start + block * channels];
+            let sum: f64 = block.iter().map(|&s| s as f64 * s as f64).sum();
+            sum / (block.len() / channels) as f64
+        })
+        .collect();
+
+    let gated_mean = |threshold: f32| {
+        let gated: Vec<f64> = powers
+            .iter()
+            .copied()
+            .filter(|&power| loudness(power) > threshold)
+            .collect();
+        if gated.is_empty() {
+            None
+        } else {
+            Some(gated.iter().sum::<f64>() / gated.len() as f64)
+        }
+    };
+
+    let relative_gate = match gated_mean(ABSOLUTE_GATE) {
+        Some(power) => loudness(power) + RELATIVE_GATE,
+        None => return f32::NEG_INFINITY,
+    };
+    match gated_mean(relative_gate.max(ABSOLUTE_GATE)) {
+        Some(power) => loudness(power),
+        None => f32::NEG_INFINITY,
+    }
+}
+
+/// Convert a mean square power to loudness in LUFS.
+fn loudness(power: f64) -> f32 {
+    (-0.691 + 10.0 * power.log10()) as f32
+}
+
+/// Apply the K-weighting filter of ITU-R BS.1770: a high shelf that models
+/// the acoustic effect of the head, followed by a high-pass filter.
+fn k_weight(samples: &mut [Sample], format: AudioFormat) {
+    let channels = format.channels as usize;
+    // The standard only gives coefficients for 48 kHz. These cookbook filters
+    // match its response closely at any sample rate.
+    let shelf = Coefficients::high_shelf(format.sample_rate, 1500.0, 4.0);
+    filter(samples, &mut vec![[0.0; 2]; channels], &shelf);
+    let high_pass = Coefficients::high_pass(format.sample_rate, 38.0, 0.5);
+    filter(samples, &mut vec![[0.0; 2]; channels], &high_pass);
+}
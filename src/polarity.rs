@@ -0,0 +1,78 @@
+use crate::{render, AudioFormat, AudioSource, Sample};
+
+/// The result of comparing the polarity of two sources with [`polarity_check`](crate::polarity_check).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PolarityCheck {
+    /// The correlation between the mono sums of the two sources.
+    ///
+    /// This ranges from 1.0, if one is the other at a different volume, to
+    /// -1.0, if one is the other inverted. Unrelated material is near 0.0.
+    pub correlation: f32,
+}
+
+impl PolarityCheck {
+    /// Returns true if inverting one of the sources would make them sum more
+    /// loudly to mono, i.e. if they are more out of phase than in phase.
+    pub fn should_invert(&self) -> bool {
+        self.correlation < 0.0
+    }
+}
+
+/// Check whether two finite sources are out of phase with each other.
+///
+/// Each source is summed to mono, and the correlation between the sums is
+/// measured over the length of the shorter one. Negative correlation means
+/// the sources partly cancel when mixed, as with two microphones on the same
+/// instrument where one is wired backwards; flipping one with an
+/// [`Invert`](crate::effects::Invert) makes them reinforce each other instead.
+///
+/// Both sources must be finite; if either never finishes, this never returns.
+///
+/// # Panics
+///
+/// If the sources have different sample rates.
+///
+/// # Examples
+/// ```
+/// # use timbre::{effects::{ChannelSelection, Invert}, generators::{SineWave, VecSource}, polarity_check, render_frames};
+/// let recording = || {
+///     let (samples, format) = render_frames(SineWave::new(0.5, 440.0), 4410);
+///     VecSource::new(samples, format)
+/// };
+///
+/// let check = polarity_check(recording(), recording());
+/// assert!(!check.should_invert());
+/// assert!((check.correlation - 1.0).abs() < 1e-6);
+///
+/// let check = polarity_check(recording(), Invert::new(recording(), ChannelSelection::All));
+/// assert!(check.should_invert());
+/// assert!((check.correlation + 1.0).abs() < 1e-6);
+/// ```
+pub fn polarity_check<A: AudioSource, B: AudioSource>(a: A, b: B) -> PolarityCheck {
+    let (a, a_format) = render(a);
+    let (b, b_format) = render(b);
+    assert_eq!(
+        a_format.sample_rate, b_format.sample_rate,
+        "The sources must have the same sample rate."
+    );
+
+    let (mut ab, mut aa, mut bb) = (0.0f64, 0.0f64, 0.0f64);
+    for (a, b) in mono_sum(&a, a_format).zip(mono_sum(&b, b_format)) {
+        ab += a * b;
+        aa += a * a;
+        bb += b * b;
+    }
+
+    let correlation = if aa == 0.0 || bb == 0.0 {
+        0.0
+    } else {
+        (ab / (aa * bb).sqrt()) as f32
+    };
+    PolarityCheck { correlation }
+}
+
+fn mono_sum(samples: &[Sample], format: AudioFormat) -> impl Iterator<Item = f64> + '_ {
+    samples
+        .chunks_exact(format.channels as usize)
+        .map(|frame| frame.iter().map(|&s| s as f64).sum())
+}
@@ -1,3 +1,3 @@
 //! Exports commonly-used traits.
 
-pub use crate::{AudioSource, IntoShared};
+pub use crate::{AudioSink, AudioSource, IntoShared, Rewind, Seek, Seekable};
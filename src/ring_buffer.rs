@@ -0,0 +1,252 @@
+use crate::{AudioFormat, AudioSink, AudioSource, Latency, ReadResult, Sample, Tail, WriteResult};
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+    Arc,
+};
+use tracing::instrument;
+
+/// The state shared by both halves of a ring buffer.
+///
+/// `head` and `tail` are the positions of the next sample to read and write.
+/// They count up to twice the capacity before wrapping, so that a full buffer
+/// can be told apart from an empty one. Only the consumer stores `head` and
+/// only the producer stores `tail`.
+struct Shared {
+    samples: Box<[AtomicU32]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    producer_dropped: AtomicBool,
+    consumer_dropped: AtomicBool,
+    format: AudioFormat,
+}
+
+impl Shared {
+    /// Returns the number of samples buffered between `head` and `tail`.
+    fn len(&self, head: usize, tail: usize) -> usize {
+        let wrap = 2 * self.samples.len();
+        (tail + wrap - head) % wrap
+    }
+
+    /// Returns the position `count` samples after `position`.
+    fn advance(&self, position: usize, count: usize) -> usize {
+        (position + count) % (2 * self.samples.len())
+    }
+
+    fn slot(&self, position: usize) -> &AtomicU32 {
+        &self.samples[position % self.samples.len()]
+    }
+}
+
+/// Create a lock-free ring buffer for passing samples from one thread to another.
+///
+/// Returns the two halves: a [`Producer`](crate::Producer) that writes samples,
+/// e.g. on a decoding thread, and a [`RingSource`](crate::RingSource) that
+/// plays them. Neither half ever blocks or locks, so both are safe to use on a
+/// realtime audio thread. Unlike [`ChannelSource`](crate::generators::ChannelSource),
+/// nothing is allocated after the buffer is created.
+///
+/// # Arguments
+///
+/// * `capacity` -- The most frames the buffer can hold.
+/// * `format` -- The format of the samples passed through the buffer.
+///
+/// # Panics
+///
+/// If `capacity` is zero.
+///
+/// # Examples
+/// ```
+/// # use timbre::{ring_buffer, AudioFormat, AudioSource, StreamState};
+/// let (mut producer, mut source) = ring_buffer(4, AudioFormat::STEREO_CD);
+///
+/// // Only as many whole frames as fit are written.
+/// assert_eq!(producer.push(&[0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]), 8);
+///
+/// let mut samples = vec![0.0; 6];
+/// assert_eq!(source.read(&mut samples).state, StreamState::Good);
+/// assert_eq!(samples, [0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+///
+/// // A buffer that ends partway through a frame gets the whole frames that fit.
+/// let result = source.read(&mut samples[..3]);
+/// assert_eq!((result.state, result.read), (StreamState::Good, 2));
+/// assert_eq!(samples[..2], [0.7, 0.8]);
+///
+/// // Reading an empty buffer underruns, until the producer is dropped.
+/// assert_eq!(source.read(&mut samples).state, StreamState::Underrun);
+/// drop(producer);
+/// assert_eq!(source.read(&mut samples).state, StreamState::Finished);
+/// ```
+pub fn ring_buffer(capacity: usize, format: AudioFormat) -> (Producer, RingSource) {
+    assert!(
+        capacity > 0,
+        "The ring buffer must hold at least one frame."
+    );
+
+    let samples = (0..format.frames_to_samples(capacity))
+        .map(|_| AtomicU32::new(0))
+        .collect();
+    let shared = Arc::new(Shared {
+        samples,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        producer_dropped: AtomicBool::new(false),
+        consumer_dropped: AtomicBool::new(false),
+        format,
+    });
+
+    (
+        Producer {
+            shared: Arc::clone(&shared),
+        },
+        RingSource { shared },
+    )
+}
+
+/// The writing half of a [`ring_buffer`](crate::ring_buffer).
+///
+/// Once the producer is dropped, the [`RingSource`](crate::RingSource)
+/// finishes after playing what's left in the buffer.
+pub struct Producer {
+    shared: Arc<Shared>,
+}
+
+impl Producer {
+    /// Write as many whole frames from `samples` as there's room for.
+    ///
+    /// # Returns
+    ///
+    /// The number of samples written.
+    pub fn push(&mut self, samples: &[Sample]) -> usize {
+        let shared = &*self.shared;
+        let channels = shared.format.channels as usize;
+        // Acquire the reader's position so that the samples it has read are
+        // done with before they're overwritten.
+        let head = shared.head.load(Ordering::Acquire);
+        let tail = shared.tail.load(Ordering::Relaxed);
+
+        let free = shared.samples.len() - shared.len(head, tail);
+        let count = std::cmp::min(free, samples.len());
+        let count = count - count % channels;
+        for (offset, sample) in samples[..count].iter().enumerate() {
+            shared
+                .slot(tail + offset)
+                .store(sample.to_bits(), Ordering::Relaxed);
+        }
+
+        shared
+            .tail
+            .store(shared.advance(tail, count), Ordering::Release);
+        count
+    }
+
+    /// Returns the number of samples that can be written without overflowing.
+    pub fn free(&self) -> usize {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Acquire);
+        let tail = shared.tail.load(Ordering::Relaxed);
+        shared.samples.len() - shared.len(head, tail)
+    }
+
+    /// Returns true if the [`RingSource`](crate::RingSource) has been dropped,
+    /// so nothing written will ever be played.
+    pub fn is_abandoned(&self) -> bool {
+        self.shared.consumer_dropped.load(Ordering::Acquire)
+    }
+}
+
+impl AudioSink for Producer {
+    fn format(&self) -> AudioFormat {
+        self.shared.format
+    }
+
+    #[instrument(name = "Producer::write", skip(self, buffer))]
+    fn write(&mut self, buffer: &[Sample]) -> WriteResult {
+        if self.is_abandoned() {
+            return WriteResult::closed(0);
+        }
+
+        let written = self.push(buffer);
+        if written == buffer.len() {
+            WriteResult::good(written)
+        } else {
+            WriteResult::full(written)
+        }
+    }
+}
+
+impl Drop for Producer {
+    fn drop(&mut self) {
+        self.shared.producer_dropped.store(true, Ordering::Release);
+    }
+}
+
+/// The reading half of a [`ring_buffer`](crate::ring_buffer), which plays the
+/// samples written by the [`Producer`](crate::Producer).
+///
+/// If the buffer runs dry, the read is an [`Underrun`](crate::StreamState::Underrun).
+/// Once the producer has been dropped and the buffer is empty, it's
+/// [`Finished`](crate::StreamState::Finished).
+pub struct RingSource {
+    shared: Arc<Shared>,
+}
+
+impl RingSource {
+    /// Returns the number of samples that have been written but not yet played.
+    pub fn pending(&self) -> usize {
+        let shared = &*self.shared;
+        let tail = shared.tail.load(Ordering::Acquire);
+        let head = shared.head.load(Ordering::Relaxed);
+        shared.len(head, tail)
+    }
+}
+
+impl AudioSource for RingSource {
+    fn format(&self) -> AudioFormat {
+        self.shared.format
+    }
+
+    #[instrument(name = "RingSource::read", skip(self, buffer))]
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let shared = &*self.shared;
+        let channels = shared.format.channels as usize;
+        // Check for the producer first: everything it wrote before it was
+        // dropped is then visible below.
+        let producer_dropped = shared.producer_dropped.load(Ordering::Acquire);
+        let tail = shared.tail.load(Ordering::Acquire);
+        let head = shared.head.load(Ordering::Relaxed);
+
+        let whole = buffer.len() - buffer.len() % channels;
+        let available = std::cmp::min(shared.len(head, tail), whole);
+        let read = available - available % channels;
+        for (offset, sample) in buffer[..read].iter_mut().enumerate() {
+            *sample = Sample::from_bits(shared.slot(head + offset).load(Ordering::Relaxed));
+        }
+
+        shared
+            .head
+            .store(shared.advance(head, read), Ordering::Release);
+
+        if read == whole {
+            ReadResult::good(read)
+        } else if producer_dropped {
+            ReadResult::finished(read)
+        } else {
+            ReadResult::underrun(read)
+        }
+    }
+}
+
+impl Latency for RingSource {
+    fn latency_frames(&self) -> u64 {
+        self.shared.format.samples_to_frames(self.pending()) as u64
+    }
+}
+
+impl Tail for RingSource {}
+
+impl Drop for RingSource {
+    fn drop(&mut self) {
+        self.shared.consumer_dropped.store(true, Ordering::Release);
+    }
+}
@@ -0,0 +1,7 @@
+//! [`AudioSink`](crate::AudioSink) implementations that consume pushed audio.
+
+mod channel_sink;
+mod wav_encoder;
+
+pub use channel_sink::ChannelSink;
+pub use wav_encoder::WavEncoder;
@@ -0,0 +1,61 @@
+use crate::{AudioFormat, AudioSink, Sample, WriteResult};
+use std::sync::mpsc::Sender;
+use tracing::instrument;
+
+/// An [`AudioSink`](crate::AudioSink) that sends the samples written to it to another thread.
+///
+/// Each write sends one chunk of interleaved samples through a
+/// [`std::sync::mpsc`] channel, so it never blocks. This is the counterpart to
+/// [`ChannelSource`](crate::generators::ChannelSource): connecting the two
+/// with a channel hands audio from one thread to another. Once the receiver is
+/// dropped, writes are [`Closed`](crate::SinkState::Closed).
+///
+/// # Examples
+/// ```
+/// # use timbre::{generators::ChannelSource, sinks::ChannelSink, AudioFormat, AudioSink, AudioSource, SinkState};
+/// let (sender, receiver) = std::sync::mpsc::channel();
+/// let mut sink = ChannelSink::new(sender, AudioFormat::MONO_CD);
+/// let mut source = ChannelSource::new(receiver, AudioFormat::MONO_CD);
+///
+/// assert_eq!(sink.write(&[0.1, 0.2, 0.3]).written, 3);
+/// let mut samples = vec![0.0; 3];
+/// source.read(&mut samples);
+/// assert_eq!(samples, [0.1, 0.2, 0.3]);
+///
+/// drop(source);
+/// assert_eq!(sink.write(&[0.4]).state, SinkState::Closed);
+/// ```
+pub struct ChannelSink {
+    sender: Sender<Vec<Sample>>,
+    format: AudioFormat,
+}
+
+impl ChannelSink {
+    /// Construct a sink that sends the samples written to it to `sender`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` -- The sending end of a channel of interleaved samples.
+    /// * `format` -- The format of the samples written.
+    pub fn new(sender: Sender<Vec<Sample>>, format: AudioFormat) -> Self {
+        ChannelSink { sender, format }
+    }
+}
+
+impl AudioSink for ChannelSink {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "ChannelSink::write", skip(self, buffer))]
+    fn write(&mut self, buffer: &[Sample]) -> WriteResult {
+        if buffer.is_empty() {
+            return WriteResult::good(0);
+        }
+
+        match self.sender.send(buffer.to_vec()) {
+            Ok(()) => WriteResult::good(buffer.len()),
+            Err(_) => WriteResult::closed(0),
+        }
+    }
+}
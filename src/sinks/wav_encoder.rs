@@ -0,0 +1,182 @@
+use crate::{AudioFormat, AudioSink, Error, Sample, WriteResult};
+
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+};
+use tracing::{instrument, warn};
+
+/// The size of the RIFF header, `fmt ` chunk and `data` chunk header, in bytes.
+const HEADER_BYTES: u32 = 44;
+/// The format tag for IEEE float samples.
+const IEEE_FLOAT: u16 = 3;
+/// The size of each sample, in bytes.
+const SAMPLE_BYTES: u16 = 4;
+
+/// An [`AudioSink`](crate::AudioSink) that encodes the samples written to it as a WAV file.
+///
+/// Samples are written as 32-bit IEEE float, so they're stored exactly, even
+/// beyond full scale, and can be read back with
+/// [`WavDecoder`](crate::decoders::WavDecoder). The lengths in the header are
+/// filled in by [`finish`](WavEncoder::finish), or on a best-effort basis when
+/// the encoder is dropped.
+///
+/// If writing fails, or the file would grow past the 4 GiB that WAV allows,
+/// writes are [`Closed`](crate::SinkState::Closed) and the error is returned
+/// by `finish`.
+///
+/// # Examples
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use timbre::{decoders::WavDecoder, generators::Ramp, pump, render, sinks::WavEncoder, AudioFormat};
+/// use std::{io::Cursor, time::Duration};
+///
+/// let ramp = || Ramp::finite(-1.0, 1.0, Duration::from_millis(10), AudioFormat::MONO_CD);
+/// let mut encoder = WavEncoder::new(Cursor::new(Vec::new()), AudioFormat::MONO_CD)?;
+/// pump(ramp(), &mut encoder);
+/// let bytes = encoder.finish()?.into_inner();
+///
+/// let decoder = WavDecoder::from_bytes(&bytes)?;
+/// assert_eq!(render(decoder), render(ramp()));
+/// # Ok(())
+/// # }
+/// ```
+pub struct WavEncoder<W: Write + Seek> {
+    writer: Option<W>,
+    format: AudioFormat,
+    data_bytes: u32,
+    error: Option<Error>,
+    bytes: Vec<u8>,
+}
+
+impl WavEncoder<BufWriter<File>> {
+    /// Construct a WavEncoder that writes to a new file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be created or the header can't be written, will
+    /// return the underlying I/O error.
+    #[instrument(name = "WavEncoder::create")]
+    pub fn create(path: &str, format: AudioFormat) -> Result<Self, Error> {
+        WavEncoder::new(BufWriter::new(File::create(path)?), format)
+    }
+}
+
+impl<W: Write + Seek> WavEncoder<W> {
+    /// Construct a WavEncoder that writes to `writer`, starting with the header.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` -- Where to write the file.
+    /// * `format` -- The format of the samples that will be written.
+    ///
+    /// # Errors
+    ///
+    /// If the header can't be written, will return the underlying I/O error.
+    #[instrument(name = "WavEncoder::new", skip(writer))]
+    pub fn new(mut writer: W, format: AudioFormat) -> Result<Self, Error> {
+        writer.write_all(&header(format, 0))?;
+        Ok(WavEncoder {
+            writer: Some(writer),
+            format,
+            data_bytes: 0,
+            error: None,
+            bytes: Vec::new(),
+        })
+    }
+
+    /// Fill in the lengths in the header, and return the writer.
+    ///
+    /// # Errors
+    ///
+    /// If an earlier write failed, or the file grew too large, will return
+    /// that error. Otherwise, returns the I/O error from updating the header,
+    /// if any.
+    pub fn finish(mut self) -> Result<W, Error> {
+        let mut writer = self.writer.take().unwrap();
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        update_header(&mut writer, self.format, self.data_bytes)?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write + Seek> AudioSink for WavEncoder<W> {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[instrument(name = "WavEncoder::write", skip(self, buffer))]
+    fn write(&mut self, buffer: &[Sample]) -> WriteResult {
+        if self.error.is_some() {
+            return WriteResult::closed(0);
+        }
+
+        let bytes = (buffer.len() * SAMPLE_BYTES as usize) as u64;
+        if self.data_bytes as u64 + bytes > (u32::MAX - HEADER_BYTES) as u64 {
+            self.error = Some(Error::UnsupportedFormat(
+                "WAV files can't be larger than 4 GiB.".to_string(),
+            ));
+            return WriteResult::closed(0);
+        }
+
+        self.bytes.clear();
+        self.bytes
+            .extend(buffer.iter().flat_map(|sample| sample.to_le_bytes()));
+        let writer = self.writer.as_mut().unwrap();
+        if let Err(error) = writer.write_all(&self.bytes) {
+            self.error = Some(error.into());
+            return WriteResult::closed(0);
+        }
+
+        self.data_bytes += bytes as u32;
+        WriteResult::good(buffer.len())
+    }
+}
+
+impl<W: Write + Seek> Drop for WavEncoder<W> {
+    fn drop(&mut self) {
+        if let Some(writer) = &mut self.writer {
+            if let Err(error) = update_header(writer, self.format, self.data_bytes) {
+                warn!("Failed to finish WAV file: {}", error);
+            }
+        }
+    }
+}
+
+/// Returns the header of a WAV file holding `data_bytes` bytes of samples.
+fn header(format: AudioFormat, data_bytes: u32) -> Vec<u8> {
+    let channels = format.channels as u16;
+    let block_align = channels * SAMPLE_BYTES;
+
+    let mut header = Vec::with_capacity(HEADER_BYTES as usize);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&(HEADER_BYTES - 8 + data_bytes).to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&IEEE_FLOAT.to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&format.sample_rate.to_le_bytes());
+    header.extend_from_slice(&(format.sample_rate * block_align as u32).to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&(SAMPLE_BYTES * 8).to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_bytes.to_le_bytes());
+    header
+}
+
+/// Rewrite the header with the final lengths, leaving the writer at the end.
+fn update_header<W: Write + Seek>(
+    writer: &mut W,
+    format: AudioFormat,
+    data_bytes: u32,
+) -> Result<(), Error> {
+    writer.seek(SeekFrom::Start(0))?;
+    writer.write_all(&header(format, data_bytes))?;
+    writer.seek(SeekFrom::End(0))?;
+    writer.flush()?;
+    Ok(())
+}
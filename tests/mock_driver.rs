@@ -0,0 +1,312 @@
+//! Runs effect graphs through `MockDriver` instead of a real device.
+//!
+//! Run with `cargo test --features testing --test mock_driver`.
+
+use std::time::Duration;
+use timbre::{
+    drivers::{MockDriver, UnderrunStrategy},
+    effects::{BasicMixer, Echo, HighPass, LowPass},
+    generators::{SineWave, VecSource},
+    AudioFormat, AudioSource, IntoShared, ReadResult, Sample, SharedAudioSource,
+};
+
+/// The graph from `examples/effects.rs`, with tones standing in for the music tracks.
+fn effects_example() -> SharedAudioSource {
+    let track1 = SineWave::new(0.5, 220.0);
+    let track2 = SineWave::new(0.5, 5000.0);
+
+    let low_pass = LowPass::new(track1.into_shared(), 300.0);
+    let high_pass = HighPass::new(track2.into_shared(), 4000.0);
+
+    let mut mixer = BasicMixer::new();
+    mixer.add_source(low_pass.into_shared());
+    mixer.add_source(high_pass.into_shared());
+
+    Echo::new(mixer.into_shared(), Duration::from_secs_f32(0.5), 0.7).into_shared()
+}
+
+#[test]
+fn effects_example_is_deterministic() {
+    let mut first = MockDriver::new(AudioFormat::STEREO_CD, 1024);
+    first.set_source(effects_example());
+    first.pump(64);
+
+    let mut second = MockDriver::new(AudioFormat::STEREO_CD, 1024);
+    second.set_source(effects_example());
+    second.pump(64);
+
+    assert_eq!(first.output().len(), 64 * 1024 * 2);
+    assert!(first.output().iter().all(|sample| sample.is_finite()));
+    assert_eq!(first.output(), second.output());
+    assert_eq!(first.underruns(), 0);
+}
+
+#[test]
+fn echo_repeats_after_delay() {
+    let mut samples = vec![0.0; 2 * 2 * 44100];
+    samples[0] = 1.0;
+    samples[1] = 1.0;
+    let impulse = VecSource::new(samples, AudioFormat::STEREO_CD);
+
+    let mut driver = MockDriver::new(AudioFormat::STEREO_CD, 441);
+    driver.set_source(Echo::new(impulse, Duration::from_millis(500), 0.7).into_shared());
+    driver.pump(200);
+
+    let output = driver.take_output();
+    let delay = 2 * 22050;
+    assert_eq!(output[0], 1.0);
+    assert!((output[delay] - 0.7).abs() < 1e-6);
+    assert!((output[2 * delay] - 0.49).abs() < 1e-6);
+    assert!(output
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % delay > 1)
+        .all(|(_, &sample)| sample == 0.0));
+}
+
+#[test]
+fn finished_source_plays_silence() {
+    let source = VecSource::new(vec![0.5; 100], AudioFormat::MONO_CD);
+
+    let mut driver = MockDriver::new(AudioFormat::MONO_CD, 64);
+    driver.set_source(source.into_shared());
+    driver.pump(3);
+
+    let output = driver.output();
+    assert_eq!(output.len(), 192);
+    assert!(output[..100].iter().all(|&sample| sample == 0.5));
+    assert!(output[100..].iter().all(|&sample| sample == 0.0));
+}
+
+/// A stereo source that counts up, but only provides half of every second block.
+struct Glitchy {
+    next: f32,
+    reads: usize,
+}
+
+impl AudioSource for Glitchy {
+    fn format(&self) -> AudioFormat {
+        AudioFormat::STEREO_CD
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.reads += 1;
+        let available = if self.reads.is_multiple_of(2) {
+            buffer.len() / 2
+        } else {
+            buffer.len()
+        };
+
+        for frame in buffer[..available].chunks_exact_mut(2) {
+            self.next += 1.0;
+            frame[0] = self.next;
+            frame[1] = -self.next;
+        }
+
+        if available == buffer.len() {
+            ReadResult::good(available)
+        } else {
+            ReadResult::underrun(available)
+        }
+    }
+}
+
+#[test]
+fn underrun_fills_with_silence_by_default() {
+    let mut driver = MockDriver::new(AudioFormat::STEREO_CD, 64);
+    driver.set_source(
+        Glitchy {
+            next: 0.0,
+            reads: 0,
+        }
+        .into_shared(),
+    );
+    driver.pump(2);
+
+    assert_eq!(driver.underruns(), 1);
+    assert!(driver.output()[128 + 64..].iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn underrun_holds_last_sample() {
+    let mut driver = MockDriver::new(AudioFormat::STEREO_CD, 64);
+    driver.set_underrun_strategy(UnderrunStrategy::HoldLast);
+    driver.set_source(
+        Glitchy {
+            next: 0.0,
+            reads: 0,
+        }
+        .into_shared(),
+    );
+    driver.pump(3);
+
+    let output = driver.output();
+    assert_eq!(driver.underruns(), 1);
+
+    // The second block has 32 valid frames, counting up from 65.
+    let last = &output[128 + 62..128 + 64];
+    assert_eq!(last, [96.0, -96.0]);
+    for frame in output[128 + 64..256].chunks_exact(2) {
+        assert_eq!(frame, last);
+    }
+
+    // The third block is read normally.
+    assert_eq!(output[256..258], [97.0, -97.0]);
+}
+
+#[test]
+fn crossfade_blends_old_source_into_new() {
+    let old = VecSource::new(vec![1.0; 1000], AudioFormat::MONO_CD).into_shared();
+    let new = VecSource::new(vec![-1.0; 1000], AudioFormat::MONO_CD).into_shared();
+
+    let mut driver = MockDriver::new(AudioFormat::MONO_CD, 64);
+    driver.set_source(old.clone());
+    driver.pump(1);
+    driver.crossfade_to_source(new, Duration::from_secs_f64(100.0 / 44100.0));
+
+    // The old source stays alive, and keeps being read, until the fade completes.
+    driver.pump(1);
+    assert_eq!(std::sync::Arc::strong_count(&old), 2);
+    driver.pump(2);
+    assert_eq!(std::sync::Arc::strong_count(&old), 1);
+
+    let output = driver.take_output();
+    assert!(output[..64].iter().all(|&sample| sample == 1.0));
+
+    // Rather than cutting, the output ramps steadily from the old source to the new.
+    let fade = &output[64..164];
+    for (i, &sample) in fade.iter().enumerate() {
+        let expected = 1.0 - 2.0 * (i + 1) as f32 / 100.0;
+        assert!((sample - expected).abs() < 1e-5, "sample {}: {}", i, sample);
+    }
+    assert!(fade.windows(2).all(|pair| pair[1] < pair[0]));
+    assert!(output[164..].iter().all(|&sample| sample == -1.0));
+}
+
+#[test]
+fn output_clipper_saturates_peaks_smoothly() {
+    let input: Vec<Sample> = (-300..=300).map(|i| i as f32 / 100.0).collect();
+    let pump = |clipper| {
+        let source = VecSource::new(input.clone(), AudioFormat::MONO_CD);
+        let mut driver = MockDriver::new(AudioFormat::MONO_CD, input.len());
+        driver.set_output_clipper(clipper);
+        driver.set_source(source.into_shared());
+        driver.pump(1);
+        driver.take_output()
+    };
+
+    // Disabled, the output is bit-exact, even beyond full scale.
+    assert_eq!(pump(false), input);
+
+    let clipped = pump(true);
+    for (&x, &y) in input.iter().zip(&clipped) {
+        if x.abs() <= 0.8 {
+            assert_eq!(y, x);
+        } else {
+            assert!(y.abs() < x.abs() && y.abs() <= 1.0, "{} -> {}", x, y);
+        }
+    }
+
+    // The curve is smooth: no jumps, and it keeps rising toward full scale.
+    assert!(clipped.windows(2).all(|pair| pair[1] >= pair[0]));
+    assert!(clipped
+        .windows(2)
+        .all(|pair| pair[1] - pair[0] <= 0.01 + 1e-6));
+    assert!(clipped[clipped.len() - 1] > 0.99);
+}
+
+#[test]
+fn pause_and_resume_with_fade_ramp_smoothly() {
+    let source = VecSource::new(vec![0.5; 1000], AudioFormat::MONO_CD).into_shared();
+    let fade = Duration::from_secs_f64(100.0 / 44100.0);
+
+    let mut driver = MockDriver::new(AudioFormat::MONO_CD, 50);
+    driver.set_source(source.clone());
+    driver.pump(1);
+    driver.pause_with_fade(fade);
+    driver.pump(4);
+
+    // The volume ramps down to silence over exactly the fade, then the source is left alone.
+    let output = driver.take_output();
+    assert!(output[..50].iter().all(|&sample| sample == 0.5));
+    let fade_out = &output[50..150];
+    assert!(fade_out.windows(2).all(|pair| pair[1] < pair[0]));
+    assert!(fade_out[0] > 0.49 && fade_out[98] > 0.0);
+    assert!(fade_out[99].abs() < 1e-6);
+    assert!(output[150..].iter().all(|&sample| sample == 0.0));
+    let mut next = [0.0];
+    source.lock().unwrap().read(&mut next);
+    assert_eq!(next, [0.5]);
+
+    driver.resume_with_fade(fade);
+    driver.pump(3);
+
+    // It ramps back up to full volume over the same duration.
+    let output = driver.take_output();
+    let fade_in = &output[..100];
+    assert!(fade_in.windows(2).all(|pair| pair[1] > pair[0]));
+    assert!(fade_in[0] > 0.0 && fade_in[0] < 0.01);
+    assert!((fade_in[99] - 0.5).abs() < 1e-6);
+    assert!(output[100..]
+        .iter()
+        .all(|&sample| (sample - 0.5).abs() < 1e-6));
+}
+
+#[test]
+fn disconnected_device_stops_until_reopened() {
+    let ramp: Vec<Sample> = (0..1000).map(|i| i as f32).collect();
+    let source = VecSource::new(ramp, AudioFormat::MONO_CD);
+
+    let mut driver = MockDriver::new(AudioFormat::MONO_CD, 100);
+    driver.set_underrun_strategy(UnderrunStrategy::HoldLast);
+    driver.set_source(source.into_shared());
+    driver.pump(1);
+    assert!(!driver.is_disconnected());
+
+    // While disconnected, nothing is output and the source isn't read.
+    driver.disconnect();
+    assert!(driver.is_disconnected());
+    driver.pump(3);
+    assert_eq!(driver.output().len(), 100);
+
+    // Reopening picks up the same source, with the same settings, where it left off.
+    driver.reopen();
+    assert!(!driver.is_disconnected());
+    assert_eq!(driver.underrun_strategy(), UnderrunStrategy::HoldLast);
+    driver.pump(1);
+    let output = driver.take_output();
+    assert_eq!(output.len(), 200);
+    assert!(output
+        .iter()
+        .enumerate()
+        .all(|(i, &sample)| sample == i as f32));
+}
+
+#[test]
+fn set_and_clear_source_cut_off_a_crossfade() {
+    let constant = |value| VecSource::new(vec![value; 1000], AudioFormat::MONO_CD).into_shared();
+    let fade = Duration::from_secs_f64(200.0 / 44100.0);
+
+    // Setting a source mid-fade drops the outgoing one at once.
+    let old = constant(1.0);
+    let mut driver = MockDriver::new(AudioFormat::MONO_CD, 50);
+    driver.set_source(old.clone());
+    driver.crossfade_to_source(constant(-1.0), fade);
+    driver.pump(1);
+    assert_eq!(std::sync::Arc::strong_count(&old), 2);
+    driver.set_source(constant(0.5));
+    assert_eq!(std::sync::Arc::strong_count(&old), 1);
+    driver.pump(1);
+    assert!(driver.output()[50..].iter().all(|&sample| sample == 0.5));
+
+    // Clearing the source mid-fade silences both.
+    let old = constant(1.0);
+    let mut driver = MockDriver::new(AudioFormat::MONO_CD, 50);
+    driver.set_source(old.clone());
+    driver.crossfade_to_source(constant(-1.0), fade);
+    driver.pump(1);
+    driver.clear_source();
+    assert_eq!(std::sync::Arc::strong_count(&old), 1);
+    driver.pump(1);
+    assert!(driver.output()[50..].iter().all(|&sample| sample == 0.0));
+}
@@ -0,0 +1,780 @@
+//! Exercises the parts of timbre that don't depend on SDL2.
+//!
+//! Run with `cargo test --no-default-features --test no_sdl` to check that
+//! decoders, effects, and generators work without the `sdl2` feature.
+
+use std::{io::Cursor, time::Duration};
+use timbre::{
+    decoders::{WavDecoder, WavStreamDecoder},
+    drivers::{adapt, PullOutput},
+    effects::{BasicMixer, Echo, Gain, HighPass, LowPass},
+    generators::{Adsr, ChannelSource, Oscillator, SineWave, SynthVoice, VecSource},
+    pump, render, render_frames, render_with_tail, ring_buffer,
+    sinks::{ChannelSink, WavEncoder},
+    AudioFormat, AudioSource, Error, IntoShared, Latency, ReadResult, Sample, StreamState, Tail,
+};
+
+#[test]
+fn render_effect_chain() {
+    let sin = SineWave::with_format(AudioFormat::MONO_CD, 0.5, 440.0);
+    let low_pass = LowPass::new(sin, 1000.0);
+    let echo = Echo::new(low_pass, Duration::from_millis(10), 0.5);
+
+    let (samples, format) = render_frames(echo, 4410);
+    assert_eq!(format, AudioFormat::MONO_CD);
+    assert_eq!(samples.len(), 4410);
+    assert!(samples.iter().any(|&sample| sample != 0.0));
+    assert!(samples.iter().all(|sample| sample.is_finite()));
+}
+
+#[test]
+fn decode_wav() {
+    let decoder = WavDecoder::from_file("./assets/tagged-mono-i16.wav").unwrap();
+    assert_eq!(decoder.metadata().title.as_deref(), Some("Test Tone"));
+
+    let (samples, format) = render(decoder);
+    assert_eq!(format, AudioFormat::MONO_CD);
+    assert_eq!(samples.len(), 4410);
+    assert!(samples.iter().all(|&sample| sample.abs() <= 0.5));
+}
+
+#[test]
+fn decode_truncated_wav() {
+    let bytes = std::fs::read("./assets/stereo-i24.wav").unwrap();
+    match WavDecoder::new(&bytes[..bytes.len() - 100]) {
+        Err(Error::Truncated) => {}
+        Err(error) => panic!("Expected Truncated, got {}", error),
+        Ok(_) => panic!("Expected Truncated, but the file decoded."),
+    }
+
+    // A file cut off in the middle of its header is truncated too.
+    assert!(matches!(
+        WavDecoder::new(&bytes[..30]),
+        Err(Error::Truncated)
+    ));
+}
+
+/// Provides half of what's asked for, then underruns.
+struct HalfFull;
+
+impl AudioSource for HalfFull {
+    fn format(&self) -> AudioFormat {
+        AudioFormat::MONO_CD
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        let available = buffer.len() / 2;
+        buffer[..available].iter_mut().for_each(|s| *s = 0.5);
+        ReadResult::underrun(available)
+    }
+}
+
+/// A stream that delivers a few bytes at a time, like a slow network connection.
+///
+/// The second half of the bytes is held back until `release` receives, so the
+/// reader is sure to run out of data partway through.
+struct Trickle {
+    bytes: Vec<u8>,
+    position: usize,
+    reads: usize,
+    release: Option<std::sync::mpsc::Receiver<()>>,
+}
+
+impl std::io::Read for Trickle {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        self.reads += 1;
+        if self.reads % 5 == 4 {
+            return Err(std::io::ErrorKind::Interrupted.into());
+        }
+
+        let mut end = self.bytes.len();
+        if let Some(release) = &self.release {
+            if self.position < end / 2 {
+                end /= 2;
+            } else {
+                let _ = release.recv();
+                self.release = None;
+            }
+        }
+
+        let count = (self.reads % 7 + 1)
+            .min(buffer.len())
+            .min(end - self.position);
+        buffer[..count].copy_from_slice(&self.bytes[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+#[test]
+fn decode_wav_stream_in_small_pieces() {
+    for path in [
+        "./assets/tagged-mono-i16.wav",
+        "./assets/extensible-stereo-i24.wav",
+    ]
+    .iter()
+    {
+        let (release, held_back) = std::sync::mpsc::channel();
+        let stream = Trickle {
+            bytes: std::fs::read(path).unwrap(),
+            position: 0,
+            reads: 0,
+            release: Some(held_back),
+        };
+        let mut decoder = WavStreamDecoder::new(stream).unwrap();
+        let expected = WavDecoder::from_file(path).unwrap();
+        assert_eq!(decoder.format(), expected.format());
+        assert_eq!(decoder.channel_mask(), expected.channel_mask());
+
+        // The stream can't keep up, so reads underrun until the rest of the
+        // data is released.
+        let mut samples = Vec::new();
+        let mut buffer = vec![0.0; 256 * decoder.format().channels as usize];
+        let mut underruns = 0;
+        loop {
+            let result = decoder.read(&mut buffer);
+            samples.extend_from_slice(&buffer[..result.read]);
+            match result.state {
+                StreamState::Finished => break,
+                StreamState::Underrun => {
+                    underruns += 1;
+                    let _ = release.send(());
+                }
+                StreamState::Good => {}
+            }
+        }
+
+        assert!(underruns > 0);
+        assert_eq!(samples, render(expected).0, "{}", path);
+    }
+}
+
+#[test]
+fn pull_output_fills_silence_on_underrun() {
+    let mut output = PullOutput::new(AudioFormat::MONO_CD);
+    output.set_source(HalfFull.into_shared());
+
+    let mut block = vec![1.0; 128];
+    output.process(&mut block);
+    assert_eq!(output.underruns(), 1);
+    assert!(block[..64].iter().all(|&sample| sample == 0.5));
+    assert!(block[64..].iter().all(|&sample| sample == 0.0));
+
+    // Without a source, the output is silent.
+    output.clear_source();
+    let mut block = vec![1.0; 128];
+    output.process(&mut block);
+    assert!(block.iter().all(|&sample| sample == 0.0));
+}
+
+/// Panics if asked for nothing, to check that effects don't pass empty reads on.
+struct RejectsEmptyReads(SineWave);
+
+impl AudioSource for RejectsEmptyReads {
+    fn format(&self) -> AudioFormat {
+        self.0.format()
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        assert!(!buffer.is_empty(), "Empty read.");
+        self.0.read(buffer)
+    }
+}
+
+#[test]
+fn filters_ignore_empty_reads() {
+    let mut low_pass = LowPass::new(RejectsEmptyReads(SineWave::new(1.0, 440.0)), 1000.0);
+    let mut high_pass = HighPass::new(RejectsEmptyReads(SineWave::new(1.0, 440.0)), 1000.0);
+    assert_eq!(low_pass.read(&mut []), ReadResult::good(0));
+    assert_eq!(high_pass.read(&mut []), ReadResult::good(0));
+
+    // The filters still work afterward.
+    let mut samples = vec![0.0; 256];
+    assert_eq!(low_pass.read(&mut samples), ReadResult::good(256));
+    assert_eq!(high_pass.read(&mut samples), ReadResult::good(256));
+
+    // A finished source's empty read still passes through as finished.
+    let finished = || VecSource::new(Vec::new(), AudioFormat::STEREO_CD);
+    let mut low_pass = LowPass::new(finished(), 1000.0);
+    let mut high_pass = HighPass::new(finished(), 1000.0);
+    assert_eq!(low_pass.read(&mut samples), ReadResult::finished(0));
+    assert_eq!(high_pass.read(&mut samples), ReadResult::finished(0));
+}
+
+#[test]
+fn adapt_plays_source_at_device_rate() {
+    // A 1 kHz tone at 44.1 kHz, played on a device that chose 48 kHz stereo.
+    let source = SineWave::with_format(AudioFormat::MONO_CD, 0.5, 1000.0).into_shared();
+    let mut output = PullOutput::new(AudioFormat::STEREO_DVD);
+    output.set_source(adapt(source, AudioFormat::STEREO_DVD).unwrap());
+
+    let mut samples = vec![0.0; 48000 * 2];
+    output.process(&mut samples);
+    assert_eq!(output.underruns(), 0);
+    assert!(samples.chunks_exact(2).all(|frame| frame[0] == frame[1]));
+
+    // One second of output still holds 1000 cycles.
+    let crossings = samples
+        .chunks_exact(2)
+        .zip(samples.chunks_exact(2).skip(1))
+        .filter(|(a, b)| a[0] < 0.0 && b[0] >= 0.0)
+        .count();
+    assert!((999..=1001).contains(&crossings), "{} cycles", crossings);
+}
+
+#[test]
+fn wet_only_echo_send_matches_echo_mix() {
+    let tone = || SineWave::with_format(AudioFormat::MONO_CD, 0.5, 440.0);
+    let delay = Duration::from_millis(50);
+
+    // The dry tone plus an echo send at 50%...
+    let mut send = Echo::new(tone(), delay, 0.5);
+    send.set_wet_only(true);
+    let mut mixer = BasicMixer::new();
+    mixer.add_source(tone().into_shared());
+    mixer.add_source(Gain::new(send, 0.5).into_shared());
+    let (mixed, _) = render_frames(mixer, 44100);
+
+    // ...sounds like a single echo at 50% wet.
+    let (single, _) = render_frames(Echo::with_mix(tone(), delay, 0.5, 0.5), 44100);
+    assert_eq!(mixed.len(), single.len());
+    assert!(mixed
+        .iter()
+        .zip(single.iter())
+        .all(|(a, b)| (a - b).abs() < 1e-5));
+}
+
+#[test]
+fn feedback_decays_to_zero_after_signal() {
+    let burst = || {
+        VecSource::new(
+            (0..5 * 44100)
+                .map(|i| if i < 4410 { 0.5 } else { 0.0 })
+                .collect(),
+            AudioFormat::MONO_CD,
+        )
+    };
+
+    // Without flushing denormals, these would hover just above zero forever.
+    let sources: Vec<Box<dyn AudioSource>> = vec![
+        Box::new(Echo::new(burst(), Duration::from_millis(1), 0.5)),
+        Box::new(LowPass::new(burst(), 1000.0)),
+        Box::new(HighPass::new(burst(), 1000.0)),
+    ];
+    for source in sources {
+        let (samples, _) = render(source);
+        assert!(samples[samples.len() - 4410..].iter().all(|&s| s == 0.0));
+    }
+}
+
+#[test]
+fn render_with_tail_captures_echo_repetitions() {
+    let mut impulse = vec![0.0; 2];
+    impulse[0] = 1.0;
+    let source = VecSource::new(impulse, AudioFormat::STEREO_CD);
+    let echo = Echo::new(source, Duration::from_secs_f64(441.0 / 44100.0), 0.5);
+    let tail = echo.tail_frames() as usize;
+    assert_eq!(tail, 10 * 441);
+
+    let (samples, _) = render_with_tail(echo);
+    assert_eq!(samples.len(), (1 + tail) * 2);
+
+    // Every repetition is there, each half as loud as the last, down to -60 dB.
+    assert_eq!(samples[samples.len() - 2], 0.5f32.powi(10));
+    for (i, frame) in samples.chunks_exact(2).enumerate() {
+        let expected = if i % 441 == 0 {
+            0.5f32.powi(i as i32 / 441)
+        } else {
+            0.0
+        };
+        assert_eq!(frame, [expected, 0.0], "frame {}", i);
+    }
+}
+
+#[test]
+#[should_panic(expected = "ReplayGain requires finite tracks")]
+fn replay_gain_refuses_an_endless_track() {
+    use timbre::ReplayGain;
+
+    // A low sample rate keeps the render up to the limit quick.
+    let format = AudioFormat {
+        channels: 1,
+        sample_rate: 1000,
+    };
+    ReplayGain::new(vec![SineWave::with_format(format, 1.0, 100.0)]);
+}
+
+#[test]
+fn render_with_tail_passes_tails_through_chains() {
+    use timbre::effects::{Allpass, Comb, Haas, Side};
+
+    let impulse = || VecSource::new(vec![1.0, 0.0], AudioFormat::MONO_CD);
+    let delay = Duration::from_secs_f64(100.0 / 44100.0);
+
+    // Sources without a tail render as they would without one.
+    assert_eq!(render_with_tail(impulse()), render(impulse()));
+
+    // An echo's tail survives the effects after it.
+    let echo = Gain::new(Echo::new(impulse(), delay, 0.5), 0.5);
+    assert_eq!(echo.tail_frames(), 1000);
+    let (samples, _) = render_with_tail(echo);
+    assert_eq!(samples.len(), 2 + 1000);
+    assert_eq!(samples[1000], 0.5 * 0.5f32.powi(10));
+
+    // Chained delays add their tails together.
+    let comb = Allpass::new(Comb::new(impulse(), delay, 0.5), delay, 0.5);
+    assert_eq!(comb.tail_frames(), 1000 + 1100);
+    let (samples, _) = render_with_tail(comb);
+    assert_eq!(samples.len(), 2 + 2100);
+    assert!(samples[2..].iter().any(|&sample| sample != 0.0));
+
+    let stereo = VecSource::new(vec![1.0, 1.0], AudioFormat::STEREO_CD);
+    let haas = Haas::new(stereo, delay, Side::Right);
+    assert_eq!(haas.tail_frames(), 100);
+    let (samples, _) = render_with_tail(haas);
+    assert_eq!(samples.len(), 2 * 101);
+    assert_eq!(samples[..2], [1.0, 0.0]);
+    assert_eq!(samples[200..], [0.0, 1.0]);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_mix_matches_serial() {
+    let mixer = |parallel| {
+        let mut mixer = BasicMixer::new();
+        mixer.set_parallel(parallel);
+        for i in 0..16 {
+            let tone = SineWave::with_format(AudioFormat::STEREO_CD, 0.1, 110.0 * (i + 1) as f32);
+            let filtered = LowPass::new(tone, 200.0 * (i + 1) as f32);
+            mixer.add_source(Echo::new(filtered, Duration::from_millis(10 + i), 0.5).into_shared());
+        }
+        mixer
+    };
+
+    let (serial, _) = render_frames(mixer(false), 44100);
+    let (parallel, _) = render_frames(mixer(true), 44100);
+    assert_eq!(serial.len(), parallel.len());
+    assert!(serial
+        .iter()
+        .zip(parallel.iter())
+        .all(|(a, b)| (a - b).abs() <= 1e-6));
+}
+
+#[test]
+fn pump_source_through_sinks() {
+    let chain = || {
+        let (samples, format) = render_frames(
+            SineWave::with_format(AudioFormat::STEREO_CD, 0.5, 440.0),
+            10000,
+        );
+        LowPass::new(VecSource::new(samples, format), 1000.0)
+    };
+    let (expected, _) = render(chain());
+
+    // Through an encoder and back.
+    let mut encoder = WavEncoder::new(Cursor::new(Vec::new()), AudioFormat::STEREO_CD).unwrap();
+    assert_eq!(pump(chain(), &mut encoder), 10000);
+    let bytes = encoder.finish().unwrap().into_inner();
+    let decoder = WavDecoder::from_bytes(&bytes).unwrap();
+    assert_eq!(decoder.format(), AudioFormat::STEREO_CD);
+    assert_eq!(render(decoder).0, expected);
+
+    // Through a channel to another thread.
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sink = ChannelSink::new(sender, AudioFormat::STEREO_CD);
+    let consumer =
+        std::thread::spawn(move || render(ChannelSource::new(receiver, AudioFormat::STEREO_CD)).0);
+    assert_eq!(pump(chain(), sink), 10000);
+    assert_eq!(consumer.join().unwrap(), expected);
+
+    // Pumping stops once the sink closes, even if the source never finishes.
+    let (sender, receiver) = std::sync::mpsc::channel::<Vec<Sample>>();
+    drop(receiver);
+    let sink = ChannelSink::new(sender, AudioFormat::STEREO_CD);
+    assert_eq!(pump(SineWave::new(0.5, 440.0), sink), 0);
+}
+
+/// Underruns without providing anything until `ready`, then provides `frames` frames of 0.5.
+struct Late {
+    ready: std::time::Instant,
+    frames: usize,
+    reads: usize,
+}
+
+impl AudioSource for Late {
+    fn format(&self) -> AudioFormat {
+        AudioFormat::MONO_CD
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        self.reads += 1;
+        if std::time::Instant::now() < self.ready {
+            return ReadResult::underrun(0);
+        }
+
+        let read = std::cmp::min(buffer.len(), self.frames);
+        buffer[..read].iter_mut().for_each(|s| *s = 0.5);
+        self.frames -= read;
+        if self.frames == 0 {
+            ReadResult::finished(read)
+        } else {
+            ReadResult::good(read)
+        }
+    }
+}
+
+#[test]
+fn render_and_pump_back_off_while_source_has_nothing() {
+    let late = || Late {
+        ready: std::time::Instant::now() + Duration::from_millis(20),
+        frames: 3000,
+        reads: 0,
+    };
+
+    // Rather than spinning on empty reads, each waits for the source to catch up.
+    let mut source = late();
+    let (samples, _) = render_frames(&mut source, 10000);
+    assert_eq!(samples, vec![0.5; 3000]);
+    assert!(source.reads < 100, "{} reads", source.reads);
+
+    let mut source = late();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    assert_eq!(
+        pump(&mut source, ChannelSink::new(sender, AudioFormat::MONO_CD)),
+        3000
+    );
+    assert_eq!(receiver.iter().flatten().count(), 3000);
+    assert!(source.reads < 100, "{} reads", source.reads);
+}
+
+#[test]
+fn synth_voice_plays_note_and_releases() {
+    let adsr = Adsr {
+        attack: Duration::from_millis(5),
+        decay: Duration::from_millis(5),
+        sustain: 0.5,
+        release: Duration::from_millis(100),
+    };
+    let sine = SineWave::with_format(AudioFormat::MONO_CD, 1.0, 1000.0);
+    let mut voice = SynthVoice::new(sine, adsr);
+    let read = |voice: &mut SynthVoice<SineWave>, frames| {
+        let mut samples = vec![0.0; frames];
+        voice.read(&mut samples);
+        samples
+    };
+    let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+    voice.note_on(69, 127);
+    let held = read(&mut voice, 44100);
+
+    // Once sustaining, it's a 440 Hz tone at the sustain level.
+    let sustained = &held[4410..];
+    let crossings = sustained
+        .windows(2)
+        .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+        .count();
+    assert!(
+        (crossings as f32 - 440.0 * 0.9).abs() <= 1.0,
+        "{}",
+        crossings
+    );
+    assert!((peak(sustained) - 0.5).abs() < 0.01);
+
+    // It fades out linearly over the 100ms release.
+    voice.note_off();
+    let released = read(&mut voice, 4410);
+    assert!((peak(&released[2205 - 50..2205 + 50]) - 0.25).abs() < 0.01);
+    assert!(peak(&released[4400..]) < 0.01);
+    assert!(!voice.is_active());
+    assert_eq!(voice.note(), None);
+    assert!(read(&mut voice, 1000).iter().all(|&s| s == 0.0));
+}
+
+/// A constant signal, so that a voice's output is exactly its gain.
+struct Dc(AudioFormat);
+
+impl AudioSource for Dc {
+    fn format(&self) -> AudioFormat {
+        self.0
+    }
+
+    fn read(&mut self, buffer: &mut [Sample]) -> ReadResult {
+        buffer.iter_mut().for_each(|s| *s = 1.0);
+        ReadResult::good(buffer.len())
+    }
+}
+
+impl Oscillator for Dc {
+    fn set_frequency(&mut self, _frequency: f32) {}
+}
+
+#[test]
+fn synth_voice_retriggers_at_new_velocity_without_jumps() {
+    let adsr = Adsr {
+        attack: Duration::from_millis(5),
+        decay: Duration::from_millis(5),
+        sustain: 0.5,
+        release: Duration::from_millis(100),
+    };
+    let mut voice = SynthVoice::new(Dc(AudioFormat::MONO_CD), adsr);
+    let read = |voice: &mut SynthVoice<Dc>, frames| {
+        let mut samples = vec![0.0; frames];
+        voice.read(&mut samples);
+        samples
+    };
+    let mut gain = Vec::new();
+
+    // Retrigger while sustaining, much softer, then louder during the release.
+    voice.note_on(69, 127);
+    gain.extend(read(&mut voice, 4410));
+    voice.note_on(81, 32);
+    assert_eq!(voice.note(), Some(81));
+    gain.extend(read(&mut voice, 4410));
+    assert!((gain[gain.len() - 1] - 0.5 * 32.0 / 127.0).abs() < 1e-6);
+    voice.note_off();
+    gain.extend(read(&mut voice, 2205));
+    voice.note_on(69, 127);
+    gain.extend(read(&mut voice, 4410));
+    assert!((gain[gain.len() - 1] - 0.5).abs() < 1e-6);
+
+    // The attack takes 220 frames to rise from silence to full volume, so no
+    // frame-to-frame change should be much faster than that.
+    let steepest = gain
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).abs())
+        .fold(0.0f32, f32::max);
+    assert!(steepest < 2.0 / 220.0, "{}", steepest);
+}
+
+#[test]
+fn ring_buffer_passes_every_sample_once_between_threads() {
+    // Each frame holds its index in both channels, which f32 stores exactly.
+    const FRAMES: usize = 50_000;
+    let (mut producer, mut source) = ring_buffer(64, AudioFormat::STEREO_CD);
+    let (release, held_back) = std::sync::mpsc::channel();
+
+    let writer = std::thread::spawn(move || {
+        let samples: Vec<Sample> = (0..FRAMES).flat_map(|i| [i as f32, i as f32]).collect();
+        let mut push_all = |samples: &[Sample]| {
+            let mut written = 0;
+            for size in (1..100).cycle() {
+                if written == samples.len() {
+                    break;
+                }
+                let end = std::cmp::min(written + size * 2, samples.len());
+                written += producer.push(&samples[written..end]);
+                if producer.free() == 0 {
+                    std::thread::yield_now();
+                }
+            }
+        };
+
+        // The second half waits until the reader has run out, so it underruns.
+        let (first, second) = samples.split_at(samples.len() / 2);
+        push_all(first);
+        held_back.recv().unwrap();
+        push_all(second);
+    });
+
+    let mut received = Vec::with_capacity(FRAMES * 2);
+    let mut underruns = 0;
+    for size in (1..77).cycle() {
+        let mut buffer = vec![0.0; size * 2];
+        let result = source.read(&mut buffer);
+        assert_eq!(result.read % 2, 0);
+        received.extend_from_slice(&buffer[..result.read]);
+        match result.state {
+            StreamState::Good => {}
+            StreamState::Underrun => {
+                underruns += 1;
+                let _ = release.send(());
+                std::thread::yield_now();
+            }
+            StreamState::Finished => break,
+        }
+    }
+    writer.join().unwrap();
+
+    assert!(underruns > 0);
+    assert_eq!(received.len(), FRAMES * 2);
+    for (i, frame) in received.chunks_exact(2).enumerate() {
+        assert_eq!(frame, [i as f32, i as f32]);
+    }
+    assert_eq!(source.latency_frames(), 0);
+}
+
+#[cfg(feature = "flac")]
+#[test]
+fn flac_decodes_like_the_equivalent_wav() {
+    use timbre::decoders::FlacDecoder;
+
+    for (flac, wav) in [
+        ("./assets/mono-i16.flac", "./assets/tagged-mono-i16.wav"),
+        (
+            "./assets/tagged-mono-i16.flac",
+            "./assets/tagged-mono-i16.wav",
+        ),
+        ("./assets/stereo-i24.flac", "./assets/stereo-i24.wav"),
+    ] {
+        let flac = FlacDecoder::from_file(flac).unwrap();
+        let wav = WavDecoder::from_file(wav).unwrap();
+        assert_eq!(flac.format(), wav.format());
+        assert_eq!(render(flac), render(wav));
+    }
+}
+
+#[cfg(feature = "ogg")]
+#[test]
+fn ogg_decodes_close_to_the_equivalent_wav() {
+    use timbre::decoders::OggDecoder;
+
+    let (ogg, ogg_format) = render(OggDecoder::from_file("./assets/stereo-i24.ogg").unwrap());
+    let (wav, wav_format) = render(WavDecoder::from_file("./assets/stereo-i24.wav").unwrap());
+    assert_eq!(ogg_format, wav_format);
+
+    // Vorbis is lossy, and the last block decodes in full, past the end of the track.
+    assert!(ogg.len() >= wav.len());
+    for (decoded, original) in ogg.iter().zip(&wav) {
+        assert!((decoded - original).abs() < 0.005);
+    }
+    assert!(ogg.iter().any(|&sample| sample.abs() > 0.4));
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn mp3_decodes_close_to_the_equivalent_wav() {
+    use timbre::decoders::Mp3Decoder;
+
+    let (mp3, mp3_format) = render(Mp3Decoder::from_file("./assets/mono-i16.mp3").unwrap());
+    let (wav, wav_format) = render(WavDecoder::from_file("./assets/tagged-mono-i16.wav").unwrap());
+    assert_eq!(mp3_format, wav_format);
+
+    // MP3 is lossy, and the encoder's delay and padding are trimmed, so the
+    // samples line up with the original.
+    assert_eq!(mp3.len(), wav.len());
+    for (decoded, original) in mp3.iter().zip(&wav) {
+        assert!((decoded - original).abs() < 0.001);
+    }
+    assert!(mp3.iter().any(|&sample| sample.abs() > 0.4));
+}
+
+#[test]
+fn streaming_wav_decoder_matches_loaded_file() {
+    use timbre::{generators::Ramp, Rewind, Seek};
+
+    // Long enough to span several of the streaming decoder's windows.
+    let format = AudioFormat::STEREO_CD;
+    // Unique to this process, so concurrent test runs don't share the file.
+    let name = format!("timbre-streaming-wav-decoder-{}.wav", std::process::id());
+    let path = std::env::temp_dir().join(name);
+    let path = path.to_str().unwrap();
+    let ramp = Ramp::finite(-1.0, 1.0, Duration::from_secs(1), format);
+    pump(ramp, WavEncoder::create(path, format).unwrap());
+
+    let mut loaded = WavDecoder::from_file(path).unwrap();
+    let mut streamed = WavDecoder::streaming(path).unwrap();
+    assert_eq!(streamed.format(), loaded.format());
+
+    let mut read = |frames: usize| {
+        let size = format.frames_to_samples(frames);
+        let (mut expected, mut actual) = (vec![0.0; size], vec![0.0; size]);
+        let result = streamed.read(&mut actual);
+        assert_eq!(result, loaded.read(&mut expected));
+        assert_eq!(actual[..result.read], expected[..result.read]);
+        result
+    };
+    // Odd sizes cross window boundaries at different points.
+    for frames in (1..5000).step_by(997).cycle() {
+        if read(frames).state == StreamState::Finished {
+            break;
+        }
+    }
+    assert_eq!(read(5), ReadResult::finished(0));
+
+    assert_eq!(streamed.seek_frame(30000), 30000);
+    loaded.seek_frame(30000);
+    assert_eq!(streamed.peek(4), loaded.peek(4));
+    assert_eq!(streamed.advance(20000), 14100);
+    assert!(streamed.peek(1).is_empty());
+
+    assert!(streamed.rewind());
+    loaded.rewind();
+    assert_eq!(render(streamed), render(loaded));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn looping_fills_across_loop_points() {
+    use timbre::effects::Looping;
+
+    let clip = || WavDecoder::from_file("./assets/tagged-mono-i16.wav").unwrap();
+    let (once, _) = render(clip());
+    let mut looping = Looping::new(clip());
+
+    // Reads of an awkward size never line up with the end of the clip.
+    let mut played = Vec::new();
+    let mut buffer = vec![0.0; 1000];
+    while played.len() < once.len() * 3 {
+        assert_eq!(looping.read(&mut buffer), ReadResult::good(1000));
+        played.extend_from_slice(&buffer);
+    }
+    for (i, sample) in played.iter().enumerate() {
+        assert_eq!(*sample, once[i % once.len()]);
+    }
+    assert_eq!(looping.loops(), 3);
+
+    // An empty source finishes instead of looping forever.
+    let mut empty = Looping::new(VecSource::new(Vec::new(), AudioFormat::MONO_CD));
+    assert_eq!(empty.read(&mut buffer), ReadResult::finished(0));
+}
+
+#[test]
+fn limiter_catches_transients_and_flushes_its_lookahead() {
+    use timbre::effects::Limiter;
+
+    // A quiet stereo tone with a sudden spike well over full scale.
+    let mut samples: Vec<f32> = (0..20000)
+        .map(|i| 0.5 * (i as f32 / 2.0 * 0.06).sin())
+        .collect();
+    samples[10000] = 4.0;
+    samples[10001] = -3.0;
+    let source = VecSource::new(samples.clone(), AudioFormat::STEREO_CD);
+    let limiter = Limiter::new(source, 0.9);
+    let latency = 2 * limiter.latency_frames() as usize;
+
+    let (limited, _) = render(limiter);
+    assert_eq!(limited.len(), samples.len() + latency);
+    assert!(limited.iter().all(|sample| sample.abs() <= 0.9));
+
+    // Well before the spike, nothing is touched; just after it, the gain
+    // comes down smoothly rather than jumping.
+    assert_eq!(limited[latency..latency + 9000], samples[..9000]);
+    let at_spike = latency + 10000;
+    assert!((limited[at_spike] - 0.9).abs() < 1e-4);
+    let left: Vec<f32> = limited[at_spike - 1000..at_spike]
+        .iter()
+        .step_by(2)
+        .cloned()
+        .collect();
+    assert!(left.windows(2).all(|pair| (pair[1] - pair[0]).abs() < 0.04));
+    assert!(left[499].abs() < 0.5 * samples[9998].abs());
+}
+
+#[test]
+fn low_pass_attenuates_mono_and_stereo_alike() {
+    let peak = |format: AudioFormat| {
+        let sin = SineWave::with_format(format, 1.0, 8000.0);
+        let mut low_pass = LowPass::new(sin, 500.0);
+        let mut samples = vec![0.0; format.channels as usize * 44100];
+        low_pass.read(&mut samples);
+        samples[samples.len() / 2..]
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()))
+    };
+
+    // A one-pole filter passes 8 kHz at about 500 / 8000 of its amplitude.
+    let mono = peak(AudioFormat::MONO_CD);
+    let stereo = peak(AudioFormat::STEREO_CD);
+    assert!(mono < 0.1);
+    assert!((mono - stereo).abs() < 1e-4);
+}